@@ -0,0 +1,3 @@
+-- Composite indexes backing keyset pagination on GET /expenses
+CREATE INDEX idx_expenses_user_date_id ON expenses(user_id, expense_date, id);
+CREATE INDEX idx_expenses_user_amount_id ON expenses(user_id, amount, id);
@@ -0,0 +1,43 @@
+CREATE TABLE categories (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(50) NOT NULL,
+    color VARCHAR(7) NOT NULL DEFAULT '#6c757d',
+    icon VARCHAR(50),
+    -- The fallback category receives the expenses of deleted categories
+    is_default BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    UNIQUE (user_id, name)
+);
+
+CREATE UNIQUE INDEX idx_categories_one_default ON categories(user_id) WHERE is_default;
+
+-- Seeds the seven historical categories for a user; 'Others' is the fallback
+CREATE FUNCTION seed_default_categories(p_user_id INTEGER) RETURNS VOID AS $$
+    INSERT INTO categories (user_id, name, color, icon, is_default)
+    VALUES
+        (p_user_id, 'Groceries', '#198754', 'bi-basket', FALSE),
+        (p_user_id, 'Leisure', '#0d6efd', 'bi-controller', FALSE),
+        (p_user_id, 'Electronics', '#0dcaf0', 'bi-laptop', FALSE),
+        (p_user_id, 'Utilities', '#ffc107', 'bi-lightning', FALSE),
+        (p_user_id, 'Clothing', '#6c757d', 'bi-bag', FALSE),
+        (p_user_id, 'Health', '#dc3545', 'bi-heart-pulse', FALSE),
+        (p_user_id, 'Others', '#212529', 'bi-three-dots', TRUE)
+    ON CONFLICT (user_id, name) DO NOTHING;
+$$ LANGUAGE SQL;
+
+SELECT seed_default_categories(id) FROM users;
+
+-- Move expenses from the enum to the per-user categories
+ALTER TABLE expenses ADD COLUMN category_id INTEGER REFERENCES categories(id);
+
+UPDATE expenses e
+SET category_id = c.id
+FROM categories c
+WHERE c.user_id = e.user_id AND c.name = e.category::TEXT;
+
+ALTER TABLE expenses ALTER COLUMN category_id SET NOT NULL;
+ALTER TABLE expenses DROP COLUMN category;
+DROP TYPE expense_category;
+
+CREATE INDEX idx_expenses_user_category ON expenses(user_id, category_id);
@@ -0,0 +1,32 @@
+CREATE TYPE recurrence_frequency AS ENUM (
+    'Daily',
+    'Weekly',
+    'Monthly',
+    'Yearly'
+);
+
+CREATE TABLE recurring_expenses (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    category_id INTEGER NOT NULL REFERENCES categories(id),
+    amount NUMERIC(10, 2) NOT NULL,
+    description TEXT,
+    frequency recurrence_frequency NOT NULL,
+    -- Only used by monthly rules; clamped to the length of short months
+    day_of_month SMALLINT CHECK (day_of_month BETWEEN 1 AND 31),
+    start_date DATE NOT NULL,
+    end_date DATE,
+    next_occurrence DATE NOT NULL,
+    paused BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX idx_recurring_expenses_user_id ON recurring_expenses(user_id);
+CREATE INDEX idx_recurring_expenses_due ON recurring_expenses(next_occurrence) WHERE NOT paused;
+
+-- Materialized occurrences keep a link to their template
+ALTER TABLE expenses
+    ADD COLUMN recurring_expense_id INTEGER REFERENCES recurring_expenses(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_expenses_recurring_expense ON expenses(recurring_expense_id, expense_date)
+    WHERE recurring_expense_id IS NOT NULL;
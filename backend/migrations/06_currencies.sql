@@ -0,0 +1,40 @@
+-- Rates are quoted against the euro, as in the ECB reference files:
+-- 1 EUR = rate units of currency
+CREATE TABLE exchange_rates (
+    currency CHAR(3) NOT NULL,
+    rate_date DATE NOT NULL,
+    rate NUMERIC(18, 8) NOT NULL CHECK (rate > 0),
+    PRIMARY KEY (currency, rate_date)
+);
+
+ALTER TABLE users ADD COLUMN base_currency CHAR(3) NOT NULL DEFAULT 'EUR';
+ALTER TABLE expenses ADD COLUMN currency CHAR(3) NOT NULL DEFAULT 'EUR';
+ALTER TABLE recurring_expenses ADD COLUMN currency CHAR(3) NOT NULL DEFAULT 'EUR';
+
+-- Latest known rate on or before the date (no rates on weekends and holidays)
+CREATE FUNCTION exchange_rate(p_currency CHAR(3), p_date DATE) RETURNS NUMERIC AS $$
+    SELECT CASE
+        WHEN p_currency = 'EUR' THEN 1
+        ELSE (
+            SELECT rate FROM exchange_rates
+            WHERE currency = p_currency AND rate_date <= p_date
+            ORDER BY rate_date DESC
+            LIMIT 1
+        )
+    END;
+$$ LANGUAGE SQL STABLE;
+
+-- NULL when a rate is missing
+CREATE FUNCTION convert_amount(p_amount NUMERIC, p_from CHAR(3), p_to CHAR(3), p_date DATE)
+RETURNS NUMERIC AS $$
+    SELECT CASE
+        WHEN p_from = p_to THEN p_amount
+        ELSE ROUND(p_amount / exchange_rate(p_from, p_date) * exchange_rate(p_to, p_date), 2)
+    END;
+$$ LANGUAGE SQL STABLE;
+
+CREATE FUNCTION convert_to_base(p_amount NUMERIC, p_currency CHAR(3), p_user_id INTEGER, p_date DATE)
+RETURNS NUMERIC AS $$
+    SELECT convert_amount(p_amount, p_currency, base_currency, p_date)
+    FROM users WHERE id = p_user_id;
+$$ LANGUAGE SQL STABLE;
@@ -0,0 +1,13 @@
+CREATE TABLE incomes (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    source VARCHAR(100) NOT NULL,
+    amount NUMERIC(10, 2) NOT NULL,
+    currency CHAR(3) NOT NULL DEFAULT 'EUR',
+    income_date DATE NOT NULL DEFAULT CURRENT_DATE,
+    -- Informational: salaries, pensions and other regular income
+    recurring BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX idx_incomes_user_date ON incomes(user_id, income_date);
@@ -0,0 +1,24 @@
+CREATE TABLE tags (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Stored trimmed and lowercased
+    name VARCHAR(50) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    UNIQUE (user_id, name)
+);
+
+CREATE TABLE expense_tags (
+    expense_id INTEGER NOT NULL REFERENCES expenses(id) ON DELETE CASCADE,
+    tag_id INTEGER NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    PRIMARY KEY (expense_id, tag_id)
+);
+
+CREATE INDEX idx_expense_tags_tag_id ON expense_tags(tag_id);
+
+-- Sorted names, empty when the expense has no tag
+CREATE FUNCTION expense_tag_names(p_expense_id INTEGER) RETURNS TEXT[] AS $$
+    SELECT COALESCE(array_agg(t.name::TEXT ORDER BY t.name), '{}')
+    FROM expense_tags et
+    JOIN tags t ON t.id = et.tag_id
+    WHERE et.expense_id = p_expense_id;
+$$ LANGUAGE SQL STABLE;
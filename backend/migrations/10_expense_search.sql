@@ -0,0 +1,33 @@
+CREATE EXTENSION IF NOT EXISTS unaccent;
+
+-- Stemming configurations that also ignore accents
+CREATE TEXT SEARCH CONFIGURATION french_unaccent (COPY = french);
+ALTER TEXT SEARCH CONFIGURATION french_unaccent
+    ALTER MAPPING FOR hword, hword_part, word WITH unaccent, french_stem;
+
+CREATE TEXT SEARCH CONFIGURATION english_unaccent (COPY = english);
+ALTER TEXT SEARCH CONFIGURATION english_unaccent
+    ALTER MAPPING FOR hword, hword_part, word WITH unaccent, english_stem;
+
+-- Descriptions are indexed with both stemmings, queries match either
+ALTER TABLE expenses ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS (
+    to_tsvector('french_unaccent'::REGCONFIG, COALESCE(description, ''))
+    || to_tsvector('english_unaccent'::REGCONFIG, COALESCE(description, ''))
+) STORED;
+
+CREATE INDEX idx_expenses_search_vector ON expenses USING GIN (search_vector);
+
+CREATE FUNCTION expense_search_query(p_query TEXT) RETURNS TSQUERY AS $$
+    SELECT websearch_to_tsquery('french_unaccent'::REGCONFIG, p_query)
+        || websearch_to_tsquery('english_unaccent'::REGCONFIG, p_query);
+$$ LANGUAGE SQL IMMUTABLE;
+
+-- HTML-escaped description with the matched words wrapped in <mark>
+CREATE FUNCTION expense_headline(p_description TEXT, p_query TEXT) RETURNS TEXT AS $$
+    SELECT ts_headline(
+        'french_unaccent'::REGCONFIG,
+        replace(replace(replace(p_description, '&', '&amp;'), '<', '&lt;'), '>', '&gt;'),
+        expense_search_query(p_query),
+        'HighlightAll=true, StartSel=<mark>, StopSel=</mark>'
+    );
+$$ LANGUAGE SQL STABLE;
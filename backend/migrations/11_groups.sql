@@ -0,0 +1,55 @@
+CREATE TABLE groups (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    -- Split expenses and settlements are in this currency
+    currency CHAR(3) NOT NULL DEFAULT 'EUR',
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE TABLE group_members (
+    group_id INTEGER NOT NULL REFERENCES groups(id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    joined_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (group_id, user_id)
+);
+
+CREATE INDEX idx_group_members_user_id ON group_members(user_id);
+
+-- Pending invitations; removed once accepted or declined
+CREATE TABLE group_invitations (
+    id SERIAL PRIMARY KEY,
+    group_id INTEGER NOT NULL REFERENCES groups(id) ON DELETE CASCADE,
+    email VARCHAR(50) NOT NULL,
+    invited_by INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    UNIQUE (group_id, email)
+);
+
+CREATE INDEX idx_group_invitations_email ON group_invitations(email);
+
+-- A split expense stays owned by the member who paid it
+ALTER TABLE expenses ADD COLUMN group_id INTEGER REFERENCES groups(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_expenses_group_id ON expenses(group_id);
+
+CREATE TABLE expense_splits (
+    expense_id INTEGER NOT NULL REFERENCES expenses(id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    amount NUMERIC(10, 2) NOT NULL CHECK (amount >= 0),
+    PRIMARY KEY (expense_id, user_id)
+);
+
+-- Payments between members that settle their balances
+CREATE TABLE settlements (
+    id SERIAL PRIMARY KEY,
+    group_id INTEGER NOT NULL REFERENCES groups(id) ON DELETE CASCADE,
+    from_user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    to_user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    amount NUMERIC(10, 2) NOT NULL CHECK (amount > 0),
+    note VARCHAR(255),
+    settled_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    CHECK (from_user_id <> to_user_id)
+);
+
+CREATE INDEX idx_settlements_group_id ON settlements(group_id);
@@ -0,0 +1,4 @@
+-- Deleted expenses stay in the trash until restored or purged
+ALTER TABLE expenses ADD COLUMN deleted_at TIMESTAMP;
+
+CREATE INDEX idx_expenses_trash ON expenses(user_id, deleted_at) WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,25 @@
+-- One row per change made to an expense, with the values before and after it
+CREATE TABLE expense_history (
+    id SERIAL PRIMARY KEY,
+    expense_id INTEGER NOT NULL REFERENCES expenses(id) ON DELETE CASCADE,
+    actor_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    action VARCHAR(10) NOT NULL CHECK (action IN ('create', 'update', 'delete', 'restore', 'revert')),
+    before JSONB,
+    after JSONB,
+    changed_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX idx_expense_history_expense_id ON expense_history(expense_id, id);
+
+-- Recorded values of an expense; amount as text to keep its exact decimals
+CREATE FUNCTION expense_snapshot(p_expense_id INTEGER) RETURNS JSONB AS $$
+    SELECT jsonb_build_object(
+        'category_id', category_id,
+        'amount', amount::TEXT,
+        'currency', currency,
+        'description', description,
+        'expense_date', expense_date,
+        'tags', to_jsonb(expense_tag_names(id))
+    )
+    FROM expenses WHERE id = p_expense_id;
+$$ LANGUAGE sql STABLE;
@@ -0,0 +1,2 @@
+-- Bumped on every change, sent as the ETag of the expense
+ALTER TABLE expenses ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
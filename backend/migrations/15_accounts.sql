@@ -0,0 +1,82 @@
+CREATE TYPE account_type AS ENUM (
+    'Cash',
+    'DebitCard',
+    'CreditCard'
+);
+
+-- Where money is spent from; a credit card's negative balance is what is owed
+CREATE TABLE accounts (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    account_type account_type NOT NULL,
+    opening_balance NUMERIC(12, 2) NOT NULL DEFAULT 0,
+    -- Expenses and incomes of the account must be in this currency
+    currency CHAR(3) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    UNIQUE (user_id, name)
+);
+
+-- Money moved between two accounts of the same user; `to_amount` is what
+-- the destination received, in its own currency
+CREATE TABLE transfers (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    from_account_id INTEGER NOT NULL REFERENCES accounts(id),
+    to_account_id INTEGER NOT NULL REFERENCES accounts(id),
+    amount NUMERIC(12, 2) NOT NULL CHECK (amount > 0),
+    to_amount NUMERIC(12, 2) NOT NULL CHECK (to_amount > 0),
+    description VARCHAR(255),
+    transfer_date DATE NOT NULL DEFAULT CURRENT_DATE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    CHECK (from_account_id <> to_account_id)
+);
+
+CREATE INDEX idx_transfers_from_account ON transfers(from_account_id, transfer_date);
+CREATE INDEX idx_transfers_to_account ON transfers(to_account_id, transfer_date);
+
+ALTER TABLE expenses ADD COLUMN account_id INTEGER REFERENCES accounts(id) ON DELETE SET NULL;
+ALTER TABLE incomes ADD COLUMN account_id INTEGER REFERENCES accounts(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_expenses_account ON expenses(account_id, expense_date) WHERE account_id IS NOT NULL;
+CREATE INDEX idx_incomes_account ON incomes(account_id, income_date) WHERE account_id IS NOT NULL;
+
+-- Money in and out of an account per day, trashed expenses excluded
+CREATE FUNCTION account_movements(p_account_id INTEGER)
+RETURNS TABLE (day DATE, inflow NUMERIC, outflow NUMERIC) AS $$
+    SELECT day, SUM(inflow), SUM(outflow)
+    FROM (
+        SELECT expense_date::DATE AS day, 0 AS inflow, amount AS outflow
+        FROM expenses WHERE account_id = p_account_id AND deleted_at IS NULL
+        UNION ALL
+        SELECT income_date, amount, 0 FROM incomes WHERE account_id = p_account_id
+        UNION ALL
+        SELECT transfer_date, to_amount, 0 FROM transfers WHERE to_account_id = p_account_id
+        UNION ALL
+        SELECT transfer_date, 0, amount FROM transfers WHERE from_account_id = p_account_id
+    ) m
+    GROUP BY day;
+$$ LANGUAGE sql STABLE;
+
+-- Balance at the end of `p_until`, or after every movement when NULL
+CREATE FUNCTION account_balance(p_account_id INTEGER, p_until DATE) RETURNS NUMERIC AS $$
+    SELECT a.opening_balance + COALESCE(SUM(m.inflow - m.outflow), 0)
+    FROM accounts a
+    LEFT JOIN account_movements(a.id) m ON p_until IS NULL OR m.day <= p_until
+    WHERE a.id = p_account_id
+    GROUP BY a.opening_balance;
+$$ LANGUAGE sql STABLE;
+
+-- The account is part of the recorded values of an expense
+CREATE OR REPLACE FUNCTION expense_snapshot(p_expense_id INTEGER) RETURNS JSONB AS $$
+    SELECT jsonb_build_object(
+        'category_id', category_id,
+        'amount', amount::TEXT,
+        'currency', currency,
+        'description', description,
+        'expense_date', expense_date,
+        'account_id', account_id,
+        'tags', to_jsonb(expense_tag_names(id))
+    )
+    FROM expenses WHERE id = p_expense_id;
+$$ LANGUAGE sql STABLE;
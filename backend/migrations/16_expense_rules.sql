@@ -0,0 +1,18 @@
+-- Auto-categorization: every condition set must match, lower priority runs first
+CREATE TABLE expense_rules (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    priority INTEGER NOT NULL,
+    description_contains VARCHAR(255),
+    description_regex VARCHAR(255),
+    min_amount NUMERIC(10, 2),
+    max_amount NUMERIC(10, 2),
+    -- Actions: the category to set and the tags to add
+    category_id INTEGER REFERENCES categories(id),
+    add_tags TEXT[] NOT NULL DEFAULT '{}',
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX idx_expense_rules_user_priority ON expense_rules(user_id, priority, id);
@@ -0,0 +1,24 @@
+-- Savings goals: an amount to put aside by a deadline
+CREATE TABLE goals (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    target_amount NUMERIC(12, 2) NOT NULL CHECK (target_amount > 0),
+    -- Contributions are in this currency
+    currency CHAR(3) NOT NULL,
+    deadline DATE NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    UNIQUE (user_id, name)
+);
+
+-- Money put aside for a goal; a negative amount is taken back out
+CREATE TABLE goal_contributions (
+    id SERIAL PRIMARY KEY,
+    goal_id INTEGER NOT NULL REFERENCES goals(id) ON DELETE CASCADE,
+    amount NUMERIC(12, 2) NOT NULL CHECK (amount <> 0),
+    contribution_date DATE NOT NULL DEFAULT CURRENT_DATE,
+    note VARCHAR(255),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX idx_goal_contributions_goal_date ON goal_contributions(goal_id, contribution_date);
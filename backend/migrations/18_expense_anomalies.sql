@@ -0,0 +1,24 @@
+CREATE TYPE anomaly_status AS ENUM (
+    'Pending',
+    'Confirmed',
+    'Dismissed'
+);
+
+-- New expenses whose amount stood out from the history of their category.
+-- Confirmed anomalies are left out of that history; dismissed ones make the
+-- category less sensitive.
+CREATE TABLE expense_anomalies (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expense_id INTEGER NOT NULL UNIQUE REFERENCES expenses(id) ON DELETE CASCADE,
+    -- Modified z-score of the amount, and the median and threshold it was
+    -- compared with, in the base currency
+    score NUMERIC(10, 2) NOT NULL,
+    median NUMERIC(12, 2) NOT NULL,
+    threshold NUMERIC(6, 2) NOT NULL,
+    status anomaly_status NOT NULL DEFAULT 'Pending',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    resolved_at TIMESTAMP
+);
+
+CREATE INDEX idx_expense_anomalies_user_status ON expense_anomalies(user_id, status, created_at);
@@ -0,0 +1,15 @@
+CREATE TYPE digest_frequency AS ENUM (
+    'Weekly',
+    'Monthly'
+);
+
+-- Users who opted in to the spending digest; opting out deletes the row
+CREATE TABLE digest_subscriptions (
+    user_id INTEGER PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    frequency digest_frequency NOT NULL,
+    -- Start of the last period a digest was sent for, so each is sent once
+    last_period_start DATE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX idx_digest_subscriptions_frequency ON digest_subscriptions(frequency, last_period_start);
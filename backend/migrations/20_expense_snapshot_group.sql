@@ -0,0 +1,15 @@
+-- The group an expense is split in is part of its recorded values, so that
+-- attaching it to a group or detaching it shows in its history
+CREATE OR REPLACE FUNCTION expense_snapshot(p_expense_id INTEGER) RETURNS JSONB AS $$
+    SELECT jsonb_build_object(
+        'category_id', category_id,
+        'amount', amount::TEXT,
+        'currency', currency,
+        'description', description,
+        'expense_date', expense_date,
+        'account_id', account_id,
+        'group_id', group_id,
+        'tags', to_jsonb(expense_tag_names(id))
+    )
+    FROM expenses WHERE id = p_expense_id;
+$$ LANGUAGE sql STABLE;
@@ -0,0 +1,243 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get, put},
+};
+
+use crate::{
+    auth::token::claims::Claims,
+    currency::handler::ensure_currency,
+    error::AppError,
+    state::AppState,
+    validation::{ValidatedJson, ValidatedQuery},
+};
+
+use super::{
+    models::{Account, AccountBalance, Transfer},
+    utils::{
+        BalanceQuery, CreateAccountPayload, CreateTransferPayload, TransferListQuery,
+        UpdateAccountPayload,
+    },
+};
+
+pub async fn get_accounts(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Vec<Account>>), AppError> {
+    let accounts = state.account_repository.find_accounts(claims.sub).await?;
+    Ok((StatusCode::OK, Json(accounts)))
+}
+
+pub async fn create_account(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(payload): ValidatedJson<CreateAccountPayload>,
+) -> Result<(StatusCode, Json<Account>), AppError> {
+    if let Some(currency) = &payload.currency {
+        ensure_currency(&state, currency).await?;
+    }
+    if state
+        .account_repository
+        .exists_by_name(claims.sub, &payload.name, None)
+        .await?
+    {
+        return Err(AppError::Conflict("Account already exists".into()));
+    }
+
+    let account = state
+        .account_repository
+        .create_account(payload, claims.sub)
+        .await?;
+    Ok((StatusCode::CREATED, Json(account)))
+}
+
+pub async fn update_account(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    ValidatedJson(payload): ValidatedJson<UpdateAccountPayload>,
+) -> Result<(StatusCode, Json<Account>), AppError> {
+    let name_taken = match &payload.name {
+        Some(name) => {
+            state
+                .account_repository
+                .exists_by_name(claims.sub, name, Some(id))
+                .await?
+        }
+        None => false,
+    };
+    if name_taken {
+        return Err(AppError::Conflict("Account already exists".into()));
+    }
+
+    let account = state
+        .account_repository
+        .update_account(id, claims.sub, payload)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Account not found".into()))?;
+    Ok((StatusCode::OK, Json(account)))
+}
+
+pub async fn delete_account(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    if state
+        .account_repository
+        .find_account(id, claims.sub)
+        .await?
+        .is_none()
+    {
+        return Ok(StatusCode::NOT_FOUND);
+    }
+    // Deleting the transfers would silently change the other account's balance
+    if state.account_repository.has_transfers(id).await? {
+        return Err(AppError::Conflict(
+            "Delete the transfers of the account first".into(),
+        ));
+    }
+    match state
+        .account_repository
+        .delete_account(id, claims.sub)
+        .await?
+    {
+        Some(_) => Ok(StatusCode::NO_CONTENT),
+        None => Ok(StatusCode::NOT_FOUND),
+    }
+}
+
+/// Running balance of the account, one entry per day with movements.
+pub async fn get_balance(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    ValidatedQuery(query): ValidatedQuery<BalanceQuery>,
+) -> Result<(StatusCode, Json<AccountBalance>), AppError> {
+    let account = state
+        .account_repository
+        .find_account(id, claims.sub)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Account not found".into()))?;
+
+    let start_balance = state
+        .account_repository
+        .find_start_balance(id, query.from)
+        .await?;
+    let days = state
+        .account_repository
+        .find_balance_days(id, query.from, query.to)
+        .await?;
+    let end_balance = days
+        .last()
+        .map_or_else(|| start_balance.clone(), |day| day.balance.clone());
+
+    Ok((
+        StatusCode::OK,
+        Json(AccountBalance {
+            account_id: account.id,
+            currency: account.currency,
+            start_balance,
+            end_balance,
+            days,
+        }),
+    ))
+}
+
+pub async fn get_transfers(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedQuery(query): ValidatedQuery<TransferListQuery>,
+) -> Result<(StatusCode, Json<Vec<Transfer>>), AppError> {
+    let transfers = state
+        .account_repository
+        .find_transfers(claims.sub, &query)
+        .await?;
+    Ok((StatusCode::OK, Json(transfers)))
+}
+
+pub async fn create_transfer(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(payload): ValidatedJson<CreateTransferPayload>,
+) -> Result<(StatusCode, Json<Transfer>), AppError> {
+    let from = ensure_account(&state, payload.from_account_id, claims.sub).await?;
+    let to = ensure_account(&state, payload.to_account_id, claims.sub).await?;
+    let to_amount = match &payload.to_amount {
+        Some(to_amount) => to_amount.clone(),
+        None if from.currency == to.currency => payload.amount.clone(),
+        None => {
+            return Err(AppError::BadRequest(
+                "to_amount is required between accounts in different currencies".into(),
+            ));
+        }
+    };
+
+    let transfer = state
+        .account_repository
+        .create_transfer(payload, to_amount, claims.sub)
+        .await?;
+    Ok((StatusCode::CREATED, Json(transfer)))
+}
+
+pub async fn delete_transfer(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    match state
+        .account_repository
+        .delete_transfer(id, claims.sub)
+        .await?
+    {
+        Some(_) => Ok(StatusCode::NO_CONTENT),
+        None => Ok(StatusCode::NOT_FOUND),
+    }
+}
+
+/// Rejects account ids that do not belong to the user.
+pub(crate) async fn ensure_account(
+    state: &AppState,
+    id: i32,
+    user_id: i32,
+) -> Result<Account, AppError> {
+    state
+        .account_repository
+        .find_account(id, user_id)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("Account not found".into()))
+}
+
+/// Rejects an account of another user, or in another currency than the
+/// expense or income put in it; a missing currency defaults to the
+/// account's.
+pub(crate) async fn check_account(
+    state: &AppState,
+    user_id: i32,
+    account_id: Option<i32>,
+    currency: Option<&str>,
+) -> Result<(), AppError> {
+    let Some(account_id) = account_id else {
+        return Ok(());
+    };
+    let account = ensure_account(state, account_id, user_id).await?;
+    if currency.is_some_and(|currency| currency != account.currency) {
+        return Err(AppError::BadRequest(format!(
+            "The account is in {}, its expenses and incomes must be too",
+            account.currency
+        )));
+    }
+    Ok(())
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/accounts", get(get_accounts).post(create_account))
+        .route("/accounts/{id}", put(update_account).delete(delete_account))
+        .route("/accounts/{id}/balance", get(get_balance))
+        .route("/transfers", get(get_transfers).post(create_transfer))
+        .route("/transfers/{id}", delete(delete_transfer))
+}
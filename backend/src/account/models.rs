@@ -0,0 +1,58 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[sqlx(type_name = "account_type", rename_all = "PascalCase")]
+pub enum AccountType {
+    Cash,
+    DebitCard,
+    CreditCard,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Account {
+    pub id: i32,
+    pub name: String,
+    pub account_type: AccountType,
+    pub opening_balance: BigDecimal,
+    pub currency: String,
+    /// Opening balance plus every income and transfer in, minus every
+    /// expense and transfer out.
+    pub balance: BigDecimal,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Transfer {
+    pub id: i32,
+    pub from_account_id: i32,
+    pub to_account_id: i32,
+    /// Taken from the source account, in its currency.
+    pub amount: BigDecimal,
+    /// Received by the destination account, in its currency.
+    pub to_amount: BigDecimal,
+    pub description: Option<String>,
+    pub transfer_date: NaiveDate,
+}
+
+/// Movements of one day and the balance at its end.
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BalanceDay {
+    pub date: NaiveDate,
+    pub inflow: BigDecimal,
+    pub outflow: BigDecimal,
+    pub balance: BigDecimal,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccountBalance {
+    pub account_id: i32,
+    pub currency: String,
+    /// Balance before the first day of the range.
+    pub start_balance: BigDecimal,
+    /// Balance at the end of the range.
+    pub end_balance: BigDecimal,
+    /// Days with movements only.
+    pub days: Vec<BalanceDay>,
+}
@@ -0,0 +1,283 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+use super::{
+    models::{Account, BalanceDay, Transfer},
+    utils::{CreateAccountPayload, CreateTransferPayload, TransferListQuery, UpdateAccountPayload},
+};
+
+pub struct AccountRepository {
+    pool: Arc<PgPool>,
+}
+
+impl AccountRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        AccountRepository { pool }
+    }
+
+    pub async fn create_account(
+        &self,
+        payload: CreateAccountPayload,
+        user_id: i32,
+    ) -> anyhow::Result<Account> {
+        sqlx::query_as!(
+            Account,
+            r#"
+    INSERT INTO accounts (user_id, name, account_type, opening_balance, currency)
+    VALUES ($1, $2, $3, COALESCE($4::NUMERIC, 0),
+            COALESCE($5, (SELECT base_currency FROM users WHERE id = $1)))
+    RETURNING id, name, account_type AS "account_type: _", opening_balance, currency,
+              opening_balance AS balance;
+    "#,
+            user_id,
+            payload.name,
+            payload.account_type as _,
+            payload.opening_balance,
+            payload.currency,
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context("Failed to create account")
+    }
+
+    pub async fn find_accounts(&self, user_id: i32) -> anyhow::Result<Vec<Account>> {
+        sqlx::query_as!(
+            Account,
+            r#"
+    SELECT id, name, account_type AS "account_type: _", opening_balance, currency,
+           account_balance(id, NULL) AS "balance!"
+    FROM accounts
+    WHERE user_id = $1
+    ORDER BY name;
+    "#,
+            user_id
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get accounts by user_id: {}", user_id))
+    }
+
+    pub async fn find_account(&self, id: i32, user_id: i32) -> anyhow::Result<Option<Account>> {
+        sqlx::query_as!(
+            Account,
+            r#"
+    SELECT id, name, account_type AS "account_type: _", opening_balance, currency,
+           account_balance(id, NULL) AS "balance!"
+    FROM accounts
+    WHERE id = $1 AND user_id = $2;
+    "#,
+            id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to get account by id: {}", id))
+    }
+
+    pub async fn exists_by_name(
+        &self,
+        user_id: i32,
+        name: &str,
+        exclude_id: Option<i32>,
+    ) -> anyhow::Result<bool> {
+        sqlx::query_scalar!(
+            r#"
+    SELECT EXISTS (
+        SELECT 1 FROM accounts
+        WHERE user_id = $1 AND name = $2 AND id IS DISTINCT FROM $3
+    ) AS "exists!";
+    "#,
+            user_id,
+            name,
+            exclude_id
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context("Failed to check if account exists")
+    }
+
+    pub async fn update_account(
+        &self,
+        id: i32,
+        user_id: i32,
+        payload: UpdateAccountPayload,
+    ) -> anyhow::Result<Option<Account>> {
+        let updated = sqlx::query_scalar!(
+            r#"
+    UPDATE accounts
+    SET name = COALESCE($1, name),
+        account_type = COALESCE($2, account_type),
+        opening_balance = COALESCE($3, opening_balance)
+    WHERE id = $4 AND user_id = $5
+    RETURNING id;
+    "#,
+            payload.name,
+            payload.account_type as _,
+            payload.opening_balance,
+            id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to update account by id: {}", id))?;
+        // The balance is read again so it uses the new opening balance
+        match updated {
+            Some(id) => self.find_account(id, user_id).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Deletes the account; its expenses and incomes are kept without one.
+    pub async fn delete_account(&self, id: i32, user_id: i32) -> anyhow::Result<Option<i32>> {
+        sqlx::query_scalar!(
+            "DELETE FROM accounts WHERE id = $1 AND user_id = $2 RETURNING id;",
+            id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to delete account by id: {}", id))
+    }
+
+    pub async fn has_transfers(&self, account_id: i32) -> anyhow::Result<bool> {
+        sqlx::query_scalar!(
+            r#"
+    SELECT EXISTS (
+        SELECT 1 FROM transfers WHERE from_account_id = $1 OR to_account_id = $1
+    ) AS "exists!";
+    "#,
+            account_id
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to check transfers of account id: {}",
+            account_id
+        ))
+    }
+
+    /// Balance before `from`, the opening balance when it is `None`.
+    pub async fn find_start_balance(
+        &self,
+        account_id: i32,
+        from: Option<NaiveDate>,
+    ) -> anyhow::Result<BigDecimal> {
+        sqlx::query_scalar!(
+            r#"
+    SELECT CASE WHEN $2::DATE IS NULL THEN opening_balance
+                ELSE account_balance(id, $2::DATE - 1) END AS "balance!"
+    FROM accounts WHERE id = $1;
+    "#,
+            account_id,
+            from
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to get balance of account id: {}",
+            account_id
+        ))
+    }
+
+    /// Days with movements between `from` and `to` (inclusive), with the
+    /// running balance at the end of each.
+    pub async fn find_balance_days(
+        &self,
+        account_id: i32,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> anyhow::Result<Vec<BalanceDay>> {
+        sqlx::query_as!(
+            BalanceDay,
+            r#"
+    SELECT date AS "date!", inflow AS "inflow!", outflow AS "outflow!", balance AS "balance!"
+    FROM (
+        SELECT m.day AS date, m.inflow, m.outflow,
+               a.opening_balance + SUM(m.inflow - m.outflow) OVER (ORDER BY m.day) AS balance
+        FROM accounts a, account_movements(a.id) m
+        WHERE a.id = $1
+    ) d
+    WHERE ($2::DATE IS NULL OR date >= $2)
+      AND ($3::DATE IS NULL OR date <= $3)
+    ORDER BY date;
+    "#,
+            account_id,
+            from,
+            to
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to get balance of account id: {}",
+            account_id
+        ))
+    }
+
+    pub async fn create_transfer(
+        &self,
+        payload: CreateTransferPayload,
+        to_amount: BigDecimal,
+        user_id: i32,
+    ) -> anyhow::Result<Transfer> {
+        sqlx::query_as!(
+            Transfer,
+            r#"
+    INSERT INTO transfers
+        (user_id, from_account_id, to_account_id, amount, to_amount, description, transfer_date)
+    VALUES ($1, $2, $3, $4, $5, $6, COALESCE($7, CURRENT_DATE))
+    RETURNING id, from_account_id, to_account_id, amount, to_amount, description, transfer_date;
+    "#,
+            user_id,
+            payload.from_account_id,
+            payload.to_account_id,
+            payload.amount,
+            to_amount,
+            payload.description,
+            payload.transfer_date,
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context("Failed to create transfer")
+    }
+
+    pub async fn find_transfers(
+        &self,
+        user_id: i32,
+        query: &TransferListQuery,
+    ) -> anyhow::Result<Vec<Transfer>> {
+        sqlx::query_as!(
+            Transfer,
+            r#"
+    SELECT id, from_account_id, to_account_id, amount, to_amount, description, transfer_date
+    FROM transfers
+    WHERE user_id = $1
+      AND ($2::INTEGER IS NULL OR from_account_id = $2 OR to_account_id = $2)
+      AND ($3::DATE IS NULL OR transfer_date >= $3)
+      AND ($4::DATE IS NULL OR transfer_date <= $4)
+    ORDER BY transfer_date DESC, id DESC;
+    "#,
+            user_id,
+            query.account_id,
+            query.from,
+            query.to
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get transfers by user_id: {}", user_id))
+    }
+
+    pub async fn delete_transfer(&self, id: i32, user_id: i32) -> anyhow::Result<Option<i32>> {
+        sqlx::query_scalar!(
+            "DELETE FROM transfers WHERE id = $1 AND user_id = $2 RETURNING id;",
+            id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to delete transfer by id: {}", id))
+    }
+}
@@ -0,0 +1,110 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+use crate::{currency::utils::validate_currency, expense::utils::validate_amount};
+
+use super::models::AccountType;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateAccountPayload {
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Name must be between 1 and 100 characters"
+    ))]
+    pub name: String,
+
+    pub account_type: AccountType,
+
+    /// Defaults to zero; negative for a credit card that is already owed.
+    pub opening_balance: Option<BigDecimal>,
+
+    /// Defaults to the user's base currency. Cannot be changed later.
+    #[validate(custom(function = "validate_currency"))]
+    pub currency: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct UpdateAccountPayload {
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Name must be between 1 and 100 characters"
+    ))]
+    pub name: Option<String>,
+
+    pub account_type: Option<AccountType>,
+
+    pub opening_balance: Option<BigDecimal>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_transfer_accounts"))]
+pub struct CreateTransferPayload {
+    #[validate(range(min = 1, message = "Invalid account"))]
+    pub from_account_id: i32,
+
+    #[validate(range(min = 1, message = "Invalid account"))]
+    pub to_account_id: i32,
+
+    #[validate(custom(function = "validate_amount"))]
+    pub amount: BigDecimal,
+
+    /// Required between accounts in different currencies; defaults to
+    /// `amount` otherwise.
+    #[validate(custom(function = "validate_amount"))]
+    pub to_amount: Option<BigDecimal>,
+
+    #[validate(length(max = 255, message = "Description too long"))]
+    pub description: Option<String>,
+
+    /// Defaults to today.
+    pub transfer_date: Option<NaiveDate>,
+}
+
+fn validate_transfer_accounts(payload: &CreateTransferPayload) -> Result<(), ValidationError> {
+    if payload.from_account_id == payload.to_account_id {
+        return Err(ValidationError::new("accounts_must_differ"));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[validate(schema(function = "validate_transfer_bounds"))]
+pub struct TransferListQuery {
+    /// Transfers in or out of this account only.
+    pub account_id: Option<i32>,
+
+    /// Inclusive lower bound on `transfer_date`.
+    pub from: Option<NaiveDate>,
+
+    /// Inclusive upper bound on `transfer_date`.
+    pub to: Option<NaiveDate>,
+}
+
+fn validate_transfer_bounds(query: &TransferListQuery) -> Result<(), ValidationError> {
+    validate_bounds(query.from, query.to)
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[validate(schema(function = "validate_balance_bounds"))]
+pub struct BalanceQuery {
+    /// First day; defaults to the first movement.
+    pub from: Option<NaiveDate>,
+
+    /// Last day; defaults to the last movement.
+    pub to: Option<NaiveDate>,
+}
+
+fn validate_balance_bounds(query: &BalanceQuery) -> Result<(), ValidationError> {
+    validate_bounds(query.from, query.to)
+}
+
+fn validate_bounds(from: Option<NaiveDate>, to: Option<NaiveDate>) -> Result<(), ValidationError> {
+    if from.zip(to).is_some_and(|(from, to)| from > to) {
+        return Err(ValidationError::new("from_must_not_be_after_to"));
+    }
+    Ok(())
+}
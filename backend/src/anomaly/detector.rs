@@ -0,0 +1,140 @@
+/// Fewest past amounts in a category before its expenses are scored.
+pub const MIN_HISTORY: usize = 8;
+
+/// Most recent past amounts a new expense is compared with.
+pub const MAX_HISTORY: i64 = 100;
+
+/// Usual cut-off of the modified z-score (Iglewicz and Hoaglin).
+const BASE_THRESHOLD: f64 = 3.5;
+
+/// Added to the threshold of a category for each dismissed anomaly.
+const DISMISSAL_STEP: f64 = 0.5;
+
+const MAX_THRESHOLD: f64 = 10.0;
+
+/// Scores are stored with a bounded precision.
+const MAX_SCORE: f64 = 99_999.0;
+
+/// Where an amount stands in its category's history.
+#[derive(Debug, Clone, Copy)]
+pub struct Score {
+    /// Typical deviations from the median; negative below it.
+    pub value: f64,
+    pub median: f64,
+}
+
+/// Score above which an amount is flagged, after `dismissed` false alarms
+/// in the category.
+pub fn threshold(dismissed: i64) -> f64 {
+    (BASE_THRESHOLD + DISMISSAL_STEP * dismissed as f64).min(MAX_THRESHOLD)
+}
+
+/// Modified z-score of `amount` against `history`, from the median and the
+/// median absolute deviation (MAD) so that past outliers barely weigh.
+/// `None` while the history is too short.
+pub fn score(amount: f64, history: &[f64]) -> Option<Score> {
+    if history.len() < MIN_HISTORY {
+        return None;
+    }
+    let median = median_of(history);
+    let deviations: Vec<f64> = history.iter().map(|x| (x - median).abs()).collect();
+    let mad = median_of(&deviations);
+
+    // Scales that match the standard deviation for normally distributed
+    // amounts; when most past amounts are equal the MAD is zero and the
+    // mean absolute deviation is used instead, and when all of them are, a
+    // tenth of the median
+    let spread = if mad > 0.0 {
+        mad * 1.4826
+    } else {
+        let mean = deviations.iter().sum::<f64>() / deviations.len() as f64;
+        if mean > 0.0 {
+            mean * 1.2533
+        } else {
+            (median.abs() / 10.0).max(0.01)
+        }
+    };
+
+    Some(Score {
+        value: ((amount - median) / spread).clamp(-MAX_SCORE, MAX_SCORE),
+        median,
+    })
+}
+
+fn median_of(values: &[f64]) -> f64 {
+    let mut values = values.to_vec();
+    values.sort_by(f64::total_cmp);
+    let middle = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GROCERIES: [f64; 8] = [40.0, 42.0, 44.0, 45.0, 45.0, 46.0, 48.0, 50.0];
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-3,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn needs_enough_history() {
+        assert!(score(450.0, &GROCERIES[..MIN_HISTORY - 1]).is_none());
+        assert!(score(450.0, &GROCERIES).is_some());
+    }
+
+    #[test]
+    fn flags_a_typo_but_not_the_usual_amount() {
+        // Median 45, MAD 2
+        let typo = score(450.0, &GROCERIES).unwrap();
+        assert_close(typo.median, 45.0);
+        assert_close(typo.value, 405.0 / (2.0 * 1.4826));
+        assert!(typo.value > threshold(0));
+
+        let usual = score(45.0, &GROCERIES).unwrap();
+        assert_close(usual.value, 0.0);
+        assert!(score(4.5, &GROCERIES).unwrap().value < -threshold(0));
+    }
+
+    #[test]
+    fn falls_back_to_the_mean_deviation_when_the_mad_is_zero() {
+        let history = [10.0, 10.0, 10.0, 10.0, 10.0, 10.0, 10.0, 20.0];
+        let score = score(20.0, &history).unwrap();
+        // Mean absolute deviation 1.25
+        assert_close(score.value, 10.0 / (1.25 * 1.2533));
+        assert!(score.value.is_finite());
+    }
+
+    #[test]
+    fn falls_back_to_a_tenth_of_the_median_when_all_amounts_are_equal() {
+        let history = [45.0; 8];
+        assert_close(score(45.0, &history).unwrap().value, 0.0);
+        assert_close(score(49.5, &history).unwrap().value, 1.0);
+        assert!(score(450.0, &history).unwrap().value > threshold(0));
+
+        let zeros = [0.0; 8];
+        assert_close(score(1.0, &zeros).unwrap().value, 100.0);
+    }
+
+    #[test]
+    fn clamps_huge_scores() {
+        assert_close(score(1e12, &[45.0; 8]).unwrap().value, MAX_SCORE);
+    }
+
+    #[test]
+    fn dismissals_raise_the_threshold_up_to_a_cap() {
+        assert_close(threshold(0), 3.5);
+        assert_close(threshold(2), 4.5);
+        assert_close(threshold(100), MAX_THRESHOLD);
+    }
+}
@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+};
+use bigdecimal::{BigDecimal, FromPrimitive, RoundingMode, ToPrimitive};
+
+use crate::{
+    auth::token::claims::Claims, error::AppError, expense::models::Expense, state::AppState,
+    validation::ValidatedQuery,
+};
+
+use super::{
+    detector::{self, MAX_HISTORY, MIN_HISTORY},
+    models::{Anomaly, AnomalyStatus},
+    utils::AnomalyListQuery,
+};
+
+pub async fn get_anomalies(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedQuery(query): ValidatedQuery<AnomalyListQuery>,
+) -> Result<(StatusCode, Json<Vec<Anomaly>>), AppError> {
+    let anomalies = state
+        .anomaly_repository
+        .find_anomalies(claims.sub, query.status)
+        .await?;
+    Ok((StatusCode::OK, Json(anomalies)))
+}
+
+/// The amount really was unusual: it no longer counts in the history of
+/// its category.
+pub async fn confirm_anomaly(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<Anomaly>), AppError> {
+    resolve(&state, id, claims.sub, AnomalyStatus::Confirmed).await
+}
+
+/// False alarm: the category's threshold is raised for the next expenses.
+pub async fn dismiss_anomaly(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<Anomaly>), AppError> {
+    resolve(&state, id, claims.sub, AnomalyStatus::Dismissed).await
+}
+
+async fn resolve(
+    state: &AppState,
+    id: i32,
+    user_id: i32,
+    status: AnomalyStatus,
+) -> Result<(StatusCode, Json<Anomaly>), AppError> {
+    let anomaly = state
+        .anomaly_repository
+        .resolve_anomaly(id, user_id, status)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Anomaly not found".into()))?;
+    Ok((StatusCode::OK, Json(anomaly)))
+}
+
+/// Scores a new expense against the history of its category and flags it
+/// when it stands out. Expenses without a rate to the base currency are
+/// not scored.
+pub(crate) async fn check_expense(
+    state: &AppState,
+    user_id: i32,
+    expense: &Expense,
+) -> Result<Option<Anomaly>, AppError> {
+    let Some(amount) = expense.amount_in_base.as_ref().and_then(|a| a.to_f64()) else {
+        return Ok(None);
+    };
+    let history: Vec<f64> = state
+        .anomaly_repository
+        .find_history(user_id, expense.category_id, expense.id, MAX_HISTORY)
+        .await?
+        .iter()
+        .filter_map(|a| a.to_f64())
+        .collect();
+    if history.len() < MIN_HISTORY {
+        return Ok(None);
+    }
+    let Some(score) = detector::score(amount, &history) else {
+        return Ok(None);
+    };
+
+    let dismissed = state
+        .anomaly_repository
+        .count_dismissed(user_id, expense.category_id)
+        .await?;
+    let threshold = detector::threshold(dismissed);
+    if score.value.abs() <= threshold {
+        return Ok(None);
+    }
+
+    let anomaly = state
+        .anomaly_repository
+        .create_anomaly(
+            user_id,
+            expense.id,
+            to_decimal(score.value),
+            to_decimal(score.median),
+            to_decimal(threshold),
+        )
+        .await?;
+    Ok(Some(anomaly))
+}
+
+fn to_decimal(value: f64) -> BigDecimal {
+    BigDecimal::from_f64(value)
+        .unwrap_or_default()
+        .with_scale_round(2, RoundingMode::HalfUp)
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/anomalies", get(get_anomalies))
+        .route("/anomalies/{id}/confirm", post(confirm_anomaly))
+        .route("/anomalies/{id}/dismiss", post(dismiss_anomaly))
+}
@@ -0,0 +1,37 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[sqlx(type_name = "anomaly_status", rename_all = "PascalCase")]
+pub enum AnomalyStatus {
+    /// Waiting for the user.
+    #[default]
+    Pending,
+    /// The amount really was unusual; left out of the category's history.
+    Confirmed,
+    /// The amount was fine; the category gets less sensitive.
+    Dismissed,
+}
+
+/// A new expense whose amount stood out from its category's history.
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Anomaly {
+    pub id: i32,
+    pub expense_id: i32,
+    pub category_id: i32,
+    pub description: Option<String>,
+    pub amount: BigDecimal,
+    pub currency: String,
+    pub expense_date: NaiveDateTime,
+    /// Modified z-score of the amount in the base currency: how many
+    /// typical deviations it is from the median.
+    pub score: BigDecimal,
+    /// Median of the category's history, in the base currency.
+    pub median: BigDecimal,
+    /// Score above which an amount is flagged.
+    pub threshold: BigDecimal,
+    pub status: AnomalyStatus,
+    pub created_at: NaiveDateTime,
+}
@@ -0,0 +1,162 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use bigdecimal::BigDecimal;
+use sqlx::PgPool;
+
+use super::models::{Anomaly, AnomalyStatus};
+
+pub struct AnomalyRepository {
+    pool: Arc<PgPool>,
+}
+
+impl AnomalyRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        AnomalyRepository { pool }
+    }
+
+    /// Latest amounts of the category in the base currency, other than
+    /// `expense_id`'s. Expenses confirmed as anomalies or without a known
+    /// rate are left out.
+    pub async fn find_history(
+        &self,
+        user_id: i32,
+        category_id: i32,
+        expense_id: i32,
+        limit: i64,
+    ) -> anyhow::Result<Vec<BigDecimal>> {
+        sqlx::query_scalar!(
+            r#"
+    SELECT amount AS "amount!"
+    FROM (
+        SELECT convert_to_base(e.amount, e.currency, e.user_id, e.expense_date::DATE) AS amount
+        FROM expenses e
+        WHERE e.user_id = $1 AND e.category_id = $2 AND e.id <> $3 AND e.deleted_at IS NULL
+          AND NOT EXISTS (
+              SELECT 1 FROM expense_anomalies a
+              WHERE a.expense_id = e.id AND a.status = 'Confirmed'
+          )
+        ORDER BY e.expense_date DESC, e.id DESC
+        LIMIT $4
+    ) h
+    WHERE amount IS NOT NULL;
+    "#,
+            user_id,
+            category_id,
+            expense_id,
+            limit
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to get history of category id: {}",
+            category_id
+        ))
+    }
+
+    /// Anomalies dismissed among the category's expenses.
+    pub async fn count_dismissed(&self, user_id: i32, category_id: i32) -> anyhow::Result<i64> {
+        sqlx::query_scalar!(
+            r#"
+    SELECT COUNT(*) AS "count!"
+    FROM expense_anomalies a
+    JOIN expenses e ON e.id = a.expense_id
+    WHERE a.user_id = $1 AND e.category_id = $2 AND a.status = 'Dismissed';
+    "#,
+            user_id,
+            category_id
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to count dismissed anomalies of category id: {}",
+            category_id
+        ))
+    }
+
+    pub async fn create_anomaly(
+        &self,
+        user_id: i32,
+        expense_id: i32,
+        score: BigDecimal,
+        median: BigDecimal,
+        threshold: BigDecimal,
+    ) -> anyhow::Result<Anomaly> {
+        sqlx::query_as!(
+            Anomaly,
+            r#"
+    WITH inserted AS (
+        INSERT INTO expense_anomalies (user_id, expense_id, score, median, threshold)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING *
+    )
+    SELECT a.id, a.expense_id, e.category_id, e.description, e.amount, e.currency,
+           e.expense_date, a.score, a.median, a.threshold, a.status AS "status: _", a.created_at
+    FROM inserted a
+    JOIN expenses e ON e.id = a.expense_id;
+    "#,
+            user_id,
+            expense_id,
+            score,
+            median,
+            threshold
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context(format!("Failed to flag expense id: {}", expense_id))
+    }
+
+    /// Anomalies of live expenses with this status, newest first.
+    pub async fn find_anomalies(
+        &self,
+        user_id: i32,
+        status: AnomalyStatus,
+    ) -> anyhow::Result<Vec<Anomaly>> {
+        sqlx::query_as!(
+            Anomaly,
+            r#"
+    SELECT a.id, a.expense_id, e.category_id, e.description, e.amount, e.currency,
+           e.expense_date, a.score, a.median, a.threshold, a.status AS "status: _", a.created_at
+    FROM expense_anomalies a
+    JOIN expenses e ON e.id = a.expense_id
+    WHERE a.user_id = $1 AND a.status = $2 AND e.deleted_at IS NULL
+    ORDER BY a.created_at DESC, a.id DESC;
+    "#,
+            user_id,
+            status as _
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get anomalies by user_id: {}", user_id))
+    }
+
+    /// Confirms or dismisses an anomaly; it can be resolved again later.
+    pub async fn resolve_anomaly(
+        &self,
+        id: i32,
+        user_id: i32,
+        status: AnomalyStatus,
+    ) -> anyhow::Result<Option<Anomaly>> {
+        sqlx::query_as!(
+            Anomaly,
+            r#"
+    WITH updated AS (
+        UPDATE expense_anomalies
+        SET status = $3, resolved_at = NOW()
+        WHERE id = $1 AND user_id = $2
+        RETURNING *
+    )
+    SELECT a.id, a.expense_id, e.category_id, e.description, e.amount, e.currency,
+           e.expense_date, a.score, a.median, a.threshold, a.status AS "status: _", a.created_at
+    FROM updated a
+    JOIN expenses e ON e.id = a.expense_id;
+    "#,
+            id,
+            user_id,
+            status as _
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to resolve anomaly by id: {}", id))
+    }
+}
@@ -0,0 +1,11 @@
+use serde::Deserialize;
+use validator::Validate;
+
+use super::models::AnomalyStatus;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct AnomalyListQuery {
+    /// Defaults to the anomalies waiting for the user.
+    #[serde(default)]
+    pub status: AnomalyStatus,
+}
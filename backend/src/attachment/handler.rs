@@ -0,0 +1,208 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::{DefaultBodyLimit, Multipart, Path, State},
+    http::{
+        StatusCode,
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    },
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use uuid::Uuid;
+
+use crate::{
+    auth::token::claims::Claims, error::AppError, expense::handler::ensure_expense, state::AppState,
+};
+
+use super::{
+    models::Attachment,
+    utils::{
+        MAX_ATTACHMENT_SIZE, MAX_ATTACHMENTS_PER_EXPENSE, content_disposition, sanitize_file_name,
+        sniff_content_type,
+    },
+};
+
+/// A validated file waiting to be stored.
+struct Upload {
+    file_name: String,
+    content_type: &'static str,
+    bytes: Vec<u8>,
+}
+
+pub async fn get_attachments(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(expense_id): Path<i32>,
+) -> Result<(StatusCode, Json<Vec<Attachment>>), AppError> {
+    ensure_expense(&state, expense_id, claims.sub).await?;
+    let attachments = state
+        .attachment_repository
+        .find_attachments(expense_id, claims.sub)
+        .await?;
+    Ok((StatusCode::OK, Json(attachments)))
+}
+
+/// Accepts one or more `file` fields; the whole request is rejected if any
+/// file is invalid.
+pub async fn upload_attachments(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(expense_id): Path<i32>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<Vec<Attachment>>), AppError> {
+    ensure_expense(&state, expense_id, claims.sub).await?;
+
+    let mut uploads = Vec::new();
+    while let Some(mut field) = multipart.next_field().await? {
+        if field.name() != Some("file") {
+            continue;
+        }
+        let file_name = sanitize_file_name(field.file_name().unwrap_or_default());
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.chunk().await? {
+            if bytes.len() + chunk.len() > MAX_ATTACHMENT_SIZE {
+                return Err(AppError::BadRequest(format!(
+                    "{} exceeds the {} MB limit",
+                    file_name,
+                    MAX_ATTACHMENT_SIZE / (1024 * 1024)
+                )));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        let content_type = sniff_content_type(&bytes).ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "{}: only JPEG, PNG and PDF files are accepted",
+                file_name
+            ))
+        })?;
+        uploads.push(Upload {
+            file_name,
+            content_type,
+            bytes,
+        });
+    }
+
+    if uploads.is_empty() {
+        return Err(AppError::BadRequest("No file uploaded".into()));
+    }
+    let existing = state
+        .attachment_repository
+        .count_attachments(expense_id)
+        .await?;
+    if existing + uploads.len() as i64 > MAX_ATTACHMENTS_PER_EXPENSE {
+        return Err(AppError::BadRequest(format!(
+            "An expense can have at most {} attachments",
+            MAX_ATTACHMENTS_PER_EXPENSE
+        )));
+    }
+
+    let mut attachments = Vec::with_capacity(uploads.len());
+    for upload in uploads {
+        let storage_key = format!("{}/{}", claims.sub, Uuid::new_v4());
+        state
+            .attachment_storage
+            .put(&storage_key, &upload.bytes)
+            .await?;
+
+        let created = state
+            .attachment_repository
+            .create_attachment(
+                expense_id,
+                claims.sub,
+                &upload.file_name,
+                upload.content_type,
+                upload.bytes.len() as i32,
+                &storage_key,
+            )
+            .await;
+        match created {
+            Ok(attachment) => attachments.push(attachment),
+            Err(e) => {
+                // Do not leave an orphan file behind
+                if let Err(e) = state.attachment_storage.delete(&storage_key).await {
+                    tracing::error!("Failed to remove orphan attachment: {:?}", e);
+                }
+                return Err(e.into());
+            }
+        }
+    }
+
+    Ok((StatusCode::CREATED, Json(attachments)))
+}
+
+pub async fn download_attachment(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path((expense_id, id)): Path<(i32, i32)>,
+) -> Result<Response, AppError> {
+    let attachment = state
+        .attachment_repository
+        .find_attachment(id, expense_id, claims.sub)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Attachment not found".into()))?;
+    let bytes = state
+        .attachment_storage
+        .get(&attachment.storage_key)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        [
+            (CONTENT_TYPE, attachment.content_type),
+            (
+                CONTENT_DISPOSITION,
+                content_disposition(&attachment.file_name),
+            ),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
+pub async fn delete_attachment(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path((expense_id, id)): Path<(i32, i32)>,
+) -> Result<StatusCode, AppError> {
+    match state
+        .attachment_repository
+        .delete_attachment(id, expense_id, claims.sub)
+        .await?
+    {
+        Some(storage_key) => {
+            remove_files(&state, &[storage_key]).await;
+            Ok(StatusCode::NO_CONTENT)
+        }
+        None => Ok(StatusCode::NOT_FOUND),
+    }
+}
+
+/// Deletes stored files whose rows are already gone. Failures only leave
+/// unreachable files behind, so they are logged rather than returned.
+pub(crate) async fn remove_files(state: &AppState, storage_keys: &[String]) {
+    for key in storage_keys {
+        if let Err(e) = state.attachment_storage.delete(key).await {
+            tracing::error!("Failed to delete attachment file: {:?}", e);
+        }
+    }
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route(
+            "/expenses/{id}/attachments",
+            get(get_attachments)
+                .post(upload_attachments)
+                // Uploads are bounded by the server-wide RequestBodyLimitLayer
+                // and MAX_ATTACHMENT_SIZE rather than axum's 2 MB default
+                .layer(DefaultBodyLimit::disable()),
+        )
+        .route(
+            "/expenses/{id}/attachments/{attachment_id}",
+            get(download_attachment).delete(delete_attachment),
+        )
+}
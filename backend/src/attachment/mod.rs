@@ -0,0 +1,5 @@
+pub mod handler;
+pub mod models;
+pub mod repository;
+pub mod storage;
+pub mod utils;
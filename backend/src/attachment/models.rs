@@ -0,0 +1,14 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Attachment {
+    pub id: i32,
+    pub expense_id: i32,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i32,
+    #[serde(skip)]
+    pub storage_key: String,
+    pub created_at: NaiveDateTime,
+}
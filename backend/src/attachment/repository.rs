@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use sqlx::PgPool;
+
+use super::models::Attachment;
+
+pub struct AttachmentRepository {
+    pool: Arc<PgPool>,
+}
+
+impl AttachmentRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        AttachmentRepository { pool }
+    }
+
+    pub async fn find_attachments(
+        &self,
+        expense_id: i32,
+        user_id: i32,
+    ) -> anyhow::Result<Vec<Attachment>> {
+        sqlx::query_as!(
+            Attachment,
+            r#"
+            SELECT id, expense_id, file_name, content_type, size_bytes, storage_key, created_at
+            FROM attachments WHERE expense_id = $1 AND user_id = $2
+            ORDER BY id;
+            "#,
+            expense_id,
+            user_id
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to get attachments by expense_id: {}",
+            expense_id
+        ))
+    }
+
+    pub async fn find_attachment(
+        &self,
+        id: i32,
+        expense_id: i32,
+        user_id: i32,
+    ) -> anyhow::Result<Option<Attachment>> {
+        sqlx::query_as!(
+            Attachment,
+            r#"
+            SELECT id, expense_id, file_name, content_type, size_bytes, storage_key, created_at
+            FROM attachments WHERE id = $1 AND expense_id = $2 AND user_id = $3;
+            "#,
+            id,
+            expense_id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to get attachment by id: {}", id))
+    }
+
+    pub async fn count_attachments(&self, expense_id: i32) -> anyhow::Result<i64> {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM attachments WHERE expense_id = $1;"#,
+            expense_id
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to count attachments of expense id: {}",
+            expense_id
+        ))?;
+        Ok(count)
+    }
+
+    pub async fn create_attachment(
+        &self,
+        expense_id: i32,
+        user_id: i32,
+        file_name: &str,
+        content_type: &str,
+        size_bytes: i32,
+        storage_key: &str,
+    ) -> anyhow::Result<Attachment> {
+        sqlx::query_as!(
+            Attachment,
+            r#"
+    INSERT INTO attachments (expense_id, user_id, file_name, content_type, size_bytes, storage_key)
+    VALUES ($1, $2, $3, $4, $5, $6)
+    RETURNING id, expense_id, file_name, content_type, size_bytes, storage_key, created_at;
+    "#,
+            expense_id,
+            user_id,
+            file_name,
+            content_type,
+            size_bytes,
+            storage_key
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context("Failed to create attachment")
+    }
+
+    /// Returns the storage key of the deleted attachment.
+    pub async fn delete_attachment(
+        &self,
+        id: i32,
+        expense_id: i32,
+        user_id: i32,
+    ) -> anyhow::Result<Option<String>> {
+        sqlx::query_scalar!(
+            "DELETE FROM attachments WHERE id = $1 AND expense_id = $2 AND user_id = $3 RETURNING storage_key;",
+            id,
+            expense_id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to delete attachment by id: {}", id))
+    }
+}
@@ -0,0 +1,67 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use async_trait::async_trait;
+
+/// Where attachment contents live; the database only keeps their keys.
+#[async_trait]
+pub trait AttachmentStorage: Send + Sync {
+    async fn put(&self, key: &str, bytes: &[u8]) -> anyhow::Result<()>;
+    async fn get(&self, key: &str) -> anyhow::Result<Vec<u8>>;
+    /// Succeeds when the key does not exist.
+    async fn delete(&self, key: &str) -> anyhow::Result<()>;
+}
+
+/// Stores each attachment as a file under `root`, at the path given by its key.
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> anyhow::Result<PathBuf> {
+        // Keys are generated by the server, but never let one escape the root
+        let relative = Path::new(key);
+        if relative.is_absolute()
+            || relative
+                .components()
+                .any(|c| !matches!(c, std::path::Component::Normal(_)))
+        {
+            anyhow::bail!("Invalid storage key: {}", key);
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl AttachmentStorage for LocalStorage {
+    async fn put(&self, key: &str, bytes: &[u8]) -> anyhow::Result<()> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .context(format!("Failed to create directory: {}", parent.display()))?;
+        }
+        tokio::fs::write(&path, bytes)
+            .await
+            .context(format!("Failed to write attachment: {}", key))
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Vec<u8>> {
+        tokio::fs::read(self.path(key)?)
+            .await
+            .context(format!("Failed to read attachment: {}", key))
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).context(format!("Failed to delete attachment: {}", key))
+            }
+            _ => Ok(()),
+        }
+    }
+}
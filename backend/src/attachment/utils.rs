@@ -0,0 +1,64 @@
+/// Largest accepted file. A request is still capped by the global body limit.
+pub const MAX_ATTACHMENT_SIZE: usize = 5 * 1024 * 1024;
+
+/// Largest number of files attached to one expense.
+pub const MAX_ATTACHMENTS_PER_EXPENSE: i64 = 10;
+
+/// Content type of a JPEG, PNG or PDF file, detected from its first bytes.
+/// The type declared by the client is not trusted.
+pub fn sniff_content_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.starts_with(b"%PDF-") {
+        Some("application/pdf")
+    } else {
+        None
+    }
+}
+
+/// Keeps the last path segment of an uploaded file name, without quotes or
+/// control characters, so it is safe in a `Content-Disposition` header.
+pub fn sanitize_file_name(raw: &str) -> String {
+    let name: String = raw
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"')
+        .take(255)
+        .collect();
+    let name = name.trim();
+    if name.is_empty() {
+        "attachment".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// `Content-Disposition` for a download, with an ASCII fallback name and the
+/// exact name percent-encoded as `filename*` (RFC 6266).
+pub fn content_disposition(file_name: &str) -> String {
+    let fallback: String = file_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_graphic() || c == ' ' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let encoded: String = file_name
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'.' | b'-' | b'_' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+    format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        fallback, encoded
+    )
+}
@@ -1,27 +1,32 @@
 use std::sync::Arc;
 
 use axum::{extract::State, http::StatusCode, response::Json};
-use serde_json::json;
 use serde::Deserialize;
+use serde_json::json;
 use validator::Validate;
 
+use crate::validation::ValidatedJson;
 use crate::{
+    auth::token::claims::Claims,
+    currency::{handler::ensure_currency, utils::validate_currency},
     error::AppError,
     state::AppState,
     user::repository::UserRepository,
-     auth::token::claims::Claims, 
 };
-use crate::validation::ValidatedJson;
 
 #[derive(Debug, Validate, Deserialize)]
 pub struct UpdateMePayload {
     #[validate(length(min = 1, message = "Name cannot be empty"))]
     name: String,
+
+    /// Currency aggregates and conversions are expressed in.
+    #[validate(custom(function = "validate_currency"))]
+    base_currency: Option<String>,
 }
 
 pub async fn me_handler(
     State(state): State<Arc<AppState>>,
-     claims: Claims,
+    claims: Claims,
 ) -> Result<Json<serde_json::Value>, AppError> {
     let user = state
         .user_repository
@@ -33,6 +38,7 @@ pub async fn me_handler(
         "id": user.id,
         "name": user.name,
         "email": user.email,
+        "base_currency": user.base_currency,
         "created_at": user.created_at
     })))
 }
@@ -42,16 +48,29 @@ pub async fn update_me_handler(
     claims: Claims,
     ValidatedJson(payload): ValidatedJson<UpdateMePayload>,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    let updated_user = state
+    if let Some(currency) = &payload.base_currency {
+        ensure_currency(&state, currency).await?;
+    }
+
+    let mut updated_user = state
         .user_repository
         .update_name(claims.sub, &payload.name)
         .await?
         .ok_or(AppError::NotFound("User not found".into()))?;
 
+    if let Some(currency) = &payload.base_currency {
+        updated_user = state
+            .user_repository
+            .update_base_currency(claims.sub, currency)
+            .await?
+            .ok_or(AppError::NotFound("User not found".into()))?;
+    }
+
     Ok(Json(json!({
         "id": updated_user.id,
         "name": updated_user.name,
         "email": updated_user.email,
+        "base_currency": updated_user.base_currency,
         "created_at": updated_user.created_at
     })))
 }
@@ -1,11 +1,11 @@
 use std::sync::Arc;
 use uuid::Uuid; // Import Uuid
 
+use anyhow::anyhow;
 use axum::{Json, extract::State, http::StatusCode};
 use serde::Deserialize;
-use validator::Validate;
 use serde_json::json; // Import json macro
-use anyhow::anyhow;
+use validator::Validate;
 
 use crate::{
     auth::{
@@ -33,7 +33,8 @@ pub struct RegisterPayload {
 pub async fn register_handler(
     State(state): State<Arc<AppState>>,
     ValidatedJson(payload): ValidatedJson<RegisterPayload>,
-) -> Result<(StatusCode, Json<serde_json::Value>), AppError> { // Return a generic JSON value
+) -> Result<(StatusCode, Json<serde_json::Value>), AppError> {
+    // Return a generic JSON value
     if state
         .user_repository
         .exists_by_email(&payload.email)
@@ -43,7 +44,7 @@ pub async fn register_handler(
     }
 
     let password_hash = state.password_service.hash_password(&payload.password)?;
-    
+
     let verification_token = Uuid::new_v4().to_string();
 
     let user = state
@@ -55,26 +56,30 @@ pub async fn register_handler(
         })
         .await?;
 
-
+    state.category_repository.seed_defaults(user.id).await?;
 
     // Update user with new verification token
     state
         .user_repository
         .set_verification_token(user.id, &verification_token)
-        .await?;        
+        .await?;
     // Send verification email
-    if let Err(e) = state.email_service
+    if let Err(e) = state
+        .email_service
         .send_verification_email(&payload.email, &verification_token)
         .await
     {
         tracing::error!("Failed to send verification email: {}", e);
 
-        return Err(AppError::InternalServerError(
-            anyhow!("Failed to send verification email")
-        ));
+        return Err(AppError::InternalServerError(anyhow!(
+            "Failed to send verification email"
+        )));
     }
 
-    Ok((StatusCode::CREATED, Json(json!({
-        "message": "Registration successful. Please check your email to verify your account."
-    }))))
-}
\ No newline at end of file
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "message": "Registration successful. Please check your email to verify your account."
+        })),
+    ))
+}
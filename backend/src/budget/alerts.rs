@@ -0,0 +1,64 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+
+use crate::{state::AppState, user::repository::UserRepository};
+
+use super::utils::month_start;
+
+/// Alert levels in percent of the budget, highest first.
+const ALERT_THRESHOLDS: [i16; 2] = [100, 80];
+
+/// Emails the user when their spending in the category reaches a new
+/// threshold of its monthly budget. Each threshold fires once per month; when
+/// one expense crosses both, only the highest is sent.
+pub async fn notify_thresholds(
+    state: &AppState,
+    user_id: i32,
+    category_id: i32,
+    expense_date: NaiveDate,
+) -> anyhow::Result<()> {
+    let month = month_start(expense_date);
+    let Some(status) = state
+        .budget_repository
+        .find_status(user_id, month, Some(category_id))
+        .await?
+        .pop()
+    else {
+        return Ok(());
+    };
+
+    let mut recorded = Vec::new();
+    for threshold in ALERT_THRESHOLDS {
+        // Older bigdecimal releases only compare with another BigDecimal
+        let percent = BigDecimal::from(threshold);
+        if status.percent_used >= percent
+            && state
+                .budget_repository
+                .record_alert(status.budget_id, month, threshold)
+                .await?
+        {
+            recorded.push(threshold);
+        }
+    }
+
+    let Some(&threshold) = recorded.first() else {
+        return Ok(());
+    };
+    let Some(user) = state.user_repository.find_by_id(user_id).await? else {
+        return Ok(());
+    };
+    let sent = state
+        .email_service
+        .send_budget_alert_email(&user.email, &status, threshold)
+        .await;
+    if sent.is_err() {
+        // Lets the next expense of the month retry the alert
+        for threshold in recorded {
+            state
+                .budget_repository
+                .remove_alert(status.budget_id, month, threshold)
+                .await?;
+        }
+    }
+    sent
+}
@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, put},
+};
+
+use crate::{
+    auth::token::claims::Claims,
+    error::AppError,
+    expense::handler::ensure_category,
+    state::AppState,
+    validation::{ValidatedJson, ValidatedQuery},
+};
+
+use super::{
+    models::{Budget, BudgetStatusReport},
+    utils::{BudgetStatusQuery, CreateBudgetPayload, UpdateBudgetPayload},
+};
+
+pub async fn get_budgets(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Vec<Budget>>), AppError> {
+    let budgets = state.budget_repository.find_budgets(claims.sub).await?;
+    Ok((StatusCode::OK, Json(budgets)))
+}
+
+pub async fn create_budget(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(payload): ValidatedJson<CreateBudgetPayload>,
+) -> Result<(StatusCode, Json<Budget>), AppError> {
+    ensure_category(&state, payload.category_id, claims.sub).await?;
+    if state
+        .budget_repository
+        .exists_by_category(claims.sub, payload.category_id)
+        .await?
+    {
+        return Err(AppError::Conflict(
+            "A budget already exists for this category".into(),
+        ));
+    }
+
+    let budget = state
+        .budget_repository
+        .create_budget(payload, claims.sub)
+        .await?;
+    Ok((StatusCode::CREATED, Json(budget)))
+}
+
+pub async fn update_budget(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    ValidatedJson(payload): ValidatedJson<UpdateBudgetPayload>,
+) -> Result<(StatusCode, Json<Budget>), AppError> {
+    let budget = state
+        .budget_repository
+        .update_budget(id, claims.sub, payload)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Budget not found".into()))?;
+    Ok((StatusCode::OK, Json(budget)))
+}
+
+pub async fn delete_budget(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    match state
+        .budget_repository
+        .delete_budget(id, claims.sub)
+        .await?
+    {
+        Some(_) => Ok(StatusCode::NO_CONTENT),
+        None => Ok(StatusCode::NOT_FOUND),
+    }
+}
+
+pub async fn get_budget_status(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedQuery(query): ValidatedQuery<BudgetStatusQuery>,
+) -> Result<(StatusCode, Json<BudgetStatusReport>), AppError> {
+    let month = query.month_start();
+    let budgets = state
+        .budget_repository
+        .find_status(claims.sub, month, None)
+        .await?;
+    Ok((StatusCode::OK, Json(BudgetStatusReport { month, budgets })))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/budgets", get(get_budgets).post(create_budget))
+        .route("/budgets/status", get(get_budget_status))
+        .route("/budgets/{id}", put(update_budget).delete(delete_budget))
+}
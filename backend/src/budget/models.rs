@@ -0,0 +1,29 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Budget {
+    pub id: i32,
+    pub category_id: i32,
+    pub amount: BigDecimal,
+}
+
+/// Budget compared with the spending of one month.
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BudgetStatus {
+    pub budget_id: i32,
+    pub category_id: i32,
+    pub category_name: String,
+    pub amount: BigDecimal,
+    pub spent: BigDecimal,
+    pub remaining: BigDecimal,
+    /// Share of the budget already spent, in percent.
+    pub percent_used: BigDecimal,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BudgetStatusReport {
+    pub month: NaiveDate,
+    pub budgets: Vec<BudgetStatus>,
+}
@@ -0,0 +1,190 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+use super::{
+    models::{Budget, BudgetStatus},
+    utils::{CreateBudgetPayload, UpdateBudgetPayload, next_month_start},
+};
+
+pub struct BudgetRepository {
+    pool: Arc<PgPool>,
+}
+
+impl BudgetRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        BudgetRepository { pool }
+    }
+
+    pub async fn find_budgets(&self, user_id: i32) -> anyhow::Result<Vec<Budget>> {
+        sqlx::query_as!(
+            Budget,
+            "SELECT id, category_id, amount FROM budgets WHERE user_id = $1 ORDER BY id;",
+            user_id
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get budgets by user_id: {}", user_id))
+    }
+
+    pub async fn exists_by_category(&self, user_id: i32, category_id: i32) -> anyhow::Result<bool> {
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM budgets WHERE user_id = $1 AND category_id = $2) AS "exists!";"#,
+            user_id,
+            category_id
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context(format!("Failed to check budget of category id: {}", category_id))?;
+        Ok(exists)
+    }
+
+    pub async fn create_budget(
+        &self,
+        payload: CreateBudgetPayload,
+        user_id: i32,
+    ) -> anyhow::Result<Budget> {
+        sqlx::query_as!(
+            Budget,
+            r#"
+    INSERT INTO budgets (user_id, category_id, amount)
+    VALUES ($1, $2, $3)
+    RETURNING id, category_id, amount;
+    "#,
+            user_id,
+            payload.category_id,
+            payload.amount,
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context("Failed to create budget")
+    }
+
+    pub async fn update_budget(
+        &self,
+        id: i32,
+        user_id: i32,
+        payload: UpdateBudgetPayload,
+    ) -> anyhow::Result<Option<Budget>> {
+        sqlx::query_as!(
+            Budget,
+            r#"
+    UPDATE budgets SET amount = $1
+    WHERE id = $2 AND user_id = $3
+    RETURNING id, category_id, amount;
+    "#,
+            payload.amount,
+            id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context("Failed to update budget")
+    }
+
+    pub async fn delete_budget(&self, id: i32, user_id: i32) -> anyhow::Result<Option<i32>> {
+        sqlx::query_scalar!(
+            "DELETE FROM budgets WHERE id = $1 AND user_id = $2 RETURNING id;",
+            id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to delete budget by id: {}", id))
+    }
+
+    /// Spending of the month starting at `month` against each budget,
+    /// optionally restricted to one category. Budgets are in the user's base
+    /// currency; expenses without a known rate are left out.
+    pub async fn find_status(
+        &self,
+        user_id: i32,
+        month: NaiveDate,
+        category_id: Option<i32>,
+    ) -> anyhow::Result<Vec<BudgetStatus>> {
+        sqlx::query_as!(
+            BudgetStatus,
+            r#"
+    WITH spending AS (
+        SELECT category_id,
+               SUM(convert_to_base(amount, currency, user_id, expense_date::DATE)) AS spent
+        FROM expenses
+        WHERE user_id = $1 AND deleted_at IS NULL AND expense_date >= $2::DATE AND expense_date < $3::DATE
+        GROUP BY category_id
+    )
+    SELECT b.id AS budget_id,
+           b.category_id,
+           c.name AS category_name,
+           b.amount,
+           COALESCE(s.spent, 0) AS "spent!",
+           b.amount - COALESCE(s.spent, 0) AS "remaining!",
+           ROUND(COALESCE(s.spent, 0) * 100 / b.amount, 1) AS "percent_used!"
+    FROM budgets b
+    JOIN categories c ON c.id = b.category_id
+    LEFT JOIN spending s ON s.category_id = b.category_id
+    WHERE b.user_id = $1 AND ($4::INTEGER IS NULL OR b.category_id = $4)
+    ORDER BY c.name;
+    "#,
+            user_id,
+            month,
+            next_month_start(month),
+            category_id
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get budget status by user_id: {}", user_id))
+    }
+
+    /// Records that `threshold` was reached; false when it already was this month.
+    pub async fn record_alert(
+        &self,
+        budget_id: i32,
+        month: NaiveDate,
+        threshold: i16,
+    ) -> anyhow::Result<bool> {
+        let inserted = sqlx::query!(
+            r#"
+    INSERT INTO budget_alerts (budget_id, period, threshold)
+    VALUES ($1, $2, $3)
+    ON CONFLICT DO NOTHING;
+    "#,
+            budget_id,
+            month,
+            threshold
+        )
+        .execute(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to record alert of budget id: {}",
+            budget_id
+        ))?;
+        Ok(inserted.rows_affected() > 0)
+    }
+
+    /// Forgets that `threshold` was reached, so the alert is sent again.
+    pub async fn remove_alert(
+        &self,
+        budget_id: i32,
+        month: NaiveDate,
+        threshold: i16,
+    ) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+    DELETE FROM budget_alerts
+    WHERE budget_id = $1 AND period = $2 AND threshold = $3;
+    "#,
+            budget_id,
+            month,
+            threshold
+        )
+        .execute(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to remove alert of budget id: {}",
+            budget_id
+        ))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+use bigdecimal::BigDecimal;
+use chrono::{Datelike, Months, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+use crate::expense::utils::validate_amount;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateBudgetPayload {
+    #[validate(range(min = 1, message = "Invalid category"))]
+    pub category_id: i32,
+
+    #[validate(custom(function = "validate_amount"))]
+    pub amount: BigDecimal,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct UpdateBudgetPayload {
+    #[validate(custom(function = "validate_amount"))]
+    pub amount: BigDecimal,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct BudgetStatusQuery {
+    /// `YYYY-MM`; defaults to the current month.
+    #[validate(custom(function = "validate_month"))]
+    pub month: Option<String>,
+}
+
+impl BudgetStatusQuery {
+    pub fn month_start(&self) -> NaiveDate {
+        self.month
+            .as_deref()
+            .and_then(parse_month)
+            .unwrap_or_else(|| month_start(Utc::now().date_naive()))
+    }
+}
+
+/// Years a `YYYY-MM` month may fall in.
+const MONTH_YEARS: std::ops::RangeInclusive<i32> = 1970..=9999;
+
+/// First day of a `YYYY-MM` month.
+pub(crate) fn parse_month(raw: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{raw}-01"), "%Y-%m-%d")
+        .ok()
+        .filter(|date| MONTH_YEARS.contains(&date.year()))
+}
+
+pub(crate) fn validate_month(raw: &str) -> Result<(), ValidationError> {
+    if parse_month(raw).is_none() {
+        return Err(ValidationError::new("invalid_month"));
+    }
+    Ok(())
+}
+
+pub(crate) fn month_start(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+/// First day of the following month, or the last representable date when
+/// there is none.
+pub(crate) fn next_month_start(date: NaiveDate) -> NaiveDate {
+    month_start(date)
+        .checked_add_months(Months::new(1))
+        .unwrap_or(NaiveDate::MAX)
+}
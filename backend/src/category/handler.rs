@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, put},
+};
+
+use crate::{
+    auth::token::claims::Claims, error::AppError, state::AppState, validation::ValidatedJson,
+};
+
+use super::{
+    models::Category,
+    utils::{CreateCategoryPayload, DeleteCategoryQuery, UpdateCategoryPayload},
+};
+
+pub async fn get_categories(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Vec<Category>>), AppError> {
+    let categories = state
+        .category_repository
+        .find_categories(claims.sub)
+        .await?;
+    Ok((StatusCode::OK, Json(categories)))
+}
+
+pub async fn create_category(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(payload): ValidatedJson<CreateCategoryPayload>,
+) -> Result<(StatusCode, Json<Category>), AppError> {
+    if state
+        .category_repository
+        .exists_by_name(claims.sub, &payload.name, None)
+        .await?
+    {
+        return Err(AppError::Conflict("Category already exists".into()));
+    }
+
+    let category = state
+        .category_repository
+        .create_category(payload, claims.sub)
+        .await?;
+    Ok((StatusCode::CREATED, Json(category)))
+}
+
+pub async fn update_category(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    ValidatedJson(payload): ValidatedJson<UpdateCategoryPayload>,
+) -> Result<(StatusCode, Json<Category>), AppError> {
+    if let Some(name) = &payload.name
+        && state
+            .category_repository
+            .exists_by_name(claims.sub, name, Some(id))
+            .await?
+    {
+        return Err(AppError::Conflict("Category already exists".into()));
+    }
+
+    let category = state
+        .category_repository
+        .update_category(id, claims.sub, payload)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Category not found".into()))?;
+    Ok((StatusCode::OK, Json(category)))
+}
+
+pub async fn delete_category(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Query(query): Query<DeleteCategoryQuery>,
+) -> Result<StatusCode, AppError> {
+    let category = state
+        .category_repository
+        .find_category(id, claims.sub)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Category not found".into()))?;
+    if category.is_default {
+        return Err(AppError::Conflict(
+            "The fallback category cannot be deleted".into(),
+        ));
+    }
+
+    let reassign_to = match query.reassign_to {
+        Some(target) if target == id => {
+            return Err(AppError::BadRequest(
+                "Cannot reassign expenses to the deleted category".into(),
+            ));
+        }
+        Some(target) => state
+            .category_repository
+            .find_category(target, claims.sub)
+            .await?
+            .ok_or_else(|| AppError::BadRequest("Target category not found".into()))?,
+        None => state
+            .category_repository
+            .find_default_category(claims.sub)
+            .await?
+            .ok_or_else(|| AppError::Conflict("No fallback category configured".into()))?,
+    };
+
+    match state
+        .category_repository
+        .delete_category(id, reassign_to.id, claims.sub)
+        .await?
+    {
+        Some(_) => Ok(StatusCode::NO_CONTENT),
+        None => Ok(StatusCode::NOT_FOUND),
+    }
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/categories", get(get_categories).post(create_category))
+        .route(
+            "/categories/{id}",
+            put(update_category).delete(delete_category),
+        )
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Category {
+    pub id: i32,
+    pub name: String,
+    pub color: String,
+    pub icon: Option<String>,
+    pub is_default: bool,
+}
@@ -0,0 +1,239 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use sqlx::PgPool;
+
+use crate::expense::{repository::record_history_many, utils::HistoryAction};
+
+use super::{
+    models::Category,
+    utils::{CreateCategoryPayload, UpdateCategoryPayload},
+};
+
+pub struct CategoryRepository {
+    pool: Arc<PgPool>,
+}
+
+impl CategoryRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        CategoryRepository { pool }
+    }
+
+    pub async fn seed_defaults(&self, user_id: i32) -> anyhow::Result<()> {
+        sqlx::query!("SELECT seed_default_categories($1);", user_id)
+            .execute(&*self.pool)
+            .await
+            .map(|_| ())
+            .context(format!(
+                "Failed to seed categories for user_id: {}",
+                user_id
+            ))
+    }
+
+    pub async fn find_categories(&self, user_id: i32) -> anyhow::Result<Vec<Category>> {
+        sqlx::query_as!(
+            Category,
+            r#"
+            SELECT id, name, color, icon, is_default
+            FROM categories WHERE user_id = $1
+            ORDER BY is_default, name;
+            "#,
+            user_id
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get categories by user_id: {}", user_id))
+    }
+
+    pub async fn find_category(&self, id: i32, user_id: i32) -> anyhow::Result<Option<Category>> {
+        sqlx::query_as!(
+            Category,
+            r#"
+            SELECT id, name, color, icon, is_default
+            FROM categories WHERE id = $1 AND user_id = $2;
+            "#,
+            id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to get category by id: {}", id))
+    }
+
+    pub async fn exists_by_name(
+        &self,
+        user_id: i32,
+        name: &str,
+        exclude_id: Option<i32>,
+    ) -> anyhow::Result<bool> {
+        let exists = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM categories
+                WHERE user_id = $1 AND LOWER(name) = LOWER($2) AND id IS DISTINCT FROM $3
+            );
+            "#,
+            user_id,
+            name,
+            exclude_id
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context("Failed to check if category exists")?;
+
+        Ok(exists.unwrap_or(false))
+    }
+
+    pub async fn create_category(
+        &self,
+        payload: CreateCategoryPayload,
+        user_id: i32,
+    ) -> anyhow::Result<Category> {
+        sqlx::query_as!(
+            Category,
+            r#"
+    INSERT INTO categories (user_id, name, color, icon)
+    VALUES ($1, $2, $3, $4)
+    RETURNING id, name, color, icon, is_default;
+    "#,
+            user_id,
+            payload.name,
+            payload.color,
+            payload.icon,
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context("Failed to create category")
+    }
+
+    pub async fn update_category(
+        &self,
+        id: i32,
+        user_id: i32,
+        payload: UpdateCategoryPayload,
+    ) -> anyhow::Result<Option<Category>> {
+        sqlx::query_as!(
+            Category,
+            r#"
+    UPDATE categories
+    SET name = COALESCE($1, name),
+        color = COALESCE($2, color),
+        icon = COALESCE($3, icon)
+    WHERE id = $4 AND user_id = $5
+    RETURNING id, name, color, icon, is_default;
+    "#,
+            payload.name,
+            payload.color,
+            payload.icon,
+            id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context("Failed to update category")
+    }
+
+    /// Moves the category's expenses and recurring templates to `reassign_to`, then deletes it.
+    pub async fn delete_category(
+        &self,
+        id: i32,
+        reassign_to: i32,
+        user_id: i32,
+    ) -> anyhow::Result<Option<i32>> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to start transaction")?;
+
+        let (expense_ids, before): (Vec<i32>, Vec<serde_json::Value>) = sqlx::query!(
+            r#"
+    SELECT id, expense_snapshot(id) AS "before!"
+    FROM expenses
+    WHERE category_id = $1 AND user_id = $2
+    ORDER BY id
+    FOR UPDATE;
+    "#,
+            id,
+            user_id
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .context(format!("Failed to get expenses of category id: {}", id))?
+        .into_iter()
+        .map(|row| (row.id, row.before))
+        .unzip();
+        sqlx::query!(
+            "UPDATE expenses SET category_id = $1, version = version + 1 WHERE id = ANY($2);",
+            reassign_to,
+            &expense_ids
+        )
+        .execute(&mut *tx)
+        .await
+        .context(format!(
+            "Failed to reassign expenses of category id: {}",
+            id
+        ))?;
+        record_history_many(
+            &mut tx,
+            &expense_ids,
+            user_id,
+            HistoryAction::Update,
+            &before,
+        )
+        .await?;
+
+        sqlx::query!(
+            "UPDATE recurring_expenses SET category_id = $1 WHERE category_id = $2 AND user_id = $3;",
+            reassign_to,
+            id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await
+        .context(format!("Failed to reassign recurring expenses of category id: {}", id))?;
+
+        sqlx::query!(
+            "UPDATE expense_rules SET category_id = $1 WHERE category_id = $2 AND user_id = $3;",
+            reassign_to,
+            id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await
+        .context(format!("Failed to reassign rules of category id: {}", id))?;
+
+        let deleted = sqlx::query_scalar!(
+            "DELETE FROM categories WHERE id = $1 AND user_id = $2 AND NOT is_default RETURNING id;",
+            id,
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .context(format!("Failed to delete category by id: {}", id))?;
+        if deleted.is_none() {
+            // Dropping the transaction rolls the reassignment back
+            return Ok(None);
+        }
+
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(deleted)
+    }
+
+    pub async fn find_default_category(&self, user_id: i32) -> anyhow::Result<Option<Category>> {
+        sqlx::query_as!(
+            Category,
+            r#"
+            SELECT id, name, color, icon, is_default
+            FROM categories WHERE user_id = $1 AND is_default;
+            "#,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to get default category for user_id: {}",
+            user_id
+        ))
+    }
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateCategoryPayload {
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Name must be between 1 and 50 characters"
+    ))]
+    pub name: String,
+
+    #[validate(custom(function = "validate_color"))]
+    pub color: String,
+
+    #[validate(length(max = 50, message = "Icon too long"))]
+    pub icon: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct UpdateCategoryPayload {
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Name must be between 1 and 50 characters"
+    ))]
+    pub name: Option<String>,
+
+    #[validate(custom(function = "validate_color"))]
+    pub color: Option<String>,
+
+    #[validate(length(max = 50, message = "Icon too long"))]
+    pub icon: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteCategoryQuery {
+    /// Category receiving the expenses; defaults to the user's fallback category.
+    pub reassign_to: Option<i32>,
+}
+
+/// Accepts `#rrggbb` hex colors.
+fn validate_color(color: &str) -> Result<(), ValidationError> {
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if !valid {
+        return Err(ValidationError::new("invalid_color"));
+    }
+    Ok(())
+}
@@ -20,6 +20,9 @@ pub struct EnvConfig {
     smtp_server: Cow<'static, str>,
     frontend_url: Cow<'static, str>,
     backend_url: Cow<'static, str>,
+    exchange_rates_path: Option<Cow<'static, str>>,
+    attachments_dir: Cow<'static, str>,
+    trash_retention_days: i64,
 }
 
 impl EnvConfig {
@@ -51,7 +54,18 @@ impl EnvConfig {
             smtp_server: provider.get("SMTP_SERVER")?,
             frontend_url: provider.get("FRONTEND_URL")?,
             backend_url: provider.get("FRONTEND_URL")?,
-
+            exchange_rates_path: provider.get("EXCHANGE_RATES_PATH").ok(),
+            attachments_dir: provider
+                .get("ATTACHMENTS_DIR")
+                .unwrap_or(Cow::Borrowed("uploads")),
+            trash_retention_days: match provider.get("TRASH_RETENTION_DAYS") {
+                Ok(days) => days
+                    .parse::<i64>()
+                    .ok()
+                    .filter(|days| *days > 0)
+                    .ok_or(ConfigError::InvalidTrashRetentionDays)?,
+                Err(_) => 30,
+            },
         })
     }
 }
@@ -100,30 +114,39 @@ impl Config for EnvConfig {
     fn tls_key_path(&self) -> Option<&str> {
         self.tls_key_path.as_deref()
     }
-    
+
     fn jwt_secret(&self) -> &str {
         &self.jwt_secret
     }
-    
+
     fn jwt_expires_in(&self) -> i64 {
         self.jwt_expires_in
     }
-    
+
     fn jwt_maxage(&self) -> i64 {
         self.jwt_maxage
     }
-    
-    
+
     fn smtp_server(&self) -> &str {
         &self.smtp_server
     }
-    
-   
+
     fn frontend_url(&self) -> &str {
         &self.frontend_url
     }
-        fn backend_url(&self) -> &str {
+    fn backend_url(&self) -> &str {
         &self.backend_url
     }
 
+    fn exchange_rates_path(&self) -> Option<&str> {
+        self.exchange_rates_path.as_deref()
+    }
+
+    fn attachments_dir(&self) -> &str {
+        &self.attachments_dir
+    }
+
+    fn trash_retention_days(&self) -> i64 {
+        self.trash_retention_days
+    }
 }
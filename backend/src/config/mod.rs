@@ -19,6 +19,15 @@ pub trait Config {
     fn frontend_url(&self) -> &str;
     fn backend_url(&self) -> &str;
 
+    // Comma-separated ECB XML rate files loaded at startup
+    fn exchange_rates_path(&self) -> Option<&str>;
+
+    // Root directory of the local attachment storage
+    fn attachments_dir(&self) -> &str;
+
+    // Days a deleted expense stays in the trash before being purged
+    fn trash_retention_days(&self) -> i64;
+
     // TLS configuration methods
     fn tls_cert_path(&self) -> Option<&str>;
     fn tls_key_path(&self) -> Option<&str>;
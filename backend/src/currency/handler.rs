@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use axum::{Json, Router, extract::State, http::StatusCode, routing::get};
+
+use crate::{auth::token::claims::Claims, error::AppError, state::AppState};
+
+use super::models::Currency;
+
+pub async fn get_currencies(
+    _claims: Claims,
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Vec<Currency>>), AppError> {
+    let currencies = state.currency_repository.find_currencies().await?;
+    Ok((StatusCode::OK, Json(currencies)))
+}
+
+/// Rejects currencies without exchange rates, whose amounts could not be converted.
+pub(crate) async fn ensure_currency(state: &AppState, code: &str) -> Result<(), AppError> {
+    if !state.currency_repository.is_supported(code).await? {
+        return Err(AppError::BadRequest("Unsupported currency".into()));
+    }
+    Ok(())
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route("/currencies", get(get_currencies))
+}
@@ -0,0 +1,19 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// `1 EUR = rate currency` on `rate_date`.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ExchangeRate {
+    pub currency: String,
+    pub rate_date: NaiveDate,
+    pub rate: BigDecimal,
+}
+
+/// A supported currency with its latest euro rate; the euro itself has no rate date.
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Currency {
+    pub code: String,
+    pub rate: BigDecimal,
+    pub rate_date: Option<NaiveDate>,
+}
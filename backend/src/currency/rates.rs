@@ -0,0 +1,60 @@
+use std::str::FromStr;
+
+use anyhow::Context;
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+
+use super::{models::ExchangeRate, repository::CurrencyRepository};
+
+/// Parses an ECB reference rate file (`eurofxref-daily.xml`, `eurofxref-hist.xml`):
+///
+/// ```xml
+/// <Cube><Cube time="2025-01-02"><Cube currency="USD" rate="1.0321"/></Cube></Cube>
+/// ```
+///
+/// Any file in this layout works, which is how currencies the ECB does not
+/// publish (such as MUR) are added.
+pub fn parse_ecb_xml(xml: &str) -> anyhow::Result<Vec<ExchangeRate>> {
+    let document = roxmltree::Document::parse(xml).context("Invalid exchange rate XML")?;
+    let mut rates = Vec::new();
+
+    let days = document
+        .descendants()
+        .filter(|node| node.tag_name().name() == "Cube" && node.has_attribute("time"));
+    for day in days {
+        let time = day.attribute("time").unwrap_or_default();
+        let rate_date = NaiveDate::parse_from_str(time, "%Y-%m-%d")
+            .context(format!("Invalid rate date: {}", time))?;
+
+        for quote in day
+            .children()
+            .filter(|node| node.tag_name().name() == "Cube")
+        {
+            let (Some(currency), Some(rate)) =
+                (quote.attribute("currency"), quote.attribute("rate"))
+            else {
+                continue;
+            };
+            rates.push(ExchangeRate {
+                currency: currency.to_string(),
+                rate_date,
+                rate: BigDecimal::from_str(rate).context(format!("Invalid rate: {}", rate))?,
+            });
+        }
+    }
+
+    Ok(rates)
+}
+
+/// Loads each file of the comma-separated `paths` list into `exchange_rates`.
+pub async fn load_rate_files(repository: &CurrencyRepository, paths: &str) -> anyhow::Result<()> {
+    for path in paths.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let xml = tokio::fs::read_to_string(path)
+            .await
+            .context(format!("Failed to read exchange rate file: {}", path))?;
+        let rates = parse_ecb_xml(&xml).context(format!("Failed to parse {}", path))?;
+        let stored = repository.upsert_rates(&rates).await?;
+        tracing::info!("Loaded {stored} exchange rate(s) from {path}");
+    }
+    Ok(())
+}
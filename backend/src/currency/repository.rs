@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+use super::{
+    models::{Currency, ExchangeRate},
+    utils::DEFAULT_CURRENCY,
+};
+
+/// Rows sent per INSERT when loading a rate file.
+const UPSERT_CHUNK: usize = 5_000;
+
+pub struct CurrencyRepository {
+    pool: Arc<PgPool>,
+}
+
+impl CurrencyRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        CurrencyRepository { pool }
+    }
+
+    pub async fn find_currencies(&self) -> anyhow::Result<Vec<Currency>> {
+        sqlx::query_as!(
+            Currency,
+            r#"
+    SELECT $1::TEXT AS "code!", 1::NUMERIC AS "rate!", NULL::DATE AS rate_date
+    UNION ALL
+    SELECT code, rate, rate_date FROM (
+        SELECT DISTINCT ON (currency) currency::TEXT AS code, rate, rate_date
+        FROM exchange_rates
+        ORDER BY currency, rate_date DESC
+    ) latest;
+    "#,
+            DEFAULT_CURRENCY
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context("Failed to get currencies")
+    }
+
+    /// The euro and every currency with at least one known rate.
+    pub async fn is_supported(&self, code: &str) -> anyhow::Result<bool> {
+        let supported = sqlx::query_scalar!(
+            r#"SELECT $1 = $2 OR EXISTS(SELECT 1 FROM exchange_rates WHERE currency = $1) AS "supported!";"#,
+            code,
+            DEFAULT_CURRENCY
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context(format!("Failed to check currency: {}", code))?;
+        Ok(supported)
+    }
+
+    /// Inserts the rates, replacing the ones already stored for the same day.
+    pub async fn upsert_rates(&self, rates: &[ExchangeRate]) -> anyhow::Result<u64> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to start transaction")?;
+        let mut stored = 0;
+
+        for chunk in rates.chunks(UPSERT_CHUNK) {
+            let currencies: Vec<String> = chunk.iter().map(|r| r.currency.clone()).collect();
+            let dates: Vec<NaiveDate> = chunk.iter().map(|r| r.rate_date).collect();
+            let values: Vec<BigDecimal> = chunk.iter().map(|r| r.rate.clone()).collect();
+
+            let result = sqlx::query!(
+                r#"
+    INSERT INTO exchange_rates (currency, rate_date, rate)
+    SELECT * FROM UNNEST($1::TEXT[], $2::DATE[], $3::NUMERIC[])
+    ON CONFLICT (currency, rate_date) DO UPDATE SET rate = EXCLUDED.rate;
+    "#,
+                &currencies,
+                &dates,
+                &values
+            )
+            .execute(&mut *tx)
+            .await
+            .context("Failed to store exchange rates")?;
+            stored += result.rows_affected();
+        }
+
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(stored)
+    }
+}
@@ -0,0 +1,12 @@
+use validator::ValidationError;
+
+/// Every amount is stored in this currency unless told otherwise.
+pub const DEFAULT_CURRENCY: &str = "EUR";
+
+/// Accepts ISO 4217 style codes (`EUR`, `USD`, `MUR`).
+pub(crate) fn validate_currency(code: &str) -> Result<(), ValidationError> {
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_uppercase()) {
+        return Err(ValidationError::new("invalid_currency"));
+    }
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use bigdecimal::{BigDecimal, RoundingMode, Zero};
+
+use crate::budget::{repository::BudgetRepository, utils::month_start};
+
+use super::{
+    models::{Digest, DigestFrequency},
+    period::Period,
+    repository::DigestRepository,
+};
+
+/// Expenses listed in a digest.
+const TOP_EXPENSES: i64 = 5;
+
+/// Gathers the digest of `period` for the user.
+pub async fn build_digest(
+    digests: &DigestRepository,
+    budgets: &BudgetRepository,
+    user_id: i32,
+    currency: String,
+    frequency: DigestFrequency,
+    period: Period,
+) -> anyhow::Result<Digest> {
+    let previous = period.previous(frequency);
+    let categories = digests
+        .find_category_totals(user_id, previous.start, period.start, period.end)
+        .await?;
+    let top_expenses = digests
+        .find_top_expenses(user_id, period.start, period.end, TOP_EXPENSES)
+        .await?;
+    let budget_month = month_start(period.last_day());
+    let budgets = budgets.find_status(user_id, budget_month, None).await?;
+
+    let total: BigDecimal = categories.iter().map(|c| &c.total).sum();
+    let previous_total: BigDecimal = categories.iter().map(|c| &c.previous_total).sum();
+    let change = &total - &previous_total;
+    let change_percent = (!previous_total.is_zero()).then(|| {
+        (&change * BigDecimal::from(100) / &previous_total)
+            .with_scale_round(1, RoundingMode::HalfUp)
+    });
+
+    Ok(Digest {
+        frequency,
+        period_start: period.start,
+        period_end: period.last_day(),
+        currency,
+        total,
+        previous_total,
+        change,
+        change_percent,
+        categories,
+        top_expenses,
+        budget_month,
+        budgets,
+    })
+}
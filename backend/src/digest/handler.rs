@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use axum::{Json, Router, extract::State, http::StatusCode, routing::get};
+use chrono::Utc;
+
+use crate::{
+    auth::token::claims::Claims,
+    error::AppError,
+    state::AppState,
+    user::repository::UserRepository,
+    validation::{ValidatedJson, ValidatedQuery},
+};
+
+use super::{
+    builder::build_digest,
+    models::{Digest, DigestFrequency, DigestSubscription},
+    period::Period,
+    utils::{DigestPreviewQuery, DigestSubscriptionPayload},
+};
+
+pub async fn get_subscription(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<DigestSubscription>), AppError> {
+    let subscription = state
+        .digest_repository
+        .find_subscription(claims.sub)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Not subscribed to the digest".into()))?;
+    Ok((StatusCode::OK, Json(subscription)))
+}
+
+/// Opts in to the digest, or changes its frequency. The first digest is
+/// sent when the current period ends.
+pub async fn subscribe(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(payload): ValidatedJson<DigestSubscriptionPayload>,
+) -> Result<(StatusCode, Json<DigestSubscription>), AppError> {
+    let period = Period::last_complete(payload.frequency, Utc::now().date_naive());
+    let subscription = state
+        .digest_repository
+        .save_subscription(claims.sub, payload.frequency, period.start)
+        .await?;
+    Ok((StatusCode::OK, Json(subscription)))
+}
+
+pub async fn unsubscribe(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+) -> Result<StatusCode, AppError> {
+    match state
+        .digest_repository
+        .delete_subscription(claims.sub)
+        .await?
+    {
+        Some(_) => Ok(StatusCode::NO_CONTENT),
+        None => Ok(StatusCode::NOT_FOUND),
+    }
+}
+
+/// The digest of the last full period, as it would be emailed.
+pub async fn preview_digest(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedQuery(query): ValidatedQuery<DigestPreviewQuery>,
+) -> Result<(StatusCode, Json<Digest>), AppError> {
+    let user = state
+        .user_repository
+        .find_by_id(claims.sub)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".into()))?;
+    let frequency = match query.frequency {
+        Some(frequency) => frequency,
+        None => state
+            .digest_repository
+            .find_subscription(claims.sub)
+            .await?
+            .map_or(DigestFrequency::Monthly, |s| s.frequency),
+    };
+
+    let period = Period::last_complete(frequency, Utc::now().date_naive());
+    let digest = build_digest(
+        &state.digest_repository,
+        &state.budget_repository,
+        claims.sub,
+        user.base_currency,
+        frequency,
+        period,
+    )
+    .await?;
+    Ok((StatusCode::OK, Json(digest)))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route(
+            "/digest",
+            get(get_subscription).put(subscribe).delete(unsubscribe),
+        )
+        .route("/digest/preview", get(preview_digest))
+}
@@ -0,0 +1,8 @@
+pub mod builder;
+pub mod handler;
+pub mod models;
+pub mod period;
+pub mod render;
+pub mod repository;
+pub mod scheduler;
+pub mod utils;
@@ -0,0 +1,76 @@
+use bigdecimal::BigDecimal;
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+
+use crate::budget::models::BudgetStatus;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[sqlx(type_name = "digest_frequency", rename_all = "PascalCase")]
+pub enum DigestFrequency {
+    /// Monday to Sunday.
+    Weekly,
+    Monthly,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DigestSubscription {
+    pub frequency: DigestFrequency,
+    /// Start of the last period a digest was sent for.
+    pub last_period_start: Option<NaiveDate>,
+    pub created_at: NaiveDateTime,
+}
+
+/// A subscriber whose digest for the period has not been sent yet.
+#[derive(Debug)]
+pub struct DigestRecipient {
+    pub user_id: i32,
+    pub name: String,
+    pub email: String,
+    pub base_currency: String,
+    /// Last period sent, restored when this one cannot be built.
+    pub last_period_start: Option<NaiveDate>,
+}
+
+/// Spending of a past week or month compared with the one before, in the
+/// user's base currency. Expenses without a known rate are left out.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Digest {
+    pub frequency: DigestFrequency,
+    pub period_start: NaiveDate,
+    /// Last day of the period.
+    pub period_end: NaiveDate,
+    pub currency: String,
+    pub total: BigDecimal,
+    pub previous_total: BigDecimal,
+    /// `total` minus `previous_total`.
+    pub change: BigDecimal,
+    /// Change in percent of the previous total; `None` when it was zero.
+    pub change_percent: Option<BigDecimal>,
+    /// Categories spent in during either period, largest total first.
+    pub categories: Vec<CategoryDigest>,
+    /// Largest expenses of the period.
+    pub top_expenses: Vec<TopExpense>,
+    /// Budgets against the month the period ends in.
+    pub budget_month: NaiveDate,
+    pub budgets: Vec<BudgetStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CategoryDigest {
+    pub category_id: i32,
+    pub category_name: String,
+    pub total: BigDecimal,
+    pub previous_total: BigDecimal,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TopExpense {
+    pub id: i32,
+    pub description: Option<String>,
+    pub category_name: String,
+    pub amount: BigDecimal,
+    pub currency: String,
+    pub amount_in_base: BigDecimal,
+    pub expense_date: NaiveDateTime,
+}
@@ -0,0 +1,47 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
+
+use crate::budget::utils::month_start;
+
+use super::models::DigestFrequency;
+
+/// Start and end (exclusive) of a digest period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Period {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl Period {
+    /// The last full week (Monday to Sunday) or month before `today`.
+    pub fn last_complete(frequency: DigestFrequency, today: NaiveDate) -> Self {
+        let end = match frequency {
+            DigestFrequency::Weekly => {
+                today - Days::new(today.weekday().num_days_from_monday().into())
+            }
+            DigestFrequency::Monthly => month_start(today),
+        };
+        Self {
+            start: Self::step_back(frequency, end),
+            end,
+        }
+    }
+
+    /// The period just before this one.
+    pub fn previous(&self, frequency: DigestFrequency) -> Self {
+        Self {
+            start: Self::step_back(frequency, self.start),
+            end: self.start,
+        }
+    }
+
+    pub fn last_day(&self) -> NaiveDate {
+        self.end - Days::new(1)
+    }
+
+    fn step_back(frequency: DigestFrequency, date: NaiveDate) -> NaiveDate {
+        match frequency {
+            DigestFrequency::Weekly => date - Days::new(7),
+            DigestFrequency::Monthly => date - Months::new(1),
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use std::fmt::Write;
+
+use bigdecimal::{BigDecimal, Signed};
+use chrono::{Datelike, NaiveDate};
+
+use crate::email::escape;
+
+use super::models::{Digest, DigestFrequency};
+
+const MONTHS: [&str; 12] = [
+    "janvier",
+    "février",
+    "mars",
+    "avril",
+    "mai",
+    "juin",
+    "juillet",
+    "août",
+    "septembre",
+    "octobre",
+    "novembre",
+    "décembre",
+];
+
+pub fn subject(digest: &Digest) -> String {
+    format!("Vos dépenses {} - Papang", period_label(digest))
+}
+
+/// Plain-text alternative of `html`.
+pub fn text(name: &str, digest: &Digest) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "Bonjour {},\n", name);
+    let _ = writeln!(
+        out,
+        "Vos dépenses {}: {}",
+        period_label(digest),
+        money(&digest.total, &digest.currency)
+    );
+    let _ = writeln!(out, "{}\n", change_label(digest));
+
+    if !digest.categories.is_empty() {
+        let _ = writeln!(out, "Par catégorie:");
+        for category in &digest.categories {
+            let _ = writeln!(
+                out,
+                "- {}: {} (précédemment {})",
+                category.category_name,
+                money(&category.total, &digest.currency),
+                money(&category.previous_total, &digest.currency)
+            );
+        }
+        out.push('\n');
+    }
+
+    if !digest.top_expenses.is_empty() {
+        let _ = writeln!(out, "Plus grosses dépenses:");
+        for expense in &digest.top_expenses {
+            let _ = writeln!(
+                out,
+                "- {} {} ({}): {}",
+                expense.expense_date.format("%d/%m/%Y"),
+                expense.description.as_deref().unwrap_or("Sans description"),
+                expense.category_name,
+                money(&expense.amount, &expense.currency)
+            );
+        }
+        out.push('\n');
+    }
+
+    if !digest.budgets.is_empty() {
+        let _ = writeln!(out, "Budgets de {}:", month_label(digest));
+        for budget in &digest.budgets {
+            let _ = writeln!(
+                out,
+                "- {}: {} sur {} ({}%)",
+                budget.category_name,
+                money(&budget.spent, &digest.currency),
+                money(&budget.amount, &digest.currency),
+                budget.percent_used
+            );
+        }
+        out.push('\n');
+    }
+
+    out.push_str(UNSUBSCRIBE);
+    out.push('\n');
+    out
+}
+
+pub fn html(name: &str, digest: &Digest) -> String {
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        r#"
+            <html>
+            <body>
+                <h2>Vos dépenses {}</h2>
+                <p>Bonjour {},</p>
+                <p>Vous avez dépensé <strong>{}</strong>. {}</p>
+            "#,
+        period_label(digest),
+        escape(name),
+        money(&digest.total, &digest.currency),
+        change_label(digest)
+    );
+
+    if !digest.categories.is_empty() {
+        out.push_str(
+            r#"<h3>Par catégorie</h3>
+                <table cellpadding="4" style="border-collapse: collapse;">
+                    <tr><th align="left">Catégorie</th><th align="right">Dépensé</th><th align="right">Précédemment</th></tr>"#,
+        );
+        for category in &digest.categories {
+            let _ = write!(
+                out,
+                r#"<tr><td>{}</td><td align="right">{}</td><td align="right">{}</td></tr>"#,
+                escape(&category.category_name),
+                money(&category.total, &digest.currency),
+                money(&category.previous_total, &digest.currency)
+            );
+        }
+        out.push_str("</table>");
+    }
+
+    if !digest.top_expenses.is_empty() {
+        out.push_str("<h3>Plus grosses dépenses</h3><ol>");
+        for expense in &digest.top_expenses {
+            let _ = write!(
+                out,
+                "<li>{} {} ({}): <strong>{}</strong></li>",
+                expense.expense_date.format("%d/%m/%Y"),
+                escape(expense.description.as_deref().unwrap_or("Sans description")),
+                escape(&expense.category_name),
+                money(&expense.amount, &expense.currency)
+            );
+        }
+        out.push_str("</ol>");
+    }
+
+    if !digest.budgets.is_empty() {
+        let _ = write!(out, "<h3>Budgets de {}</h3><ul>", month_label(digest));
+        for budget in &digest.budgets {
+            let color = if budget.remaining.is_negative() {
+                "#dc3545"
+            } else {
+                "inherit"
+            };
+            let _ = write!(
+                out,
+                r#"<li style="color: {};">{}: {} sur {} ({}%)</li>"#,
+                color,
+                escape(&budget.category_name),
+                money(&budget.spent, &digest.currency),
+                money(&budget.amount, &digest.currency),
+                budget.percent_used
+            );
+        }
+        out.push_str("</ul>");
+    }
+
+    let _ = write!(
+        out,
+        r#"
+                <p style="color: #6c757d; font-size: small;">{}</p>
+            </body>
+            </html>
+            "#,
+        UNSUBSCRIBE
+    );
+    out
+}
+
+const UNSUBSCRIBE: &str = "Vous recevez ce résumé car vous l'avez activé. Vous pouvez le désactiver à tout moment depuis votre profil.";
+
+fn period_label(digest: &Digest) -> String {
+    match digest.frequency {
+        DigestFrequency::Weekly => format!(
+            "de la semaine du {} au {}",
+            digest.period_start.format("%d/%m/%Y"),
+            digest.period_end.format("%d/%m/%Y")
+        ),
+        DigestFrequency::Monthly => format!("de {}", month_label(digest)),
+    }
+}
+
+fn month_label(digest: &Digest) -> String {
+    month_name(digest.budget_month)
+}
+
+/// "octobre 2026".
+pub(crate) fn month_name(date: NaiveDate) -> String {
+    format!("{} {}", MONTHS[date.month0() as usize], date.year())
+}
+
+fn change_label(digest: &Digest) -> String {
+    let previous = money(&digest.previous_total, &digest.currency);
+    match &digest.change_percent {
+        Some(percent) if percent.is_positive() => {
+            format!(
+                "C'est {}% de plus que la période précédente ({}).",
+                percent, previous
+            )
+        }
+        Some(percent) if percent.is_negative() => {
+            format!(
+                "C'est {}% de moins que la période précédente ({}).",
+                percent.abs(),
+                previous
+            )
+        }
+        Some(_) => format!("Autant que la période précédente ({}).", previous),
+        None => "Aucune dépense la période précédente.".to_string(),
+    }
+}
+
+fn money(amount: &BigDecimal, currency: &str) -> String {
+    format!("{} {}", amount.with_scale(2), currency)
+}
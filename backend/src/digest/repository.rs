@@ -0,0 +1,252 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+use super::models::{
+    CategoryDigest, DigestFrequency, DigestRecipient, DigestSubscription, TopExpense,
+};
+
+pub struct DigestRepository {
+    pool: Arc<PgPool>,
+}
+
+// Amounts are converted to the base currency like summaries; periods are
+// bounded by `[from, to)` dates.
+impl DigestRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        DigestRepository { pool }
+    }
+
+    pub async fn find_subscription(
+        &self,
+        user_id: i32,
+    ) -> anyhow::Result<Option<DigestSubscription>> {
+        sqlx::query_as!(
+            DigestSubscription,
+            r#"
+    SELECT frequency AS "frequency: _", last_period_start, created_at
+    FROM digest_subscriptions
+    WHERE user_id = $1;
+    "#,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to get digest subscription by user_id: {}",
+            user_id
+        ))
+    }
+
+    /// Subscribes the user or changes the frequency. `last_period_start` is
+    /// the period counted as already sent, so the first digest is for the
+    /// next one.
+    pub async fn save_subscription(
+        &self,
+        user_id: i32,
+        frequency: DigestFrequency,
+        last_period_start: NaiveDate,
+    ) -> anyhow::Result<DigestSubscription> {
+        sqlx::query_as!(
+            DigestSubscription,
+            r#"
+    INSERT INTO digest_subscriptions (user_id, frequency, last_period_start)
+    VALUES ($1, $2, $3)
+    ON CONFLICT (user_id) DO UPDATE
+    SET frequency = EXCLUDED.frequency,
+        last_period_start = CASE
+            WHEN digest_subscriptions.frequency = EXCLUDED.frequency
+            THEN digest_subscriptions.last_period_start
+            ELSE EXCLUDED.last_period_start
+        END
+    RETURNING frequency AS "frequency: _", last_period_start, created_at;
+    "#,
+            user_id,
+            frequency as _,
+            last_period_start
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to save digest subscription by user_id: {}",
+            user_id
+        ))
+    }
+
+    pub async fn delete_subscription(&self, user_id: i32) -> anyhow::Result<Option<i32>> {
+        sqlx::query_scalar!(
+            r#"
+    DELETE FROM digest_subscriptions
+    WHERE user_id = $1
+    RETURNING user_id;
+    "#,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to delete digest subscription by user_id: {}",
+            user_id
+        ))
+    }
+
+    /// Subscribers at this frequency not sent the period starting at
+    /// `period_start` yet.
+    pub async fn find_due(
+        &self,
+        frequency: DigestFrequency,
+        period_start: NaiveDate,
+    ) -> anyhow::Result<Vec<DigestRecipient>> {
+        sqlx::query_as!(
+            DigestRecipient,
+            r#"
+    SELECT u.id AS user_id, u.name, u.email, u.base_currency, s.last_period_start
+    FROM digest_subscriptions s
+    JOIN users u ON u.id = s.user_id
+    WHERE s.frequency = $1 AND (s.last_period_start IS NULL OR s.last_period_start < $2)
+    ORDER BY u.id;
+    "#,
+            frequency as _,
+            period_start
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context("Failed to get due digests")
+    }
+
+    /// Marks the period as sent; false when it already was, or the user
+    /// opted out or changed frequency meanwhile.
+    pub async fn claim_period(
+        &self,
+        user_id: i32,
+        frequency: DigestFrequency,
+        period_start: NaiveDate,
+    ) -> anyhow::Result<bool> {
+        let updated = sqlx::query!(
+            r#"
+    UPDATE digest_subscriptions
+    SET last_period_start = $3
+    WHERE user_id = $1 AND frequency = $2
+      AND (last_period_start IS NULL OR last_period_start < $3);
+    "#,
+            user_id,
+            frequency as _,
+            period_start
+        )
+        .execute(&*self.pool)
+        .await
+        .context(format!("Failed to claim digest of user_id: {}", user_id))?;
+        Ok(updated.rows_affected() > 0)
+    }
+
+    /// Gives back a claimed period so that the next run tries it again.
+    pub async fn release_period(
+        &self,
+        user_id: i32,
+        frequency: DigestFrequency,
+        period_start: NaiveDate,
+        last_period_start: Option<NaiveDate>,
+    ) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+    UPDATE digest_subscriptions
+    SET last_period_start = $4
+    WHERE user_id = $1 AND frequency = $2 AND last_period_start = $3;
+    "#,
+            user_id,
+            frequency as _,
+            period_start,
+            last_period_start
+        )
+        .execute(&*self.pool)
+        .await
+        .context(format!("Failed to release digest of user_id: {}", user_id))?;
+        Ok(())
+    }
+
+    /// Totals per category over `[from, to)` and the period `[previous_from,
+    /// from)` before it.
+    pub async fn find_category_totals(
+        &self,
+        user_id: i32,
+        previous_from: NaiveDate,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> anyhow::Result<Vec<CategoryDigest>> {
+        sqlx::query_as!(
+            CategoryDigest,
+            r#"
+    WITH converted AS (
+        SELECT category_id, expense_date,
+               convert_to_base(amount, currency, user_id, expense_date::DATE) AS amount
+        FROM expenses
+        WHERE user_id = $1 AND deleted_at IS NULL
+          AND expense_date >= $2::DATE AND expense_date < $4::DATE
+    ),
+    totals AS (
+        SELECT category_id,
+               COALESCE(SUM(amount) FILTER (WHERE expense_date >= $3::DATE), 0) AS total,
+               COALESCE(SUM(amount) FILTER (WHERE expense_date < $3::DATE), 0) AS previous_total
+        FROM converted
+        GROUP BY category_id
+    )
+    SELECT t.category_id AS "category_id!",
+           c.name AS category_name,
+           t.total AS "total!",
+           t.previous_total AS "previous_total!"
+    FROM totals t
+    JOIN categories c ON c.id = t.category_id
+    ORDER BY t.total DESC, t.previous_total DESC, c.name;
+    "#,
+            user_id,
+            previous_from,
+            from,
+            to
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to get digest totals by user_id: {}",
+            user_id
+        ))
+    }
+
+    /// Largest expenses over `[from, to)` in the base currency.
+    pub async fn find_top_expenses(
+        &self,
+        user_id: i32,
+        from: NaiveDate,
+        to: NaiveDate,
+        limit: i64,
+    ) -> anyhow::Result<Vec<TopExpense>> {
+        sqlx::query_as!(
+            TopExpense,
+            r#"
+    SELECT id AS "id!", description, category_name AS "category_name!", amount AS "amount!",
+           currency AS "currency!", amount_in_base AS "amount_in_base!",
+           expense_date AS "expense_date!"
+    FROM (
+        SELECT e.id, e.description, c.name AS category_name, e.amount, e.currency,
+               convert_to_base(e.amount, e.currency, e.user_id, e.expense_date::DATE) AS amount_in_base,
+               e.expense_date
+        FROM expenses e
+        JOIN categories c ON c.id = e.category_id
+        WHERE e.user_id = $1 AND e.deleted_at IS NULL
+          AND e.expense_date >= $2::DATE AND e.expense_date < $3::DATE
+    ) converted
+    WHERE amount_in_base IS NOT NULL
+    ORDER BY amount_in_base DESC, expense_date DESC, id DESC
+    LIMIT $4;
+    "#,
+            user_id,
+            from,
+            to,
+            limit
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get top expenses by user_id: {}", user_id))
+    }
+}
@@ -0,0 +1,206 @@
+use std::time::Duration;
+
+use anyhow::Context;
+use chrono::{NaiveDate, Utc};
+
+use crate::{budget::repository::BudgetRepository, email::EmailService};
+
+use super::{
+    builder::build_digest,
+    models::{Digest, DigestFrequency},
+    period::Period,
+    repository::DigestRepository,
+};
+
+const SEND_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Sends the digests of the periods that just ended at startup, then every
+/// hour, once per subscriber and period.
+pub fn spawn_digest_sender(
+    digests: DigestRepository,
+    budgets: BudgetRepository,
+    email_service: EmailService,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SEND_INTERVAL);
+        loop {
+            interval.tick().await;
+            let today = Utc::now().date_naive();
+            for frequency in [DigestFrequency::Weekly, DigestFrequency::Monthly] {
+                match send_due(&digests, &budgets, &email_service, frequency, today).await {
+                    Ok(0) => {}
+                    Ok(sent) => tracing::info!("Sent {sent} {frequency:?} digest(s)"),
+                    Err(e) => tracing::error!("Failed to send {frequency:?} digests: {:?}", e),
+                }
+            }
+        }
+    });
+}
+
+async fn send_due(
+    digests: &DigestRepository,
+    budgets: &BudgetRepository,
+    email_service: &EmailService,
+    frequency: DigestFrequency,
+    today: NaiveDate,
+) -> anyhow::Result<usize> {
+    let period = Period::last_complete(frequency, today);
+    let mut sent = 0;
+    for recipient in digests.find_due(frequency, period.start).await? {
+        // Claimed first so that overlapping runs never send it twice
+        if !digests
+            .claim_period(recipient.user_id, frequency, period.start)
+            .await?
+        {
+            continue;
+        }
+        let delivery = deliver(
+            build_digest(
+                digests,
+                budgets,
+                recipient.user_id,
+                recipient.base_currency.clone(),
+                frequency,
+                period,
+            ),
+            async |digest| {
+                email_service
+                    .send_digest_email(&recipient.email, &recipient.name, digest)
+                    .await
+            },
+            async || {
+                digests
+                    .release_period(
+                        recipient.user_id,
+                        frequency,
+                        period.start,
+                        recipient.last_period_start,
+                    )
+                    .await
+            },
+        )
+        .await;
+        match delivery {
+            Delivery::Sent => sent += 1,
+            Delivery::Empty => {}
+            Delivery::Failed(e) => {
+                tracing::error!(
+                    "Failed to send digest to user_id {}: {:?}",
+                    recipient.user_id,
+                    e
+                )
+            }
+        }
+    }
+    Ok(sent)
+}
+
+/// Outcome of a claimed digest.
+#[derive(Debug)]
+enum Delivery {
+    Sent,
+    /// Nothing to tell without any expense in either period.
+    Empty,
+    /// The claim was released so that the next run tries again.
+    Failed(anyhow::Error),
+}
+
+/// Builds and sends one claimed digest, releasing the claim when either step
+/// fails.
+async fn deliver(
+    build: impl Future<Output = anyhow::Result<Digest>>,
+    send: impl AsyncFnOnce(&Digest) -> anyhow::Result<()>,
+    release: impl AsyncFnOnce() -> anyhow::Result<()>,
+) -> Delivery {
+    let result = match build.await {
+        Ok(digest) if digest.categories.is_empty() => return Delivery::Empty,
+        Ok(digest) => send(&digest).await.context("Failed to send digest"),
+        Err(e) => Err(e.context("Failed to build digest")),
+    };
+    match result {
+        Ok(()) => Delivery::Sent,
+        Err(e) => {
+            if let Err(release_error) = release().await {
+                tracing::error!("{:?}", release_error);
+            }
+            Delivery::Failed(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use bigdecimal::BigDecimal;
+
+    use super::*;
+    use crate::digest::models::CategoryDigest;
+
+    fn digest(categories: usize) -> Digest {
+        let day = NaiveDate::from_ymd_opt(2026, 9, 30).unwrap();
+        Digest {
+            frequency: DigestFrequency::Monthly,
+            period_start: day,
+            period_end: day,
+            currency: "EUR".into(),
+            total: BigDecimal::from(10),
+            previous_total: BigDecimal::from(0),
+            change: BigDecimal::from(10),
+            change_percent: None,
+            categories: (0..categories)
+                .map(|i| CategoryDigest {
+                    category_id: i as i32,
+                    category_name: "Courses".into(),
+                    total: BigDecimal::from(10),
+                    previous_total: BigDecimal::from(0),
+                })
+                .collect(),
+            top_expenses: Vec::new(),
+            budget_month: day,
+            budgets: Vec::new(),
+        }
+    }
+
+    async fn run(built: anyhow::Result<Digest>, sent: anyhow::Result<()>) -> (Delivery, bool) {
+        let released = Cell::new(false);
+        let delivery = deliver(
+            async { built },
+            async |_| sent,
+            async || {
+                released.set(true);
+                Ok(())
+            },
+        )
+        .await;
+        (delivery, released.get())
+    }
+
+    #[tokio::test]
+    async fn keeps_the_claim_of_a_sent_digest() {
+        let (delivery, released) = run(Ok(digest(1)), Ok(())).await;
+        assert!(matches!(delivery, Delivery::Sent));
+        assert!(!released);
+    }
+
+    #[tokio::test]
+    async fn keeps_the_claim_of_an_empty_digest() {
+        let (delivery, released) = run(Ok(digest(0)), Err(anyhow::anyhow!("not sent"))).await;
+        assert!(matches!(delivery, Delivery::Empty));
+        assert!(!released);
+    }
+
+    #[tokio::test]
+    async fn releases_the_claim_when_the_email_fails() {
+        let (delivery, released) = run(Ok(digest(1)), Err(anyhow::anyhow!("SMTP down"))).await;
+        assert!(matches!(delivery, Delivery::Failed(_)));
+        assert!(released);
+    }
+
+    #[tokio::test]
+    async fn releases_the_claim_when_building_fails() {
+        let (delivery, released) = run(Err(anyhow::anyhow!("database down")), Ok(())).await;
+        assert!(matches!(delivery, Delivery::Failed(_)));
+        assert!(released);
+    }
+}
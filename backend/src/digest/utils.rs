@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use super::models::DigestFrequency;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct DigestSubscriptionPayload {
+    pub frequency: DigestFrequency,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct DigestPreviewQuery {
+    /// Defaults to the subscription's frequency, else monthly.
+    pub frequency: Option<DigestFrequency>,
+}
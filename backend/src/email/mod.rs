@@ -1,8 +1,13 @@
 use anyhow::Result;
 use lettre::{
-    message::header::ContentType,
-    transport::smtp::authentication::Credentials,
     AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{MultiPart, header::ContentType},
+    transport::smtp::authentication::Credentials,
+};
+
+use crate::{
+    budget::models::BudgetStatus,
+    digest::{models::Digest, render},
 };
 
 pub struct EmailService {
@@ -22,10 +27,18 @@ impl EmailService {
         Self { mailer, from_email }
     }
 
-    pub async fn send_verification_email(&self, to_email: &str, verification_token: &str) -> Result<()> {
-        let backend_url = std::env::var("BACKEND_URL").unwrap_or_else(|_| "http://localhost:3001".to_string());
-        let verification_url = format!("{}/auth/verify-email?token={}", backend_url, verification_token);
-        
+    pub async fn send_verification_email(
+        &self,
+        to_email: &str,
+        verification_token: &str,
+    ) -> Result<()> {
+        let backend_url =
+            std::env::var("BACKEND_URL").unwrap_or_else(|_| "http://localhost:3001".to_string());
+        let verification_url = format!(
+            "{}/auth/verify-email?token={}",
+            backend_url, verification_token
+        );
+
         let email = Message::builder()
             .from(self.from_email.parse().unwrap())
             .to(to_email.parse().unwrap())
@@ -58,9 +71,10 @@ impl EmailService {
         to_email: &str,
         reset_token: &str,
     ) -> Result<(), anyhow::Error> {
-        let backend_url = std::env::var("BACKEND_URL").unwrap_or_else(|_| "http://localhost:3001".to_string());
+        let backend_url =
+            std::env::var("BACKEND_URL").unwrap_or_else(|_| "http://localhost:3001".to_string());
         let reset_url = format!("{}/auth/reset-password?token={}", backend_url, reset_token);
-        
+
         let subject = "Demande de réinitialisation de mot de passe - Papang";
         let body = format!(
             r#"
@@ -91,4 +105,110 @@ impl EmailService {
         self.mailer.send(email).await?;
         Ok(())
     }
+
+    pub async fn send_budget_alert_email(
+        &self,
+        to_email: &str,
+        status: &BudgetStatus,
+        threshold: i16,
+    ) -> Result<()> {
+        let subject = if threshold >= 100 {
+            format!("Budget {} dépassé - Papang", status.category_name)
+        } else {
+            format!(
+                "Budget {} atteint à {}% - Papang",
+                status.category_name, threshold
+            )
+        };
+        let body = format!(
+            r#"
+            <html>
+            <body>
+                <h2>{}</h2>
+                <p>Vous avez dépensé <strong>{} €</strong> sur un budget mensuel de <strong>{} €</strong> pour la catégorie {} ({}%).</p>
+                <p>Reste disponible ce mois-ci: {} €</p>
+            </body>
+            </html>
+            "#,
+            escape(&subject),
+            status.spent,
+            status.amount,
+            escape(&status.category_name),
+            status.percent_used,
+            status.remaining
+        );
+
+        let email = Message::builder()
+            .from(self.from_email.parse().unwrap())
+            .to(to_email.parse()?)
+            .subject(subject)
+            .header(ContentType::TEXT_HTML)
+            .body(body)?;
+
+        self.mailer.send(email).await?;
+        Ok(())
+    }
+
+    pub async fn send_group_invitation_email(
+        &self,
+        to_email: &str,
+        inviter_name: &str,
+        group_name: &str,
+        frontend_url: &str,
+    ) -> Result<()> {
+        let subject = format!("Invitation au groupe {} - Papang", group_name);
+        let body = format!(
+            r#"
+            <html>
+            <body>
+                <h2>{}</h2>
+                <p>{} vous invite à rejoindre le groupe <strong>{}</strong> pour partager vos dépenses.</p>
+                <p>Connectez-vous ou créez un compte avec cette adresse email pour accepter l'invitation:</p>
+                <a href="{}" style="background-color: #007bff; color: white; padding: 10px 20px; text-decoration: none; border-radius: 5px;">
+                    Ouvrir Papang
+                </a>
+            </body>
+            </html>
+            "#,
+            subject, inviter_name, group_name, frontend_url
+        );
+
+        let email = Message::builder()
+            .from(self.from_email.parse().unwrap())
+            .to(to_email.parse()?)
+            .subject(subject)
+            .header(ContentType::TEXT_HTML)
+            .body(body)?;
+
+        self.mailer.send(email).await?;
+        Ok(())
+    }
+
+    /// Sends the digest as HTML with a plain-text alternative.
+    pub async fn send_digest_email(
+        &self,
+        to_email: &str,
+        name: &str,
+        digest: &Digest,
+    ) -> Result<()> {
+        let email = Message::builder()
+            .from(self.from_email.parse().unwrap())
+            .to(to_email.parse()?)
+            .subject(render::subject(digest))
+            .multipart(MultiPart::alternative_plain_html(
+                render::text(name, digest),
+                render::html(name, digest),
+            ))?;
+
+        self.mailer.send(email).await?;
+        Ok(())
+    }
+}
+
+/// Escapes user-provided text for an HTML email body.
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
 }
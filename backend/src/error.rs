@@ -1,7 +1,10 @@
 use axum::{
     Json,
-    extract::rejection::JsonRejection,
-    http::StatusCode,
+    extract::{
+        multipart::MultipartError,
+        rejection::{JsonRejection, QueryRejection},
+    },
+    http::{StatusCode, header::ETAG},
     response::{IntoResponse, Response},
 };
 use serde_json::json;
@@ -24,6 +27,14 @@ pub enum AppError {
     #[error("Conflict: {0}")]
     Conflict(String),
 
+    /// The resource changed since the client read it; answered with its
+    /// current representation and `ETag`.
+    #[error("Precondition Failed: resource changed, now at {etag}")]
+    PreconditionFailed {
+        etag: String,
+        current: serde_json::Value,
+    },
+
     #[error("Internal Server Error: {0}")]
     InternalServerError(#[from] anyhow::Error),
 
@@ -32,7 +43,12 @@ pub enum AppError {
 
     #[error(transparent)]
     AxumJsonRejection(#[from] JsonRejection),
-    
+
+    #[error(transparent)]
+    AxumQueryRejection(#[from] QueryRejection),
+
+    #[error(transparent)]
+    AxumMultipartError(#[from] MultipartError),
 }
 
 impl IntoResponse for AppError {
@@ -47,6 +63,12 @@ impl IntoResponse for AppError {
                 }));
                 (StatusCode::BAD_REQUEST, body).into_response()
             }
+            AppError::PreconditionFailed { etag, current } => (
+                StatusCode::PRECONDITION_FAILED,
+                [(ETAG, etag.clone())],
+                Json(current.clone()),
+            )
+                .into_response(),
             _ => {
                 let (status, error_message) = match &self {
                     AppError::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
@@ -57,8 +79,12 @@ impl IntoResponse for AppError {
                         StatusCode::INTERNAL_SERVER_ERROR,
                         &"An internal server error occurred.".to_string(),
                     ),
-                    AppError::ValidationError(_) => unreachable!(),
+                    AppError::ValidationError(_) | AppError::PreconditionFailed { .. } => {
+                        unreachable!()
+                    }
                     AppError::AxumJsonRejection(_) => (StatusCode::BAD_REQUEST, &self.to_string()),
+                    AppError::AxumQueryRejection(_) => (StatusCode::BAD_REQUEST, &self.to_string()),
+                    AppError::AxumMultipartError(err) => (err.status(), &err.body_text()),
                 };
                 let body = Json(json!({ "error": error_message }));
                 (status, body).into_response()
@@ -2,84 +2,551 @@ use std::sync::Arc;
 
 use axum::{
     Json, Router,
-    extract::{Path, Query, State},
-    http::StatusCode,
-    routing::{delete, post},
+    extract::{Path, State},
+    http::{HeaderName, StatusCode, header::ETAG},
+    routing::{delete, get, post},
 };
+use bigdecimal::BigDecimal;
+use validator::Validate;
 
 use crate::{
-    auth::token::claims::Claims, error::AppError, state::AppState, validation::ValidatedJson,
+    account::handler::check_account,
+    anomaly::handler::check_expense,
+    attachment::handler::remove_files,
+    auth::token::claims::Claims,
+    budget,
+    currency::handler::ensure_currency,
+    error::AppError,
+    rule::{
+        engine::{RuleSet, merge_tags},
+        handler::load_rules,
+    },
+    state::AppState,
+    tag::utils::normalize_tags,
+    validation::{ValidatedJson, ValidatedQuery},
 };
 
 use super::{
-    models::{Expense, ExpenseCategory},
-    utils::{CreateExpensePayload, UpdateExpensePayload},
+    models::{
+        BatchItemResult, BatchOutcome, BatchResponse, CreatedExpense, Expense, ExpenseHistoryEntry,
+        ExpensePage, GuardedChange, TrashedExpense,
+    },
+    utils::{
+        BatchMode, BatchOperation, BatchPayload, CreateExpensePayload, ExpenseCursor,
+        ExpenseListQuery, IfMatch, PatchExpensePayload, UpdateExpensePayload, etag,
+    },
 };
 
+/// A single expense with its `ETag`.
+type TaggedExpense = (StatusCode, [(HeaderName, String); 1], Json<Expense>);
+
 pub async fn create_expense_handler(
     claims: Claims,
     State(state): State<Arc<AppState>>,
-    ValidatedJson(payload): ValidatedJson<CreateExpensePayload>,
-) -> Result<(StatusCode, Json<Expense>), AppError> {
+    ValidatedJson(mut payload): ValidatedJson<CreateExpensePayload>,
+) -> Result<(StatusCode, [(HeaderName, String); 1], Json<CreatedExpense>), AppError> {
+    let rules = load_rules(&state, claims.sub).await?;
+    apply_rules(&state, claims.sub, &rules, &mut payload).await?;
+    if let Some(currency) = &payload.currency {
+        ensure_currency(&state, currency).await?;
+    }
+    check_account(
+        &state,
+        claims.sub,
+        payload.account_id,
+        payload.currency.as_deref(),
+    )
+    .await?;
     let expense = state
         .expense_repository
         .create_expense(payload, claims.sub)
         .await?;
-    Ok((StatusCode::CREATED, Json(expense)))
-}
+    spawn_budget_alerts(&state, claims.sub, &expense);
+    // The expense is saved either way
+    let anomaly = check_expense(&state, claims.sub, &expense)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::error!("Failed to check expense for anomalies: {:?}", e);
+            None
+        });
 
-#[derive(serde::Deserialize)]
-pub struct ExpenseCategoryQuery {
-    pub category: Option<ExpenseCategory>,
+    Ok((
+        StatusCode::CREATED,
+        [(ETAG, etag(expense.version))],
+        Json(CreatedExpense { expense, anomaly }),
+    ))
 }
 
-pub async fn get_expenses(
+/// Creates, updates and deletes many expenses in one transaction, with a
+/// result per operation in request order.
+pub async fn batch_expenses(
     claims: Claims,
     State(state): State<Arc<AppState>>,
-    Query(query): Query<ExpenseCategoryQuery>,
-) -> Result<(StatusCode, Json<Vec<Expense>>), AppError> {
-    let expenses = if let Some(category) = query.category {
-        state
+    ValidatedJson(payload): ValidatedJson<BatchPayload>,
+) -> Result<(StatusCode, Json<BatchResponse>), AppError> {
+    let atomic = payload.mode == BatchMode::Atomic;
+    let mut results: Vec<Option<BatchItemResult>> = Vec::with_capacity(payload.operations.len());
+    let mut indices = Vec::new();
+    let mut operations = Vec::new();
+    let rules = load_rules(&state, claims.sub).await?;
+    for (index, mut operation) in payload.operations.into_iter().enumerate() {
+        match check_operation(&state, claims.sub, &rules, &mut operation).await {
+            Ok(()) => {
+                results.push(None);
+                indices.push(index);
+                operations.push(operation);
+            }
+            Err(e) => results.push(Some(failed_item(index, e))),
+        }
+    }
+
+    let rejected = results.iter().any(Option::is_some);
+    let committed = if atomic && rejected {
+        false
+    } else {
+        let (committed, outcomes) = state
             .expense_repository
-            .find_expenses_by_category(claims.sub, category)
-            .await?
+            .apply_batch(claims.sub, &operations, atomic)
+            .await?;
+        for (index, outcome) in indices.into_iter().zip(outcomes) {
+            let item = match outcome {
+                Ok(BatchOutcome::Created(expense)) => {
+                    if committed {
+                        spawn_budget_alerts(&state, claims.sub, &expense);
+                    }
+                    applied_item(index, StatusCode::CREATED, Some(expense), None)
+                }
+                Ok(BatchOutcome::Updated(expense)) => {
+                    applied_item(index, StatusCode::OK, Some(expense), None)
+                }
+                Ok(BatchOutcome::Deleted(id)) => {
+                    applied_item(index, StatusCode::NO_CONTENT, None, Some(id))
+                }
+                Ok(BatchOutcome::NotFound) => {
+                    failed_item(index, AppError::NotFound("Expense not found".into()))
+                }
+                Err(e) => failed_item(index, AppError::InternalServerError(e)),
+            };
+            results[index] = Some(item);
+        }
+        committed
+    };
+
+    let results = results
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| match item {
+            // Succeeded, or was never tried, in a batch that was rolled back
+            Some(item) if committed || item.error.is_some() => item,
+            _ => BatchItemResult {
+                index,
+                status: StatusCode::FAILED_DEPENDENCY.as_u16(),
+                expense: None,
+                id: None,
+                error: Some("Not applied because another operation failed".into()),
+            },
+        })
+        .collect();
+    let status = if committed {
+        StatusCode::OK
     } else {
-        state.expense_repository.find_expenses(claims.sub).await?
+        StatusCode::UNPROCESSABLE_ENTITY
     };
+    Ok((status, Json(BatchResponse { committed, results })))
+}
 
-    Ok((StatusCode::OK, Json(expenses)))
+/// Checks a batch operation the way its single-expense endpoint would, and
+/// runs the rules on the expenses it creates.
+async fn check_operation(
+    state: &AppState,
+    user_id: i32,
+    rules: &RuleSet,
+    operation: &mut BatchOperation,
+) -> Result<(), AppError> {
+    match operation {
+        BatchOperation::Create(payload) => {
+            payload.validate()?;
+            apply_rules(state, user_id, rules, payload).await?;
+            if let Some(currency) = &payload.currency {
+                ensure_currency(state, currency).await?;
+            }
+            check_account(
+                state,
+                user_id,
+                payload.account_id,
+                payload.currency.as_deref(),
+            )
+            .await?;
+        }
+        BatchOperation::Update { id, changes } => {
+            changes.validate()?;
+            check_changes(state, user_id, *id, changes).await?;
+        }
+        BatchOperation::Delete { .. } => {}
+    }
+    Ok(())
+}
+
+/// Adds the tags of the rules matching a new expense. Its category, when not
+/// given, is the one the rules set, or else the user's default category.
+async fn apply_rules(
+    state: &AppState,
+    user_id: i32,
+    rules: &RuleSet,
+    payload: &mut CreateExpensePayload,
+) -> Result<(), AppError> {
+    let matched = rules.evaluate(payload.description.as_deref(), &payload.amount);
+    if !matched.tags.is_empty() {
+        let tags = normalize_tags(payload.tags.as_deref().unwrap_or_default());
+        payload.tags = Some(merge_tags(&tags, &matched.tags));
+    }
+
+    let category_id = match payload.category_id.or(matched.category_id) {
+        Some(category_id) => category_id,
+        None => {
+            state
+                .category_repository
+                .find_default_category(user_id)
+                .await?
+                .ok_or_else(|| AppError::BadRequest("A category is required".into()))?
+                .id
+        }
+    };
+    ensure_category(state, category_id, user_id).await?;
+    payload.category_id = Some(category_id);
+    Ok(())
+}
+
+fn applied_item(
+    index: usize,
+    status: StatusCode,
+    expense: Option<Expense>,
+    id: Option<i32>,
+) -> BatchItemResult {
+    BatchItemResult {
+        index,
+        status: status.as_u16(),
+        expense,
+        id,
+        error: None,
+    }
+}
+
+/// Same status and message as the error would get as a response.
+fn failed_item(index: usize, error: AppError) -> BatchItemResult {
+    let (status, message) = match error {
+        AppError::ValidationError(errors) => (
+            StatusCode::BAD_REQUEST,
+            format!("Validation failed: {}", errors),
+        ),
+        AppError::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+        AppError::NotFound(message) => (StatusCode::NOT_FOUND, message),
+        AppError::Conflict(message) => (StatusCode::CONFLICT, message),
+        error => {
+            tracing::error!("Batch operation failed: {}", error);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "An internal server error occurred.".to_string(),
+            )
+        }
+    };
+    BatchItemResult {
+        index,
+        status: status.as_u16(),
+        expense: None,
+        id: None,
+        error: Some(message),
+    }
+}
+
+/// Budget alerts are emailed in the background so they never fail the request.
+fn spawn_budget_alerts(state: &Arc<AppState>, user_id: i32, expense: &Expense) {
+    let alert_state = state.clone();
+    let (category_id, date) = (expense.category_id, expense.expense_date.date());
+    tokio::spawn(async move {
+        if let Err(e) =
+            budget::alerts::notify_thresholds(&alert_state, user_id, category_id, date).await
+        {
+            tracing::error!("Failed to send budget alert: {:?}", e);
+        }
+    });
+}
+
+pub async fn get_expenses(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedQuery(query): ValidatedQuery<ExpenseListQuery>,
+) -> Result<(StatusCode, Json<ExpensePage>), AppError> {
+    let cursor = match query.cursor.as_deref() {
+        Some(raw) => Some(
+            ExpenseCursor::decode(raw, query.sort())
+                .ok_or_else(|| AppError::BadRequest("Invalid cursor".into()))?,
+        ),
+        None => None,
+    };
+
+    let page = state
+        .expense_repository
+        .find_expenses(claims.sub, &query, cursor.as_ref())
+        .await?;
+
+    Ok((StatusCode::OK, Json(page)))
+}
+
+pub async fn get_expense(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<TaggedExpense, AppError> {
+    let expense = ensure_expense(&state, id, claims.sub).await?;
+    Ok(tagged(StatusCode::OK, expense))
 }
 
 pub async fn delete_expense(
     claims: Claims,
     State(state): State<Arc<AppState>>,
     Path(id): Path<i32>,
+    IfMatch(version): IfMatch,
 ) -> Result<StatusCode, AppError> {
+    // Only moves it to the trash, attachments are kept until it is purged
     match state
         .expense_repository
-        .delete_expense(id, claims.sub)
+        .delete_expense(id, claims.sub, version)
         .await?
     {
-        Some(_) => Ok(StatusCode::NO_CONTENT),
-        None => Ok(StatusCode::NOT_FOUND),
+        GuardedChange::Applied(_) => Ok(StatusCode::NO_CONTENT),
+        GuardedChange::NotFound => Ok(StatusCode::NOT_FOUND),
+        GuardedChange::Stale(current) => Err(precondition_failed(current)),
     }
 }
 
+pub async fn get_trash(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Vec<TrashedExpense>>), AppError> {
+    let expenses = state
+        .expense_repository
+        .find_trash(claims.sub, state.trash_retention_days)
+        .await?;
+    Ok((StatusCode::OK, Json(expenses)))
+}
+
+pub async fn restore_expense(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<TaggedExpense, AppError> {
+    let expense = state
+        .expense_repository
+        .restore_expense(id, claims.sub)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Expense not found in the trash".into()))?;
+    Ok(tagged(StatusCode::OK, expense))
+}
+
+pub async fn purge_expense(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    let purged = state
+        .expense_repository
+        .purge_expense(id, claims.sub)
+        .await?;
+    if purged.count == 0 {
+        return Ok(StatusCode::NOT_FOUND);
+    }
+    remove_files(&state, &purged.storage_keys).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn empty_trash(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+) -> Result<StatusCode, AppError> {
+    let purged = state.expense_repository.purge_trash(claims.sub).await?;
+    remove_files(&state, &purged.storage_keys).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
 pub async fn update_expense(
     claims: Claims,
     State(state): State<Arc<AppState>>,
+    IfMatch(version): IfMatch,
     ValidatedJson(payload): ValidatedJson<UpdateExpensePayload>,
 ) -> Result<(StatusCode, Json<Option<Expense>>), AppError> {
+    let (id, changes) = payload.into_patch();
+    check_changes(&state, claims.sub, id, &changes).await?;
+    match state
+        .expense_repository
+        .update_expense(id, claims.sub, &changes, version)
+        .await?
+    {
+        GuardedChange::Applied(v) => Ok((StatusCode::OK, Json(Some(v)))),
+        GuardedChange::NotFound => Ok((StatusCode::NOT_FOUND, Json(None))),
+        GuardedChange::Stale(current) => Err(precondition_failed(current)),
+    }
+}
+
+pub async fn patch_expense(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    IfMatch(version): IfMatch,
+    ValidatedJson(changes): ValidatedJson<PatchExpensePayload>,
+) -> Result<TaggedExpense, AppError> {
+    check_changes(&state, claims.sub, id, &changes).await?;
     match state
         .expense_repository
-        .update_expense(claims.sub, payload)
+        .update_expense(id, claims.sub, &changes, version)
         .await?
     {
-        Some(v) => Ok((StatusCode::OK, Json(Some(v)))),
-        None => Ok((StatusCode::NOT_FOUND, Json(None))),
+        GuardedChange::Applied(expense) => Ok(tagged(StatusCode::OK, expense)),
+        GuardedChange::NotFound => Err(AppError::NotFound("Expense not found".into())),
+        GuardedChange::Stale(current) => Err(precondition_failed(current)),
     }
 }
 
+/// Checks the references of an update; a missing expense is left to the
+/// update itself.
+async fn check_changes(
+    state: &AppState,
+    user_id: i32,
+    id: i32,
+    changes: &PatchExpensePayload,
+) -> Result<(), AppError> {
+    if let Some(category_id) = changes.category_id {
+        ensure_category(state, category_id, user_id).await?;
+    }
+    if let Some(currency) = &changes.currency {
+        ensure_currency(state, currency).await?;
+    }
+    let expense = state.expense_repository.find_expense(id, user_id).await?;
+    if let Some(expense) = &expense {
+        ensure_split_unchanged(
+            expense,
+            changes.amount.as_ref(),
+            changes.currency.as_deref(),
+        )?;
+    }
+
+    let account_id = match changes.account_id {
+        Some(account_id) => account_id,
+        None => expense.as_ref().and_then(|e| e.account_id),
+    };
+    let currency = changes
+        .currency
+        .as_deref()
+        .or(expense.as_ref().map(|e| e.currency.as_str()));
+    check_account(state, user_id, account_id, currency).await
+}
+
+pub async fn get_history(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<Vec<ExpenseHistoryEntry>>), AppError> {
+    let history = state
+        .expense_repository
+        .find_history(id, claims.sub)
+        .await?;
+    // Expenses recorded before history was kept have none until first edited
+    if history.is_empty() {
+        ensure_expense(&state, id, claims.sub).await?;
+    }
+    Ok((StatusCode::OK, Json(history)))
+}
+
+/// Brings the expense back to the values it had right after `entry_id`.
+pub async fn revert_expense(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path((id, entry_id)): Path<(i32, i32)>,
+    IfMatch(version): IfMatch,
+) -> Result<TaggedExpense, AppError> {
+    let expense = ensure_expense(&state, id, claims.sub).await?;
+    let entry = state
+        .expense_repository
+        .find_history_entry(entry_id, id, claims.sub)
+        .await?
+        .ok_or_else(|| AppError::NotFound("History entry not found".into()))?;
+    let Some(snapshot) = entry.after.map(|after| after.0) else {
+        return Err(AppError::BadRequest(
+            "A deletion cannot be reverted, restore the expense from the trash".into(),
+        ));
+    };
+    ensure_category(&state, snapshot.category_id, claims.sub).await?;
+    ensure_split_unchanged(&expense, Some(&snapshot.amount), Some(&snapshot.currency))?;
+
+    match state
+        .expense_repository
+        .revert_expense(id, claims.sub, &snapshot, version)
+        .await?
+    {
+        GuardedChange::Applied(expense) => Ok(tagged(StatusCode::OK, expense)),
+        GuardedChange::NotFound => Err(AppError::NotFound("Expense not found".into())),
+        GuardedChange::Stale(current) => Err(precondition_failed(current)),
+    }
+}
+
+fn tagged(status: StatusCode, expense: Expense) -> TaggedExpense {
+    (status, [(ETAG, etag(expense.version))], Json(expense))
+}
+
+/// Answers a change made against an outdated version with the current one.
+fn precondition_failed(current: Box<Expense>) -> AppError {
+    match serde_json::to_value(&current) {
+        Ok(value) => AppError::PreconditionFailed {
+            etag: etag(current.version),
+            current: value,
+        },
+        Err(e) => AppError::InternalServerError(e.into()),
+    }
+}
+
+/// Shares were computed from the amount, so a split expense keeps it.
+fn ensure_split_unchanged(
+    expense: &Expense,
+    amount: Option<&BigDecimal>,
+    currency: Option<&str>,
+) -> Result<(), AppError> {
+    let amount_changed = amount.is_some_and(|amount| *amount != expense.amount);
+    let currency_changed = currency.is_some_and(|currency| currency != expense.currency);
+    if expense.group_id.is_some() && (amount_changed || currency_changed) {
+        return Err(AppError::Conflict(
+            "Remove the split before changing the amount or currency".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Rejects expense ids that do not belong to the user.
+pub(crate) async fn ensure_expense(
+    state: &AppState,
+    id: i32,
+    user_id: i32,
+) -> Result<Expense, AppError> {
+    state
+        .expense_repository
+        .find_expense(id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Expense not found".into()))
+}
+
+/// Rejects category ids that do not belong to the user.
+pub(crate) async fn ensure_category(
+    state: &AppState,
+    category_id: i32,
+    user_id: i32,
+) -> Result<(), AppError> {
+    state
+        .category_repository
+        .find_category(category_id, user_id)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("Category not found".into()))?;
+    Ok(())
+}
+
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route(
@@ -88,5 +555,17 @@ pub fn router() -> Router<Arc<AppState>> {
                 .get(get_expenses)
                 .put(update_expense),
         )
-        .route("/expenses/{id}", delete(delete_expense))
+        .route("/expenses/batch", post(batch_expenses))
+        .route(
+            "/expenses/{id}",
+            get(get_expense).patch(patch_expense).delete(delete_expense),
+        )
+        .route("/expenses/{id}/restore", post(restore_expense))
+        .route("/expenses/{id}/history", get(get_history))
+        .route(
+            "/expenses/{id}/history/{entry_id}/revert",
+            post(revert_expense),
+        )
+        .route("/expenses/trash", get(get_trash).delete(empty_trash))
+        .route("/expenses/trash/{id}", delete(purge_expense))
 }
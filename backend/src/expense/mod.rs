@@ -1,4 +1,5 @@
 pub mod handler;
 pub mod models;
 pub mod repository;
+pub mod scheduler;
 pub mod utils;
@@ -1,25 +1,151 @@
 use bigdecimal::BigDecimal;
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
-use sqlx::Type;
+use sqlx::types::Json;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
-#[sqlx(type_name = "expense_category", rename_all = "PascalCase")]
-pub enum ExpenseCategory {
-    Groceries,
-    Leisure,
-    Electronics,
-    Utilities,
-    Clothing,
-    Health,
-    Others,
-}
+use crate::anomaly::models::Anomaly;
 
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Expense {
     pub id: i32,
-    pub category: ExpenseCategory,
+    pub category_id: i32,
+    pub amount: BigDecimal,
+    pub currency: String,
+    /// `amount` in the user's base currency at the rate of `expense_date`;
+    /// `None` when no rate is known.
+    pub amount_in_base: Option<BigDecimal>,
+    pub description: Option<String>,
+    pub expense_date: NaiveDateTime,
+    /// Account the expense was paid from.
+    pub account_id: Option<i32>,
+    /// Group the expense is split in, if any.
+    pub group_id: Option<i32>,
+    /// Sorted tag names.
+    pub tags: Vec<String>,
+    /// Bumped on every change; sent as the `ETag` of the expense.
+    pub version: i32,
+    /// HTML-escaped description with the words matching `q` wrapped in
+    /// `<mark>`; only set when searching.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight: Option<String>,
+    /// Search relevance, kept for the pagination cursor.
+    #[serde(skip)]
+    pub rank: Option<f32>,
+}
+
+/// A new expense, with the anomaly raised if its amount stood out.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatedExpense {
+    #[serde(flatten)]
+    pub expense: Expense,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anomaly: Option<Anomaly>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExpensePage {
+    pub items: Vec<Expense>,
+    pub next_cursor: Option<String>,
+}
+
+/// An expense waiting in the trash.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TrashedExpense {
+    pub id: i32,
+    pub category_id: i32,
     pub amount: BigDecimal,
+    pub currency: String,
     pub description: Option<String>,
     pub expense_date: NaiveDateTime,
+    pub tags: Vec<String>,
+    pub deleted_at: NaiveDateTime,
+    /// When the expense will be deleted for good.
+    pub purge_at: NaiveDateTime,
+}
+
+/// Outcome of a permanent deletion.
+#[derive(Debug)]
+pub struct PurgedExpenses {
+    pub count: i64,
+    /// Attachment files left to remove from the storage.
+    pub storage_keys: Vec<String>,
+}
+
+/// Values of an expense at one point of its history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpenseSnapshot {
+    pub category_id: i32,
+    pub amount: BigDecimal,
+    pub currency: String,
+    pub description: Option<String>,
+    pub expense_date: NaiveDateTime,
+    /// Missing from entries recorded before accounts existed.
+    #[serde(default)]
+    pub account_id: Option<i32>,
+    /// Group the expense was split in. A revert keeps the current split.
+    #[serde(default)]
+    pub group_id: Option<i32>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExpenseHistoryEntry {
+    pub id: i32,
+    pub expense_id: i32,
+    /// `create`, `update`, `delete`, `restore` or `revert`.
+    pub action: String,
+    /// `None` once the user who made the change is deleted.
+    pub actor_id: Option<i32>,
+    pub actor_name: Option<String>,
+    /// `None` for a creation or a restoration from the trash.
+    pub before: Option<Json<ExpenseSnapshot>>,
+    /// `None` for a deletion.
+    pub after: Option<Json<ExpenseSnapshot>>,
+    pub changed_at: NaiveDateTime,
+}
+
+/// Outcome of a change made against the version the client last saw.
+#[derive(Debug)]
+pub enum GuardedChange<T> {
+    Applied(T),
+    NotFound,
+    /// The expense changed since; holds its current values.
+    Stale(Box<Expense>),
+}
+
+/// What a batch operation did.
+#[derive(Debug)]
+pub enum BatchOutcome {
+    Created(Expense),
+    Updated(Expense),
+    /// Id of the expense moved to the trash.
+    Deleted(i32),
+    NotFound,
+}
+
+impl BatchOutcome {
+    pub fn is_applied(&self) -> bool {
+        !matches!(self, BatchOutcome::NotFound)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchItemResult {
+    /// Position of the operation in the request.
+    pub index: usize,
+    /// HTTP status the operation would have had on its own.
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expense: Option<Expense>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchResponse {
+    /// `false` when an atomic batch was rolled back; nothing was saved.
+    pub committed: bool,
+    pub results: Vec<BatchItemResult>,
 }
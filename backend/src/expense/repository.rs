@@ -1,11 +1,22 @@
 use std::sync::Arc;
 
 use anyhow::Context;
-use sqlx::PgPool;
+use chrono::{Duration, NaiveDateTime, Utc};
+use futures::TryStreamExt;
+use sqlx::{Acquire, PgExecutor, PgPool, Postgres, QueryBuilder, Transaction, types::Json};
+use tokio::sync::mpsc;
+
+use crate::{export::models::ExportRow, tag::utils::normalize_tags};
 
 use super::{
-    models::{Expense, ExpenseCategory},
-    utils::{CreateExpensePayload, UpdateExpensePayload},
+    models::{
+        BatchOutcome, Expense, ExpenseHistoryEntry, ExpensePage, ExpenseSnapshot, GuardedChange,
+        PurgedExpenses, TrashedExpense,
+    },
+    utils::{
+        BatchOperation, CreateExpensePayload, CursorValue, ExpenseCursor, ExpenseListQuery,
+        ExpenseSort, HistoryAction, PatchExpensePayload, SortOrder, TagMatch,
+    },
 };
 
 pub struct ExpenseRepository {
@@ -21,93 +32,809 @@ impl ExpenseRepository {
         payload: CreateExpensePayload,
         user_id: i32,
     ) -> anyhow::Result<Expense> {
-        sqlx::query_as!(
-            Expense,
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to start transaction")?;
+        let expense = insert_expense(&mut tx, &payload, user_id).await?;
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(expense)
+    }
+
+    pub async fn find_expenses(
+        &self,
+        user_id: i32,
+        query: &ExpenseListQuery,
+        cursor: Option<&ExpenseCursor>,
+    ) -> anyhow::Result<ExpensePage> {
+        let limit = query.limit();
+        let sort = query.sort();
+        let sort_column = sort_column(sort);
+        let (comparison, direction) = match query.order {
+            SortOrder::Asc => (">", "ASC"),
+            SortOrder::Desc => ("<", "DESC"),
+        };
+
+        // The filtered rows are wrapped so the cursor can compare on `rank`
+        let mut builder = QueryBuilder::<Postgres>::new(
+            "SELECT * FROM (SELECT id, category_id, amount, currency, \
+             convert_to_base(amount, currency, user_id, expense_date::DATE) AS amount_in_base, \
+             description, expense_date, account_id, group_id, expense_tag_names(id) AS tags, \
+             version",
+        );
+        push_search_columns(&mut builder, query);
+        builder.push(" FROM expenses WHERE deleted_at IS NULL AND user_id = ");
+        builder.push_bind(user_id);
+        push_expense_filters(&mut builder, query);
+        builder.push(") AS e");
+
+        if let Some(cursor) = cursor {
+            builder.push(format!(" WHERE ({sort_column}, id) {comparison} ("));
+            match &cursor.value {
+                CursorValue::Date(date) => builder.push_bind(*date),
+                CursorValue::Amount(amount) => builder.push_bind(amount.clone()),
+                CursorValue::Rank(rank) => builder.push_bind(*rank),
+            };
+            builder.push(", ").push_bind(cursor.id).push(")");
+        }
+
+        builder.push(format!(
+            " ORDER BY {sort_column} {direction}, id {direction} LIMIT "
+        ));
+        // One extra row tells us whether another page exists.
+        builder.push_bind(limit + 1);
+
+        let mut items = builder
+            .build_query_as::<Expense>()
+            .fetch_all(&*self.pool)
+            .await
+            .context(format!("Failed to get expenses by user_id: {}", user_id))?;
+
+        let next_cursor = if items.len() as i64 > limit {
+            items.truncate(limit as usize);
+            items
+                .last()
+                .map(|last| ExpenseCursor::from_expense(last, sort).encode())
+        } else {
+            None
+        };
+
+        Ok(ExpensePage { items, next_cursor })
+    }
+
+    /// Sends every expense matching the list filters, in list order, to `tx`
+    /// as it is read. Pagination fields of `query` are ignored. Stops early
+    /// when the receiver is dropped.
+    pub async fn export_expenses(
+        &self,
+        user_id: i32,
+        query: &ExpenseListQuery,
+        tx: &mpsc::Sender<anyhow::Result<ExportRow>>,
+    ) -> anyhow::Result<()> {
+        let sort_column = sort_column(query.sort());
+        let direction = match query.order {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        };
+
+        let mut builder = QueryBuilder::<Postgres>::new(
+            "SELECT id, expense_date, \
+             (SELECT name FROM categories c WHERE c.id = category_id) AS category, \
+             description, amount, currency, \
+             convert_to_base(amount, currency, user_id, expense_date::DATE) AS amount_in_base",
+        );
+        push_search_columns(&mut builder, query);
+        builder.push(" FROM expenses WHERE deleted_at IS NULL AND user_id = ");
+        builder.push_bind(user_id);
+        push_expense_filters(&mut builder, query);
+        builder.push(format!(
+            " ORDER BY {sort_column} {direction}, id {direction}"
+        ));
+
+        let mut rows = builder.build_query_as::<ExportRow>().fetch(&*self.pool);
+        while let Some(row) = rows
+            .try_next()
+            .await
+            .context(format!("Failed to export expenses by user_id: {}", user_id))?
+        {
+            if tx.send(Ok(row)).await.is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn find_expense(&self, id: i32, user_id: i32) -> anyhow::Result<Option<Expense>> {
+        fetch_expense(&*self.pool, id, user_id).await
+    }
+
+    /// Moves the expense to the trash, unless it is no longer at `version`.
+    pub async fn delete_expense(
+        &self,
+        id: i32,
+        user_id: i32,
+        version: Option<i32>,
+    ) -> anyhow::Result<GuardedChange<i32>> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to start transaction")?;
+        if let Some(current) = find_stale(&mut tx, id, user_id, version).await? {
+            return Ok(GuardedChange::Stale(Box::new(current)));
+        }
+        let deleted = trash_expense(&mut tx, id, user_id).await?;
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(deleted.map_or(GuardedChange::NotFound, GuardedChange::Applied))
+    }
+
+    /// Puts back every value of `snapshot`, tags included, unless the
+    /// expense is no longer at `version`.
+    pub async fn revert_expense(
+        &self,
+        id: i32,
+        user_id: i32,
+        snapshot: &ExpenseSnapshot,
+        version: Option<i32>,
+    ) -> anyhow::Result<GuardedChange<Expense>> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to start transaction")?;
+        if let Some(current) = find_stale(&mut tx, id, user_id, version).await? {
+            return Ok(GuardedChange::Stale(Box::new(current)));
+        }
+        let Some(before) = lock_snapshot(&mut tx, id, user_id).await? else {
+            return Ok(GuardedChange::NotFound);
+        };
+        sqlx::query!(
             r#"
-    INSERT INTO expenses (user_id, category, amount, description)
-    VALUES ($1, $2, $3, $4)
-    RETURNING id, category AS "category: _", amount, description, expense_date;
+    UPDATE expenses
+    SET category_id = $1, amount = $2, currency = $3, description = $4, expense_date = $5,
+        account_id = (SELECT id FROM accounts WHERE id = $6),
+        version = version + 1
+    WHERE id = $7;
     "#,
-            user_id,
-            payload.category as ExpenseCategory,
-            payload.amount,
-            payload.description,
+            snapshot.category_id,
+            snapshot.amount,
+            snapshot.currency,
+            snapshot.description,
+            snapshot.expense_date,
+            snapshot.account_id,
+            id
         )
-        .fetch_one(&*self.pool)
+        .execute(&mut *tx)
         .await
-        .context("Failed to create expense")
+        .context(format!("Failed to revert expense by id: {}", id))?;
+
+        set_tags(&mut tx, id, user_id, &normalize_tags(&snapshot.tags)).await?;
+        record_history(&mut tx, id, user_id, HistoryAction::Revert, Some(before)).await?;
+        let expense = fetch_expense(&mut *tx, id, user_id).await?;
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(expense.map_or(GuardedChange::NotFound, GuardedChange::Applied))
     }
 
-    pub async fn find_expenses(&self, user_id: i32) -> anyhow::Result<Vec<Expense>> {
+    /// Changes of an expense, trashed or not, oldest first.
+    pub async fn find_history(
+        &self,
+        expense_id: i32,
+        user_id: i32,
+    ) -> anyhow::Result<Vec<ExpenseHistoryEntry>> {
         sqlx::query_as!(
-            Expense,
+            ExpenseHistoryEntry,
             r#"
-            SELECT id, category AS "category: _", amount, description, expense_date 
-            FROM expenses WHERE user_id = $1;
-            "#,
+    SELECT h.id, h.expense_id, h.action, h.actor_id, u.name AS "actor_name?",
+           h.before AS "before: Json<ExpenseSnapshot>",
+           h.after AS "after: Json<ExpenseSnapshot>",
+           h.changed_at
+    FROM expense_history h
+    JOIN expenses e ON e.id = h.expense_id
+    LEFT JOIN users u ON u.id = h.actor_id
+    WHERE h.expense_id = $1 AND e.user_id = $2
+    ORDER BY h.id;
+    "#,
+            expense_id,
             user_id
         )
         .fetch_all(&*self.pool)
         .await
-        .context(format!("Failed to get expense by user_id: {}", user_id))
+        .context(format!(
+            "Failed to get history of expense id: {}",
+            expense_id
+        ))
     }
 
-    pub async fn delete_expense(&self, id: i32, user_id: i32) -> anyhow::Result<Option<i32>> {
-        sqlx::query_scalar!(
-            "DELETE FROM expenses WHERE id = $1 AND user_id = $2 RETURNING id;",
+    pub async fn find_history_entry(
+        &self,
+        id: i32,
+        expense_id: i32,
+        user_id: i32,
+    ) -> anyhow::Result<Option<ExpenseHistoryEntry>> {
+        sqlx::query_as!(
+            ExpenseHistoryEntry,
+            r#"
+    SELECT h.id, h.expense_id, h.action, h.actor_id, u.name AS "actor_name?",
+           h.before AS "before: Json<ExpenseSnapshot>",
+           h.after AS "after: Json<ExpenseSnapshot>",
+           h.changed_at
+    FROM expense_history h
+    JOIN expenses e ON e.id = h.expense_id
+    LEFT JOIN users u ON u.id = h.actor_id
+    WHERE h.id = $1 AND h.expense_id = $2 AND e.user_id = $3;
+    "#,
             id,
+            expense_id,
             user_id
         )
         .fetch_optional(&*self.pool)
         .await
-        .context(format!("Failed to delete expense by id: {}", id))
+        .context(format!("Failed to get history entry by id: {}", id))
     }
 
-    pub async fn find_expenses_by_category(
+    /// Trashed expenses, most recently deleted first.
+    pub async fn find_trash(
         &self,
         user_id: i32,
-        category: ExpenseCategory,
-    ) -> anyhow::Result<Vec<Expense>> {
+        retention_days: i64,
+    ) -> anyhow::Result<Vec<TrashedExpense>> {
         sqlx::query_as!(
-            Expense,
+            TrashedExpense,
             r#"
-            SELECT id, category AS "category: _", amount, description, expense_date 
-            FROM expenses WHERE user_id = $1 AND category = $2
-            "#,
+    SELECT id, category_id, amount, currency, description, expense_date,
+           expense_tag_names(id) AS "tags!",
+           deleted_at AS "deleted_at!",
+           deleted_at + make_interval(days => $2::INTEGER) AS "purge_at!"
+    FROM expenses
+    WHERE user_id = $1 AND deleted_at IS NOT NULL
+    ORDER BY deleted_at DESC, id DESC;
+    "#,
             user_id,
-            category as ExpenseCategory
+            retention_days as i32
         )
         .fetch_all(&*self.pool)
         .await
-        .context(format!(
-            "Failed to get expenses with user_id {} and category {:?} ",
-            user_id, category
-        ))
+        .context(format!("Failed to get trash by user_id: {}", user_id))
     }
 
-    pub async fn update_expense(
+    pub async fn restore_expense(&self, id: i32, user_id: i32) -> anyhow::Result<Option<Expense>> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to start transaction")?;
+        let restored = sqlx::query_scalar!(
+            r#"
+    UPDATE expenses SET deleted_at = NULL, version = version + 1
+    WHERE id = $1 AND user_id = $2 AND deleted_at IS NOT NULL
+    RETURNING id;
+    "#,
+            id,
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .context(format!("Failed to restore expense by id: {}", id))?;
+        let Some(id) = restored else {
+            return Ok(None);
+        };
+
+        record_history(&mut tx, id, user_id, HistoryAction::Restore, None).await?;
+        let expense = fetch_expense(&mut *tx, id, user_id).await?;
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(expense)
+    }
+
+    /// Deletes one trashed expense for good.
+    pub async fn purge_expense(&self, id: i32, user_id: i32) -> anyhow::Result<PurgedExpenses> {
+        self.purge(Some(user_id), Some(id), None).await
+    }
+
+    /// Empties the user's trash.
+    pub async fn purge_trash(&self, user_id: i32) -> anyhow::Result<PurgedExpenses> {
+        self.purge(Some(user_id), None, None).await
+    }
+
+    /// Deletes for good every expense trashed more than `retention_days` ago.
+    pub async fn purge_expired(&self, retention_days: i64) -> anyhow::Result<PurgedExpenses> {
+        let deleted_before = Utc::now().naive_utc() - Duration::days(retention_days);
+        self.purge(None, None, Some(deleted_before)).await
+    }
+
+    async fn purge(
         &self,
-        user_id: i32,
-        payload: UpdateExpensePayload,
-    ) -> anyhow::Result<Option<Expense>> {
+        user_id: Option<i32>,
+        id: Option<i32>,
+        deleted_before: Option<NaiveDateTime>,
+    ) -> anyhow::Result<PurgedExpenses> {
+        // The outer select still sees the attachment rows the cascade removes
         sqlx::query_as!(
-            Expense,
+            PurgedExpenses,
             r#"
-    UPDATE expenses 
-    SET category = COALESCE($1, category),
-        amount = COALESCE($2, amount),
-        description = COALESCE($3, description)
-    WHERE id = $4 AND user_id = $5
-    RETURNING id, category AS "category: _", amount, description, expense_date;
+    WITH purged AS (
+        DELETE FROM expenses
+        WHERE deleted_at IS NOT NULL
+          AND ($1::INTEGER IS NULL OR user_id = $1)
+          AND ($2::INTEGER IS NULL OR id = $2)
+          AND ($3::TIMESTAMP IS NULL OR deleted_at < $3)
+        RETURNING id
+    )
+    SELECT (SELECT COUNT(*) FROM purged) AS "count!",
+           ARRAY(SELECT a.storage_key FROM attachments a
+                 JOIN purged p ON p.id = a.expense_id) AS "storage_keys!";
     "#,
-            payload.category as _,
-            payload.amount,
-            payload.description,
-            payload.id,
-            user_id
+            user_id,
+            id,
+            deleted_before
         )
-        .fetch_optional(&*self.pool)
+        .fetch_one(&*self.pool)
         .await
-        .context("Failed to update expense")
+        .context("Failed to purge trashed expenses")
+    }
+
+    /// Applies `changes`, unless the expense is no longer at `version`.
+    pub async fn update_expense(
+        &self,
+        id: i32,
+        user_id: i32,
+        changes: &PatchExpensePayload,
+        version: Option<i32>,
+    ) -> anyhow::Result<GuardedChange<Expense>> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to start transaction")?;
+        if let Some(current) = find_stale(&mut tx, id, user_id, version).await? {
+            return Ok(GuardedChange::Stale(Box::new(current)));
+        }
+        let expense = modify_expense(&mut tx, id, changes, user_id).await?;
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(expense.map_or(GuardedChange::NotFound, GuardedChange::Applied))
+    }
+
+    /// Applies each expense's changes in one transaction; expenses deleted
+    /// meanwhile are left out of the result.
+    pub async fn update_expenses(
+        &self,
+        user_id: i32,
+        changes: &[(i32, PatchExpensePayload)],
+    ) -> anyhow::Result<Vec<Expense>> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to start transaction")?;
+        let mut expenses = Vec::with_capacity(changes.len());
+        for (id, change) in changes {
+            if let Some(expense) = modify_expense(&mut tx, *id, change, user_id).await? {
+                expenses.push(expense);
+            }
+        }
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(expenses)
+    }
+
+    /// Applies every operation in one transaction, each behind its own
+    /// savepoint so a failing one leaves the others untouched. The
+    /// transaction is rolled back when `atomic` and an operation failed.
+    /// Returns whether it was committed, and each operation's outcome.
+    pub async fn apply_batch(
+        &self,
+        user_id: i32,
+        operations: &[BatchOperation],
+        atomic: bool,
+    ) -> anyhow::Result<(bool, Vec<anyhow::Result<BatchOutcome>>)> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to start transaction")?;
+        let mut results = Vec::with_capacity(operations.len());
+        for operation in operations {
+            let mut savepoint = Acquire::begin(&mut tx)
+                .await
+                .context("Failed to create savepoint")?;
+            let result = match operation {
+                BatchOperation::Create(payload) => insert_expense(&mut savepoint, payload, user_id)
+                    .await
+                    .map(BatchOutcome::Created),
+                BatchOperation::Update { id, changes } => {
+                    modify_expense(&mut savepoint, *id, changes, user_id)
+                        .await
+                        .map(|expense| {
+                            expense.map_or(BatchOutcome::NotFound, BatchOutcome::Updated)
+                        })
+                }
+                BatchOperation::Delete { id } => trash_expense(&mut savepoint, *id, user_id)
+                    .await
+                    .map(|deleted| deleted.map_or(BatchOutcome::NotFound, BatchOutcome::Deleted)),
+            };
+            if result.as_ref().is_ok_and(BatchOutcome::is_applied) {
+                savepoint
+                    .commit()
+                    .await
+                    .context("Failed to release savepoint")?;
+            } else {
+                savepoint
+                    .rollback()
+                    .await
+                    .context("Failed to roll back savepoint")?;
+            }
+            results.push(result);
+        }
+
+        let all_applied = results
+            .iter()
+            .all(|r| r.as_ref().is_ok_and(BatchOutcome::is_applied));
+        let committed = !atomic || all_applied;
+        if committed {
+            tx.commit().await.context("Failed to commit transaction")?;
+        } else {
+            tx.rollback()
+                .await
+                .context("Failed to roll back transaction")?;
+        }
+        Ok((committed, results))
+    }
+}
+
+async fn insert_expense(
+    tx: &mut Transaction<'_, Postgres>,
+    payload: &CreateExpensePayload,
+    user_id: i32,
+) -> anyhow::Result<Expense> {
+    let id = sqlx::query_scalar!(
+        r#"
+    INSERT INTO expenses
+        (user_id, category_id, amount, currency, description, expense_date, account_id)
+    VALUES ($1, $2, $3,
+            COALESCE($4, (SELECT currency FROM accounts WHERE id = $7),
+                     (SELECT base_currency FROM users WHERE id = $1)),
+            $5, COALESCE($6, LOCALTIMESTAMP), $7)
+    RETURNING id;
+    "#,
+        user_id,
+        payload.category_id.context("Expense has no category")?,
+        payload.amount,
+        payload.currency,
+        payload.description,
+        payload.expense_date,
+        payload.account_id,
+    )
+    .fetch_one(&mut **tx)
+    .await
+    .context("Failed to create expense")?;
+
+    if let Some(tags) = &payload.tags {
+        set_tags(tx, id, user_id, &normalize_tags(tags)).await?;
+    }
+    record_history(tx, id, user_id, HistoryAction::Create, None).await?;
+    fetch_expense(&mut **tx, id, user_id)
+        .await?
+        .context("Created expense not found")
+}
+
+async fn modify_expense(
+    tx: &mut Transaction<'_, Postgres>,
+    id: i32,
+    changes: &PatchExpensePayload,
+    user_id: i32,
+) -> anyhow::Result<Option<Expense>> {
+    let Some(before) = lock_snapshot(tx, id, user_id).await? else {
+        return Ok(None);
+    };
+    sqlx::query!(
+        r#"
+    UPDATE expenses 
+    SET category_id = COALESCE($1, category_id),
+        amount = COALESCE($2, amount),
+        currency = COALESCE($3, currency),
+        description = CASE WHEN $4 THEN $5 ELSE description END,
+        expense_date = COALESCE($6, expense_date),
+        account_id = CASE WHEN $7 THEN $8 ELSE account_id END,
+        version = version + 1
+    WHERE id = $9;
+    "#,
+        changes.category_id,
+        changes.amount,
+        changes.currency,
+        changes.description.is_some(),
+        changes.description.clone().flatten(),
+        changes.expense_date,
+        changes.account_id.is_some(),
+        changes.account_id.flatten(),
+        id
+    )
+    .execute(&mut **tx)
+    .await
+    .context("Failed to update expense")?;
+
+    if let Some(tags) = &changes.tags {
+        set_tags(tx, id, user_id, &normalize_tags(tags)).await?;
+    }
+    record_history(tx, id, user_id, HistoryAction::Update, Some(before)).await?;
+    fetch_expense(&mut **tx, id, user_id).await
+}
+
+/// Moves a live expense to the trash; `None` when there is none.
+async fn trash_expense(
+    tx: &mut Transaction<'_, Postgres>,
+    id: i32,
+    user_id: i32,
+) -> anyhow::Result<Option<i32>> {
+    let Some(before) = lock_snapshot(tx, id, user_id).await? else {
+        return Ok(None);
+    };
+    sqlx::query!(
+        "UPDATE expenses SET deleted_at = NOW(), version = version + 1 WHERE id = $1;",
+        id
+    )
+    .execute(&mut **tx)
+    .await
+    .context(format!("Failed to delete expense by id: {}", id))?;
+    record_history(tx, id, user_id, HistoryAction::Delete, Some(before)).await?;
+    Ok(Some(id))
+}
+
+async fn fetch_expense<'e, E>(executor: E, id: i32, user_id: i32) -> anyhow::Result<Option<Expense>>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query_as!(
+        Expense,
+        r#"
+    SELECT id, category_id, amount, currency,
+           convert_to_base(amount, currency, user_id, expense_date::DATE) AS amount_in_base,
+           description, expense_date, account_id, group_id, expense_tag_names(id) AS "tags!",
+           version,
+           NULL::TEXT AS highlight, NULL::REAL AS rank
+    FROM expenses WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL;
+    "#,
+        id,
+        user_id
+    )
+    .fetch_optional(executor)
+    .await
+    .context(format!("Failed to get expense by id: {}", id))
+}
+
+/// Locks a live expense and returns it when it is no longer at `version`.
+/// A missing expense is left to the change itself.
+async fn find_stale(
+    tx: &mut Transaction<'_, Postgres>,
+    id: i32,
+    user_id: i32,
+    version: Option<i32>,
+) -> anyhow::Result<Option<Expense>> {
+    let Some(version) = version else {
+        return Ok(None);
+    };
+    let current = sqlx::query_scalar!(
+        r#"
+    SELECT version FROM expenses
+    WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
+    FOR UPDATE;
+    "#,
+        id,
+        user_id
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .context(format!("Failed to get version of expense id: {}", id))?;
+    match current {
+        Some(current) if current != version => fetch_expense(&mut **tx, id, user_id).await,
+        _ => Ok(None),
+    }
+}
+
+/// Current values of a live expense, locking it for the rest of the
+/// transaction; `None` when it is missing or trashed.
+pub(crate) async fn lock_snapshot(
+    tx: &mut Transaction<'_, Postgres>,
+    id: i32,
+    user_id: i32,
+) -> anyhow::Result<Option<serde_json::Value>> {
+    sqlx::query_scalar!(
+        r#"
+    SELECT expense_snapshot(id) AS "snapshot!"
+    FROM expenses
+    WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
+    FOR UPDATE;
+    "#,
+        id,
+        user_id
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .context(format!("Failed to get expense by id: {}", id))
+}
+
+/// Adds a history entry; the values after the change are read from the
+/// expense, except for a deletion.
+pub(crate) async fn record_history(
+    tx: &mut Transaction<'_, Postgres>,
+    expense_id: i32,
+    actor_id: i32,
+    action: HistoryAction,
+    before: Option<serde_json::Value>,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+    INSERT INTO expense_history (expense_id, actor_id, action, before, after)
+    VALUES ($1, $2, $3, $4, CASE WHEN $5 THEN expense_snapshot($1) END);
+    "#,
+        expense_id,
+        actor_id,
+        action.as_str(),
+        before,
+        action != HistoryAction::Delete
+    )
+    .execute(&mut **tx)
+    .await
+    .context(format!(
+        "Failed to record history of expense id: {}",
+        expense_id
+    ))?;
+    Ok(())
+}
+
+/// Adds an entry per expense for changes made in bulk, like
+/// `record_history`. `before` is empty for creations, otherwise it holds
+/// the values of each expense in the order of `expense_ids`.
+pub(crate) async fn record_history_many(
+    tx: &mut Transaction<'_, Postgres>,
+    expense_ids: &[i32],
+    actor_id: i32,
+    action: HistoryAction,
+    before: &[serde_json::Value],
+) -> anyhow::Result<()> {
+    if expense_ids.is_empty() {
+        return Ok(());
+    }
+    sqlx::query!(
+        r#"
+    INSERT INTO expense_history (expense_id, actor_id, action, before, after)
+    SELECT r.expense_id, $2, $3, r.before, expense_snapshot(r.expense_id)
+    FROM UNNEST($1::INT[], $4::JSONB[]) AS r(expense_id, before)
+    WHERE r.expense_id IS NOT NULL;
+    "#,
+        expense_ids,
+        actor_id,
+        action.as_str(),
+        before
+    )
+    .execute(&mut **tx)
+    .await
+    .context("Failed to record history of expenses")?;
+    Ok(())
+}
+
+/// Replaces the tags of an expense, creating the unknown ones. `names` must
+/// already be normalized.
+async fn set_tags(
+    tx: &mut Transaction<'_, Postgres>,
+    expense_id: i32,
+    user_id: i32,
+    names: &[String],
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        "DELETE FROM expense_tags WHERE expense_id = $1;",
+        expense_id
+    )
+    .execute(&mut **tx)
+    .await
+    .context(format!(
+        "Failed to clear tags of expense id: {}",
+        expense_id
+    ))?;
+    if names.is_empty() {
+        return Ok(());
+    }
+
+    sqlx::query!(
+        r#"
+    INSERT INTO tags (user_id, name)
+    SELECT $1, UNNEST($2::TEXT[])
+    ON CONFLICT (user_id, name) DO NOTHING;
+    "#,
+        user_id,
+        names
+    )
+    .execute(&mut **tx)
+    .await
+    .context("Failed to create tags")?;
+
+    sqlx::query!(
+        r#"
+    INSERT INTO expense_tags (expense_id, tag_id)
+    SELECT $1, id FROM tags WHERE user_id = $2 AND name = ANY($3);
+    "#,
+        expense_id,
+        user_id,
+        names
+    )
+    .execute(&mut **tx)
+    .await
+    .context(format!("Failed to tag expense id: {}", expense_id))?;
+    Ok(())
+}
+
+fn sort_column(sort: ExpenseSort) -> &'static str {
+    match sort {
+        ExpenseSort::Date => "expense_date",
+        ExpenseSort::Amount => "amount",
+        ExpenseSort::Relevance => "rank",
+    }
+}
+
+/// Appends the `highlight` and `rank` columns, NULL unless searching.
+fn push_search_columns(builder: &mut QueryBuilder<'_, Postgres>, query: &ExpenseListQuery) {
+    match query.search() {
+        Some(q) => {
+            builder
+                .push(", expense_headline(description, ")
+                .push_bind(q.to_string())
+                .push(") AS highlight, ts_rank(search_vector, expense_search_query(")
+                .push_bind(q.to_string())
+                .push(")) AS rank");
+        }
+        None => {
+            builder.push(", NULL::TEXT AS highlight, NULL::REAL AS rank");
+        }
+    }
+}
+
+/// Appends the `AND ...` clauses shared by every filtered expense query.
+fn push_expense_filters(builder: &mut QueryBuilder<'_, Postgres>, query: &ExpenseListQuery) {
+    if let Some(category_id) = query.category_id {
+        builder.push(" AND category_id = ").push_bind(category_id);
+    }
+    if let Some(account_id) = query.account_id {
+        builder.push(" AND account_id = ").push_bind(account_id);
+    }
+    if let Some(from) = query.from {
+        builder.push(" AND expense_date >= ").push_bind(from);
+    }
+    if let Some(to) = query.to {
+        builder
+            .push(" AND expense_date < ")
+            .push_bind(to)
+            .push(" + INTERVAL '1 day'");
+    }
+    if let Some(min_amount) = &query.min_amount {
+        builder
+            .push(" AND amount >= ")
+            .push_bind(min_amount.clone());
+    }
+    if let Some(max_amount) = &query.max_amount {
+        builder
+            .push(" AND amount <= ")
+            .push_bind(max_amount.clone());
+    }
+    if let Some(currency) = &query.currency {
+        builder.push(" AND currency = ").push_bind(currency.clone());
+    }
+    if let Some(q) = query.search() {
+        builder
+            .push(" AND search_vector @@ expense_search_query(")
+            .push_bind(q.to_string())
+            .push(")");
+    }
+
+    let tags = query.tag_names();
+    if !tags.is_empty() {
+        let matching = "SELECT COUNT(*) FROM expense_tags et JOIN tags t ON t.id = et.tag_id \
+                        WHERE et.expense_id = expenses.id AND t.name = ANY(";
+        let required = match query.tag_match {
+            TagMatch::Any => 1,
+            TagMatch::All => tags.len() as i64,
+        };
+        builder
+            .push(format!(" AND ({matching}"))
+            .push_bind(tags)
+            .push(")) >= ")
+            .push_bind(required);
     }
 }
@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+use crate::attachment::storage::AttachmentStorage;
+
+use super::repository::ExpenseRepository;
+
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Deletes expenses whose trash retention is over at startup, then every
+/// hour, along with their attachment files.
+pub fn spawn_trash_purger(
+    repository: ExpenseRepository,
+    storage: Box<dyn AttachmentStorage>,
+    retention_days: i64,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            match repository.purge_expired(retention_days).await {
+                Ok(purged) => {
+                    if purged.count > 0 {
+                        tracing::info!("Purged {} trashed expense(s)", purged.count);
+                    }
+                    for key in &purged.storage_keys {
+                        if let Err(e) = storage.delete(key).await {
+                            tracing::error!("Failed to delete attachment file: {:?}", e);
+                        }
+                    }
+                }
+                Err(e) => tracing::error!("Failed to purge trashed expenses: {:?}", e),
+            }
+        }
+    });
+}
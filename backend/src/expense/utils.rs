@@ -1,37 +1,416 @@
+use axum::{
+    extract::FromRequestParts,
+    http::{header::IF_MATCH, request::Parts},
+};
 use bigdecimal::BigDecimal;
-use serde::{Deserialize, Serialize};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::str::FromStr;
 use validator::{Validate, ValidationError};
 
-use super::models::ExpenseCategory;
+use crate::{
+    currency::utils::validate_currency,
+    error::AppError,
+    tag::utils::{normalize_tags, validate_tags},
+};
+
+use super::models::Expense;
+
+const CURSOR_DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+/// How far ahead of now an expense can be dated.
+const MAX_FUTURE_DAYS: i64 = 31;
 
 #[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct CreateExpensePayload {
-    #[validate(custom(function = "validate_category"))]
-    pub category: ExpenseCategory,
+    /// Defaults to the category set by the user's rules, or else the
+    /// default category.
+    #[validate(range(min = 1, message = "Invalid category"))]
+    pub category_id: Option<i32>,
 
     #[validate(custom(function = "validate_amount"))]
     pub amount: BigDecimal,
 
+    /// Defaults to the currency of the account, or else the user's base
+    /// currency.
+    #[validate(custom(function = "validate_currency"))]
+    pub currency: Option<String>,
+
     #[validate(length(max = 255, message = "Description too long"))]
     pub description: Option<String>,
+
+    /// Account the expense was paid from.
+    #[validate(range(min = 1, message = "Invalid account"))]
+    pub account_id: Option<i32>,
+
+    /// Defaults to now; a date alone means midnight.
+    #[serde(default, deserialize_with = "deserialize_expense_date")]
+    #[validate(custom(function = "validate_expense_date"))]
+    pub expense_date: Option<NaiveDateTime>,
+
+    /// Tag names; unknown tags are created.
+    #[validate(custom(function = "validate_tags"))]
+    pub tags: Option<Vec<String>>,
 }
 
+/// Full update with the id in the body, kept for older clients; prefer
+/// `PatchExpensePayload`.
 #[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct UpdateExpensePayload {
     #[validate(range(min = 1))]
     pub id: i32,
 
-    #[validate(custom(function = "validate_category"))]
-    pub category: ExpenseCategory,
+    #[validate(range(min = 1, message = "Invalid category"))]
+    pub category_id: i32,
 
     #[validate(custom(function = "validate_amount"))]
     pub amount: BigDecimal,
 
+    /// Unchanged when omitted.
+    #[validate(custom(function = "validate_currency"))]
+    pub currency: Option<String>,
+
     #[validate(length(max = 255, message = "Description too long"))]
     pub description: Option<String>,
+
+    /// Replaces every tag of the expense; unchanged when omitted.
+    #[validate(custom(function = "validate_tags"))]
+    pub tags: Option<Vec<String>>,
+}
+
+impl UpdateExpensePayload {
+    /// Omitted fields stay unchanged, `description` included.
+    pub fn into_patch(self) -> (i32, PatchExpensePayload) {
+        let patch = PatchExpensePayload {
+            category_id: Some(self.category_id),
+            amount: Some(self.amount),
+            currency: self.currency,
+            description: self.description.map(Some),
+            account_id: None,
+            expense_date: None,
+            tags: self.tags,
+        };
+        (self.id, patch)
+    }
+}
+
+/// Changes to an expense; omitted fields stay unchanged.
+#[derive(Debug, Default, Serialize, Deserialize, Validate)]
+pub struct PatchExpensePayload {
+    #[validate(range(min = 1, message = "Invalid category"))]
+    pub category_id: Option<i32>,
+
+    #[validate(custom(function = "validate_amount"))]
+    pub amount: Option<BigDecimal>,
+
+    #[validate(custom(function = "validate_currency"))]
+    pub currency: Option<String>,
+
+    /// An explicit `null` clears the description.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    #[validate(length(max = 255, message = "Description too long"))]
+    pub description: Option<Option<String>>,
+
+    /// An explicit `null` detaches the expense from its account.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    #[validate(range(min = 1, message = "Invalid account"))]
+    pub account_id: Option<Option<i32>>,
+
+    /// A date alone means midnight.
+    #[serde(default, deserialize_with = "deserialize_expense_date")]
+    #[validate(custom(function = "validate_expense_date"))]
+    pub expense_date: Option<NaiveDateTime>,
+
+    /// Replaces every tag of the expense.
+    #[validate(custom(function = "validate_tags"))]
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchMode {
+    /// Nothing is saved unless every operation succeeds.
+    #[default]
+    Atomic,
+    /// Successful operations are saved, failed ones are reported.
+    BestEffort,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum BatchOperation {
+    Create(CreateExpensePayload),
+    Update {
+        id: i32,
+        #[serde(flatten)]
+        changes: PatchExpensePayload,
+    },
+    Delete {
+        id: i32,
+    },
 }
 
-fn validate_amount(amount: &BigDecimal) -> Result<(), ValidationError> {
+#[derive(Debug, Deserialize, Validate)]
+pub struct BatchPayload {
+    #[serde(default)]
+    pub mode: BatchMode,
+
+    /// Each operation is validated on its own, so one invalid item does not
+    /// reject a best-effort batch.
+    #[validate(length(min = 1, max = 500, message = "Between 1 and 500 operations"))]
+    pub operations: Vec<BatchOperation>,
+}
+
+/// Kind of change recorded in the expense history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryAction {
+    Create,
+    Update,
+    Delete,
+    Restore,
+    Revert,
+}
+
+impl HistoryAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HistoryAction::Create => "create",
+            HistoryAction::Update => "update",
+            HistoryAction::Delete => "delete",
+            HistoryAction::Restore => "restore",
+            HistoryAction::Revert => "revert",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExpenseSort {
+    #[default]
+    Date,
+    Amount,
+    /// Best matches of `q` first; only valid with `q`.
+    Relevance,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// How the `tags` filter combines several tags.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagMatch {
+    /// Expenses carrying at least one of the tags.
+    #[default]
+    Any,
+    /// Expenses carrying every tag.
+    All,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[validate(schema(function = "validate_list_bounds"))]
+pub struct ExpenseListQuery {
+    pub category_id: Option<i32>,
+
+    pub account_id: Option<i32>,
+
+    /// Inclusive lower bound on `expense_date`.
+    pub from: Option<NaiveDate>,
+
+    /// Inclusive upper bound on `expense_date`.
+    pub to: Option<NaiveDate>,
+
+    pub min_amount: Option<BigDecimal>,
+
+    pub max_amount: Option<BigDecimal>,
+
+    #[validate(custom(function = "validate_currency"))]
+    pub currency: Option<String>,
+
+    /// Comma-separated tag names.
+    #[validate(length(max = 1000, message = "Too many tags"))]
+    pub tags: Option<String>,
+
+    #[serde(default)]
+    pub tag_match: TagMatch,
+
+    /// Full-text search on the description, French and English stemming,
+    /// accents ignored. Supports `"quoted phrases"`, `or` and `-excluded`.
+    #[validate(length(
+        min = 1,
+        max = 200,
+        message = "Search must be between 1 and 200 characters"
+    ))]
+    pub q: Option<String>,
+
+    /// Defaults to `relevance` when searching, `date` otherwise.
+    pub sort: Option<ExpenseSort>,
+
+    #[serde(default)]
+    pub order: SortOrder,
+
+    #[validate(range(min = 1, max = 200, message = "Limit must be between 1 and 200"))]
+    pub limit: Option<i64>,
+
+    /// Opaque value taken from `next_cursor` of the previous page.
+    pub cursor: Option<String>,
+}
+
+impl ExpenseListQuery {
+    pub const DEFAULT_LIMIT: i64 = 50;
+
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(Self::DEFAULT_LIMIT)
+    }
+
+    /// Trimmed search text; `None` when blank.
+    pub fn search(&self) -> Option<&str> {
+        self.q.as_deref().map(str::trim).filter(|q| !q.is_empty())
+    }
+
+    pub fn sort(&self) -> ExpenseSort {
+        self.sort.unwrap_or(if self.search().is_some() {
+            ExpenseSort::Relevance
+        } else {
+            ExpenseSort::Date
+        })
+    }
+
+    /// Normalized names of the `tags` filter; empty when not filtering.
+    pub fn tag_names(&self) -> Vec<String> {
+        let names: Vec<String> = self
+            .tags
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .filter(|name| !name.trim().is_empty())
+            .map(str::to_string)
+            .collect();
+        normalize_tags(&names)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum CursorValue {
+    Date(NaiveDateTime),
+    Amount(BigDecimal),
+    Rank(f32),
+}
+
+/// Position of the last row of a page, as `(sort value, id)`.
+#[derive(Debug, Clone)]
+pub struct ExpenseCursor {
+    pub value: CursorValue,
+    pub id: i32,
+}
+
+impl ExpenseCursor {
+    pub fn from_expense(expense: &Expense, sort: ExpenseSort) -> Self {
+        let value = match sort {
+            ExpenseSort::Date => CursorValue::Date(expense.expense_date),
+            ExpenseSort::Amount => CursorValue::Amount(expense.amount.clone()),
+            ExpenseSort::Relevance => CursorValue::Rank(expense.rank.unwrap_or_default()),
+        };
+        Self {
+            value,
+            id: expense.id,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        match &self.value {
+            CursorValue::Date(date) => format!("{}_{}", date.format(CURSOR_DATE_FORMAT), self.id),
+            CursorValue::Amount(amount) => format!("{}_{}", amount, self.id),
+            CursorValue::Rank(rank) => format!("{}_{}", rank, self.id),
+        }
+    }
+
+    pub fn decode(raw: &str, sort: ExpenseSort) -> Option<Self> {
+        let (value, id) = raw.rsplit_once('_')?;
+        let id = id.parse::<i32>().ok()?;
+        let value = match sort {
+            ExpenseSort::Date => {
+                CursorValue::Date(NaiveDateTime::parse_from_str(value, CURSOR_DATE_FORMAT).ok()?)
+            }
+            ExpenseSort::Amount => CursorValue::Amount(BigDecimal::from_str(value).ok()?),
+            ExpenseSort::Relevance => CursorValue::Rank(value.parse::<f32>().ok()?),
+        };
+        Some(Self { value, id })
+    }
+}
+
+fn validate_list_bounds(query: &ExpenseListQuery) -> Result<(), ValidationError> {
+    validate_date_range(query.from, query.to)?;
+    if let (Some(min), Some(max)) = (&query.min_amount, &query.max_amount)
+        && min > max
+    {
+        return Err(ValidationError::new(
+            "min_amount_must_not_exceed_max_amount",
+        ));
+    }
+    if query.sort == Some(ExpenseSort::Relevance) && query.search().is_none() {
+        return Err(ValidationError::new("relevance_sort_requires_q"));
+    }
+    Ok(())
+}
+
+/// Rejects a `from`/`to` query range that ends before it starts.
+pub(crate) fn validate_date_range<T: PartialOrd>(
+    from: Option<T>,
+    to: Option<T>,
+) -> Result<(), ValidationError> {
+    if let (Some(from), Some(to)) = (from, to)
+        && from > to
+    {
+        return Err(ValidationError::new("from_must_not_be_after_to"));
+    }
+    Ok(())
+}
+
+pub(crate) fn validate_expense_date(date: &NaiveDateTime) -> Result<(), ValidationError> {
+    if *date > Utc::now().naive_utc() + Duration::days(MAX_FUTURE_DAYS) {
+        return Err(ValidationError::new("expense_date_too_far_in_the_future"));
+    }
+    Ok(())
+}
+
+/// Tells an explicit `null` (`Some(None)`) from an omitted field (`None`).
+pub(crate) fn deserialize_nullable<'de, D, T>(
+    deserializer: D,
+) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Accepts `YYYY-MM-DD` as well as a full date-time.
+fn deserialize_expense_date<'de, D>(deserializer: D) -> Result<Option<NaiveDateTime>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum DateOrDateTime {
+        DateTime(NaiveDateTime),
+        Date(NaiveDate),
+    }
+
+    Ok(
+        Option::<DateOrDateTime>::deserialize(deserializer)?.map(|value| match value {
+            DateOrDateTime::DateTime(date_time) => date_time,
+            DateOrDateTime::Date(date) => date.and_time(NaiveTime::MIN),
+        }),
+    )
+}
+
+pub(crate) fn validate_amount(amount: &BigDecimal) -> Result<(), ValidationError> {
     let min = BigDecimal::from(0);
     if amount <= &min {
         return Err(ValidationError::new("amount_must_be_greater_than_zero"));
@@ -39,18 +418,35 @@ fn validate_amount(amount: &BigDecimal) -> Result<(), ValidationError> {
     Ok(())
 }
 
-fn validate_category(category: &ExpenseCategory) -> Result<(), ValidationError> {
-    if !matches!(
-        category,
-        ExpenseCategory::Groceries
-            | ExpenseCategory::Leisure
-            | ExpenseCategory::Electronics
-            | ExpenseCategory::Utilities
-            | ExpenseCategory::Clothing
-            | ExpenseCategory::Health
-            | ExpenseCategory::Others
-    ) {
-        return Err(ValidationError::new("invalid_category"));
+/// Version the client last saw, from `If-Match`; `None` without the header
+/// or with `*`.
+#[derive(Debug, Clone, Copy)]
+pub struct IfMatch(pub Option<i32>);
+
+impl<S> FromRequestParts<S> for IfMatch
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(value) = parts.headers.get(IF_MATCH) else {
+            return Ok(IfMatch(None));
+        };
+        let value = value.to_str().unwrap_or_default().trim();
+        if value == "*" {
+            return Ok(IfMatch(None));
+        }
+        value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .and_then(|v| v.parse().ok())
+            .map(|version| IfMatch(Some(version)))
+            .ok_or_else(|| AppError::BadRequest("If-Match must be one ETag of the expense".into()))
     }
-    Ok(())
+}
+
+/// Strong `ETag` of an expense version.
+pub fn etag(version: i32) -> String {
+    format!("\"{}\"", version)
 }
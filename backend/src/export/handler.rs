@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use axum::{
+    Router,
+    body::Body,
+    extract::State,
+    http::{
+        HeaderMap, StatusCode,
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    },
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use chrono::Utc;
+use tokio::sync::mpsc;
+use tokio_util::io::ReaderStream;
+use uuid::Uuid;
+
+use crate::{
+    attachment::utils::content_disposition, auth::token::claims::Claims, error::AppError,
+    expense::utils::ExpenseListQuery, state::AppState, validation::ValidatedQuery,
+};
+
+use super::{
+    utils::{EXPORT_BUFFER, ExportLocale, ExportOptions},
+    writer::{RowEncoder, encode_rows, write_xlsx},
+};
+
+/// Exports the expenses selected by the list filters. CSV and JSON are
+/// streamed as rows are read; XLSX is assembled in a temporary file first.
+pub async fn export_expenses(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    ValidatedQuery(query): ValidatedQuery<ExpenseListQuery>,
+    ValidatedQuery(options): ValidatedQuery<ExportOptions>,
+) -> Result<Response, AppError> {
+    let locale = options
+        .locale
+        .or_else(|| ExportLocale::from_headers(&headers))
+        .unwrap_or_default();
+
+    let (tx, rx) = mpsc::channel(EXPORT_BUFFER);
+    let user_id = claims.sub;
+    let export_state = state.clone();
+    tokio::spawn(async move {
+        if let Err(e) = export_state
+            .expense_repository
+            .export_expenses(user_id, &query, &tx)
+            .await
+        {
+            tracing::error!("Failed to export expenses: {:?}", e);
+            let _ = tx.send(Err(e)).await;
+        }
+    });
+
+    let body = match RowEncoder::new(options.format, locale) {
+        Some(encoder) => Body::from_stream(encode_rows(rx, encoder)),
+        None => {
+            let path = std::env::temp_dir().join(format!("export-{}.xlsx", Uuid::new_v4()));
+            let xlsx_path = path.clone();
+            tokio::task::spawn_blocking(move || write_xlsx(rx, locale, &xlsx_path))
+                .await
+                .map_err(anyhow::Error::from)??;
+            let file = tokio::fs::File::open(&path).await;
+            // The open handle keeps the contents readable after the unlink
+            if let Err(e) = tokio::fs::remove_file(&path).await {
+                tracing::error!("Failed to remove export file: {:?}", e);
+            }
+            let file = file.map_err(anyhow::Error::from)?;
+            Body::from_stream(ReaderStream::new(file))
+        }
+    };
+
+    let file_name = format!(
+        "expenses-{}.{}",
+        Utc::now().format("%Y-%m-%d"),
+        options.format.extension()
+    );
+    Ok((
+        StatusCode::OK,
+        [
+            (CONTENT_TYPE, options.format.content_type().to_string()),
+            (CONTENT_DISPOSITION, content_disposition(&file_name)),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route("/expenses/export", get(export_expenses))
+}
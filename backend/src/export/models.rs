@@ -0,0 +1,16 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+/// One exported expense, with its category name resolved.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ExportRow {
+    pub id: i32,
+    pub expense_date: NaiveDateTime,
+    pub category: Option<String>,
+    pub description: Option<String>,
+    pub amount: BigDecimal,
+    pub currency: String,
+    /// `None` when no exchange rate is known for the day.
+    pub amount_in_base: Option<BigDecimal>,
+}
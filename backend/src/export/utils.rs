@@ -0,0 +1,132 @@
+use axum::http::{HeaderMap, header::ACCEPT_LANGUAGE};
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+use validator::Validate;
+
+/// Rows buffered between the database and the response body.
+pub const EXPORT_BUFFER: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Json => "application/json",
+            ExportFormat::Xlsx => {
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            }
+        }
+    }
+}
+
+/// Number, date and header conventions of the CSV file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportLocale {
+    /// `;` separated, decimal comma, `31/01/2025`.
+    #[default]
+    Fr,
+    /// `,` separated, decimal point, `2025-01-31`.
+    En,
+}
+
+impl ExportLocale {
+    /// First supported language of the `Accept-Language` header.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        headers
+            .get(ACCEPT_LANGUAGE)?
+            .to_str()
+            .ok()?
+            .split(',')
+            .map(|tag| {
+                tag.split(';')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_lowercase()
+            })
+            .find_map(|tag| match tag.split('-').next() {
+                Some("fr") => Some(ExportLocale::Fr),
+                Some("en") => Some(ExportLocale::En),
+                _ => None,
+            })
+    }
+
+    pub fn delimiter(self) -> u8 {
+        match self {
+            ExportLocale::Fr => b';',
+            ExportLocale::En => b',',
+        }
+    }
+
+    pub fn headers(self) -> [&'static str; 7] {
+        match self {
+            ExportLocale::Fr => [
+                "Id",
+                "Date",
+                "Catégorie",
+                "Description",
+                "Montant",
+                "Devise",
+                "Montant (devise principale)",
+            ],
+            ExportLocale::En => [
+                "Id",
+                "Date",
+                "Category",
+                "Description",
+                "Amount",
+                "Currency",
+                "Amount (base currency)",
+            ],
+        }
+    }
+
+    pub fn format_amount(self, amount: &BigDecimal) -> String {
+        let amount = amount.to_string();
+        match self {
+            ExportLocale::Fr => amount.replace('.', ","),
+            ExportLocale::En => amount,
+        }
+    }
+
+    pub fn format_date(self, date: &NaiveDateTime) -> String {
+        match self {
+            ExportLocale::Fr => date.format("%d/%m/%Y").to_string(),
+            ExportLocale::En => date.format("%Y-%m-%d").to_string(),
+        }
+    }
+
+    /// Excel number format for the XLSX date column.
+    pub fn excel_date_format(self) -> &'static str {
+        match self {
+            ExportLocale::Fr => "dd/mm/yyyy",
+            ExportLocale::En => "yyyy-mm-dd",
+        }
+    }
+}
+
+/// Read next to the expense list filters, which select the exported rows.
+#[derive(Debug, Deserialize, Validate)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+
+    /// Taken from `Accept-Language` when omitted, then French.
+    pub locale: Option<ExportLocale>,
+}
@@ -0,0 +1,222 @@
+use std::{io, path::Path};
+
+use bigdecimal::{BigDecimal, ToPrimitive};
+use bytes::Bytes;
+use futures::{Stream, stream};
+use rust_xlsxwriter::{Format, Workbook};
+use tokio::sync::mpsc;
+
+use super::{
+    models::ExportRow,
+    utils::{ExportFormat, ExportLocale},
+};
+
+/// Turns rows into chunks of a CSV or JSON document.
+pub enum RowEncoder {
+    Csv(ExportLocale),
+    Json { first: bool },
+}
+
+impl RowEncoder {
+    /// `None` for XLSX, which cannot be written row by row.
+    pub fn new(format: ExportFormat, locale: ExportLocale) -> Option<Self> {
+        match format {
+            ExportFormat::Csv => Some(RowEncoder::Csv(locale)),
+            ExportFormat::Json => Some(RowEncoder::Json { first: true }),
+            ExportFormat::Xlsx => None,
+        }
+    }
+
+    fn start(&mut self) -> anyhow::Result<Bytes> {
+        match self {
+            RowEncoder::Csv(locale) => {
+                // The BOM makes spreadsheet software read the file as UTF-8
+                let mut chunk = b"\xEF\xBB\xBF".to_vec();
+                chunk.extend(csv_record(*locale, locale.headers())?);
+                Ok(Bytes::from(chunk))
+            }
+            RowEncoder::Json { .. } => Ok(Bytes::from_static(b"[")),
+        }
+    }
+
+    fn row(&mut self, row: &ExportRow) -> anyhow::Result<Bytes> {
+        match self {
+            RowEncoder::Csv(locale) => {
+                let locale = *locale;
+                let record = csv_record(
+                    locale,
+                    [
+                        row.id.to_string(),
+                        locale.format_date(&row.expense_date),
+                        neutralize_formula(row.category.as_deref().unwrap_or_default()),
+                        neutralize_formula(row.description.as_deref().unwrap_or_default()),
+                        locale.format_amount(&row.amount),
+                        row.currency.clone(),
+                        row.amount_in_base
+                            .as_ref()
+                            .map(|amount| locale.format_amount(amount))
+                            .unwrap_or_default(),
+                    ],
+                )?;
+                Ok(Bytes::from(record))
+            }
+            RowEncoder::Json { first } => {
+                let mut chunk = if *first { Vec::new() } else { vec![b','] };
+                *first = false;
+                serde_json::to_writer(&mut chunk, row)?;
+                Ok(Bytes::from(chunk))
+            }
+        }
+    }
+
+    fn finish(&mut self) -> Bytes {
+        match self {
+            RowEncoder::Csv(_) => Bytes::new(),
+            RowEncoder::Json { .. } => Bytes::from_static(b"]"),
+        }
+    }
+}
+
+/// Prefixes user text that spreadsheet software would run as a formula
+/// (`=HYPERLINK(...)`, `@SUM(...)`...) with `'`, which shows it as typed.
+fn neutralize_formula(text: &str) -> String {
+    if text.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// One CSV line, quoted as needed.
+fn csv_record<I, T>(locale: ExportLocale, record: I) -> anyhow::Result<Vec<u8>>
+where
+    I: IntoIterator<Item = T>,
+    T: AsRef<[u8]>,
+{
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(locale.delimiter())
+        .from_writer(Vec::new());
+    writer.write_record(record)?;
+    Ok(writer.into_inner()?)
+}
+
+/// Response body encoding rows as they arrive. A failed read ends the
+/// stream with an error, which aborts the response instead of sending a
+/// truncated file that looks complete.
+pub fn encode_rows(
+    rx: mpsc::Receiver<anyhow::Result<ExportRow>>,
+    encoder: RowEncoder,
+) -> impl Stream<Item = io::Result<Bytes>> {
+    enum Stage {
+        Start,
+        Rows,
+        Done,
+    }
+
+    stream::unfold(
+        (rx, encoder, Stage::Start),
+        |(mut rx, mut encoder, stage)| async move {
+            let (chunk, next) = match stage {
+                Stage::Start => (encoder.start(), Stage::Rows),
+                Stage::Rows => match rx.recv().await {
+                    Some(Ok(row)) => (encoder.row(&row), Stage::Rows),
+                    Some(Err(e)) => (Err(e), Stage::Done),
+                    None => (Ok(encoder.finish()), Stage::Done),
+                },
+                Stage::Done => return None,
+            };
+            let next = if chunk.is_err() { Stage::Done } else { next };
+            Some((chunk.map_err(io::Error::other), (rx, encoder, next)))
+        },
+    )
+}
+
+/// Writes the rows to an XLSX file at `path`. Blocking; the worksheet is
+/// flushed to disk as it grows so rows are not kept in memory.
+pub fn write_xlsx(
+    mut rx: mpsc::Receiver<anyhow::Result<ExportRow>>,
+    locale: ExportLocale,
+    path: &Path,
+) -> anyhow::Result<()> {
+    let mut workbook = Workbook::new();
+    let header = Format::new().set_bold();
+    let date = Format::new().set_num_format(locale.excel_date_format());
+    let amount = Format::new().set_num_format("0.00");
+
+    let worksheet = workbook.add_worksheet_with_constant_memory();
+    for (col, title) in locale.headers().into_iter().enumerate() {
+        worksheet.write_string_with_format(0, col as u16, title, &header)?;
+    }
+
+    // Text goes in string cells, which are never evaluated as formulas
+    let mut line = 0;
+    while let Some(row) = rx.blocking_recv() {
+        let row = row?;
+        line += 1;
+        worksheet.write_number(line, 0, row.id)?;
+        worksheet.write_datetime_with_format(line, 1, row.expense_date, &date)?;
+        if let Some(category) = &row.category {
+            worksheet.write_string(line, 2, category)?;
+        }
+        if let Some(description) = &row.description {
+            worksheet.write_string(line, 3, description)?;
+        }
+        worksheet.write_number_with_format(line, 4, to_f64(&row.amount), &amount)?;
+        worksheet.write_string(line, 5, &row.currency)?;
+        if let Some(amount_in_base) = &row.amount_in_base {
+            worksheet.write_number_with_format(line, 6, to_f64(amount_in_base), &amount)?;
+        }
+    }
+
+    workbook.save(path)?;
+    Ok(())
+}
+
+fn to_f64(amount: &BigDecimal) -> f64 {
+    amount.to_f64().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn row(description: &str) -> ExportRow {
+        ExportRow {
+            id: 1,
+            expense_date: NaiveDate::from_ymd_opt(2025, 1, 31)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+            category: Some("@Courses".into()),
+            description: Some(description.into()),
+            amount: BigDecimal::from_str("12.50").unwrap(),
+            currency: "EUR".into(),
+            amount_in_base: None,
+        }
+    }
+
+    fn csv_line(description: &str) -> String {
+        let mut encoder = RowEncoder::new(ExportFormat::Csv, ExportLocale::En).unwrap();
+        String::from_utf8(encoder.row(&row(description)).unwrap().to_vec()).unwrap()
+    }
+
+    #[test]
+    fn csv_cells_never_start_a_formula() {
+        let line = csv_line("=HYPERLINK(\"http://evil.example\",\"Click\")");
+        assert_eq!(
+            line,
+            "1,2025-01-31,'@Courses,\"'=HYPERLINK(\"\"http://evil.example\"\",\"\"Click\"\")\",12.50,EUR,\n"
+        );
+        assert!(csv_line("+33 6 12").contains(",'+33 6 12,"));
+        assert!(csv_line("-5 au pot").contains(",'-5 au pot,"));
+    }
+
+    #[test]
+    fn csv_keeps_ordinary_text() {
+        assert!(csv_line("Boulangerie = pain").contains(",Boulangerie = pain,"));
+    }
+}
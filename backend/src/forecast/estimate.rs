@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use bigdecimal::{BigDecimal, RoundingMode, Zero};
+use chrono::NaiveDate;
+
+use super::models::ScheduledExpense;
+
+/// Share of the pace estimate the range spans on each side when there is
+/// no history to compare it with.
+const PACE_SPREAD_PERCENT: i32 = 25;
+
+/// How the rest of a category's month is estimated.
+pub struct Estimate {
+    pub pace: BigDecimal,
+    pub history: Option<BigDecimal>,
+    pub projected: BigDecimal,
+    pub low: BigDecimal,
+    pub high: BigDecimal,
+}
+
+impl Estimate {
+    /// `actual` was spent in the first `days_elapsed` days, `discretionary`
+    /// of it outside recurring expenses; `recurring` is still due, and
+    /// `past` holds what was spent in the rest of the month in each past
+    /// year.
+    pub fn new(
+        actual: &BigDecimal,
+        discretionary: &BigDecimal,
+        recurring: &BigDecimal,
+        past: &[BigDecimal],
+        days_elapsed: u32,
+        days_left: u32,
+    ) -> Self {
+        let pace = round(
+            discretionary * BigDecimal::from(days_left) / BigDecimal::from(days_elapsed.max(1)),
+        );
+        let history = (!past.is_empty())
+            .then(|| round(past.iter().sum::<BigDecimal>() / BigDecimal::from(past.len() as u32)));
+        let rest = match &history {
+            Some(history) => round((&pace + history) / BigDecimal::from(2)),
+            None => pace.clone(),
+        };
+
+        // Every estimate of the rest of the month bounds the range
+        let (lowest, highest) = if past.is_empty() {
+            let spread = &pace * BigDecimal::from(PACE_SPREAD_PERCENT) / BigDecimal::from(100);
+            (round(&pace - &spread), round(&pace + &spread))
+        } else {
+            past.iter()
+                .fold((pace.clone(), pace.clone()), |(low, high), year| {
+                    (low.min(year.clone()), high.max(year.clone()))
+                })
+        };
+
+        let known = actual + recurring;
+        Estimate {
+            projected: &known + &rest,
+            low: &known + lowest,
+            high: &known + highest,
+            pace,
+            history,
+        }
+    }
+}
+
+/// Amount per category of the recurring occurrences from `month` (its
+/// first day) through `month_end` not yet turned into expenses.
+pub fn recurring_due(
+    scheduled: &[ScheduledExpense],
+    month: NaiveDate,
+    month_end: NaiveDate,
+) -> HashMap<i32, BigDecimal> {
+    let mut due: HashMap<i32, BigDecimal> = HashMap::new();
+    for expense in scheduled {
+        let Some(amount) = &expense.amount_in_base else {
+            continue;
+        };
+        let schedule = expense.schedule();
+        let last = expense.end_date.map_or(month_end, |end| end.min(month_end));
+        let mut date = schedule.first_on_or_after(expense.next_occurrence.max(month));
+        while date <= last {
+            *due.entry(expense.category_id)
+                .or_insert_with(BigDecimal::zero) += amount;
+            date = schedule.next_after(date);
+        }
+    }
+    due
+}
+
+fn round(amount: BigDecimal) -> BigDecimal {
+    amount.with_scale_round(2, RoundingMode::HalfUp)
+}
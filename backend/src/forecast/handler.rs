@@ -0,0 +1,140 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{Json, Router, extract::State, http::StatusCode, routing::get};
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{Datelike, Days, Utc};
+
+use crate::{
+    auth::token::claims::Claims,
+    budget::utils::{month_start, next_month_start},
+    error::AppError,
+    state::AppState,
+    user::repository::UserRepository,
+};
+
+use super::{
+    estimate::{Estimate, recurring_due},
+    models::{CategoryForecast, SpendingForecast},
+};
+
+/// Past years whose same month is compared with the current one.
+const HISTORY_YEARS: i32 = 3;
+
+/// Month-end spending per category, projected from this month's pace, the
+/// recurring expenses still due and the same month in past years.
+pub async fn get_forecast(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<SpendingForecast>), AppError> {
+    let user = state
+        .user_repository
+        .find_by_id(claims.sub)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".into()))?;
+
+    let today = Utc::now().date_naive();
+    let month = month_start(today);
+    let month_end = next_month_start(today) - Days::new(1);
+    let days_elapsed = today.day();
+    let days_in_month = month_end.day();
+
+    let spending = state
+        .forecast_repository
+        .find_month_spending(claims.sub, month, today)
+        .await?;
+    // Today is counted as elapsed, so recurring expenses are due from tomorrow
+    let recurring = recurring_due(
+        &state
+            .forecast_repository
+            .find_scheduled(claims.sub, month_end)
+            .await?,
+        today + Days::new(1),
+        month_end,
+    );
+    let history_years = state
+        .forecast_repository
+        .find_history_years(claims.sub, month, HISTORY_YEARS)
+        .await?;
+    let mut past: HashMap<i32, HashMap<i32, BigDecimal>> = HashMap::new();
+    for remainder in state
+        .forecast_repository
+        .find_past_remainders(claims.sub, month, days_elapsed as i32, HISTORY_YEARS)
+        .await?
+    {
+        past.entry(remainder.category_id)
+            .or_default()
+            .insert(remainder.year, remainder.total);
+    }
+
+    let zero = BigDecimal::zero();
+    let mut categories: Vec<CategoryForecast> = state
+        .category_repository
+        .find_categories(claims.sub)
+        .await?
+        .into_iter()
+        .filter_map(|category| {
+            let current = spending.iter().find(|s| s.category_id == category.id);
+            let due = recurring.get(&category.id);
+            let by_year = past.get(&category.id);
+            if current.is_none() && due.is_none() && by_year.is_none() {
+                return None;
+            }
+
+            let actual = current.map_or(&zero, |s| &s.total);
+            let discretionary = current.map_or(&zero, |s| &s.discretionary);
+            let due = due.cloned().unwrap_or_default();
+            // A year with spending in other categories only counts as zero
+            let past: Vec<BigDecimal> = history_years
+                .iter()
+                .map(|year| {
+                    by_year
+                        .and_then(|totals| totals.get(year))
+                        .cloned()
+                        .unwrap_or_default()
+                })
+                .collect();
+            let estimate = Estimate::new(
+                actual,
+                discretionary,
+                &due,
+                &past,
+                days_elapsed,
+                days_in_month - days_elapsed,
+            );
+
+            Some(CategoryForecast {
+                category_id: category.id,
+                category_name: category.name,
+                actual: actual.clone(),
+                recurring: due,
+                pace: estimate.pace,
+                history: estimate.history,
+                projected: estimate.projected,
+                low: estimate.low,
+                high: estimate.high,
+            })
+        })
+        .collect();
+    categories.sort_by(|a, b| b.projected.cmp(&a.projected));
+
+    Ok((
+        StatusCode::OK,
+        Json(SpendingForecast {
+            month,
+            as_of: today,
+            days_elapsed,
+            days_in_month,
+            currency: user.base_currency,
+            history_years,
+            actual: categories.iter().map(|c| &c.actual).sum(),
+            projected: categories.iter().map(|c| &c.projected).sum(),
+            low: categories.iter().map(|c| &c.low).sum(),
+            high: categories.iter().map(|c| &c.high).sum(),
+            categories,
+        }),
+    ))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route("/expenses/forecast", get(get_forecast))
+}
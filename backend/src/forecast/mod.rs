@@ -0,0 +1,4 @@
+pub mod estimate;
+pub mod handler;
+pub mod models;
+pub mod repository;
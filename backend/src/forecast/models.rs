@@ -0,0 +1,81 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::recurring::{models::RecurrenceFrequency, schedule::Schedule};
+
+/// Spending of a category so far this month.
+#[derive(Debug)]
+pub struct MonthSpending {
+    pub category_id: i32,
+    pub total: BigDecimal,
+    /// Leaves out the occurrences of recurring expenses.
+    pub discretionary: BigDecimal,
+}
+
+/// Spending of a category, in the same month of a past year, after the
+/// day of the month the forecast is made on.
+#[derive(Debug)]
+pub struct PastRemainder {
+    pub year: i32,
+    pub category_id: i32,
+    /// Leaves out the occurrences of recurring expenses.
+    pub total: BigDecimal,
+}
+
+/// A recurring expense that has occurrences left to materialize.
+#[derive(Debug)]
+pub struct ScheduledExpense {
+    pub category_id: i32,
+    /// `None` when no exchange rate is known.
+    pub amount_in_base: Option<BigDecimal>,
+    pub frequency: RecurrenceFrequency,
+    pub day_of_month: Option<i16>,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub next_occurrence: NaiveDate,
+}
+
+impl ScheduledExpense {
+    pub fn schedule(&self) -> Schedule {
+        Schedule::new(self.frequency, self.day_of_month, self.start_date)
+    }
+}
+
+/// Month-end projection of a category. Amounts are in the base currency.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CategoryForecast {
+    pub category_id: i32,
+    pub category_name: String,
+    /// Spent so far this month.
+    pub actual: BigDecimal,
+    /// Recurring expenses still due by the end of the month.
+    pub recurring: BigDecimal,
+    /// Rest of the month at this month's pace, recurring expenses aside.
+    pub pace: BigDecimal,
+    /// Rest of the month as spent on average in the same month of past
+    /// years; `None` without history.
+    pub history: Option<BigDecimal>,
+    pub projected: BigDecimal,
+    pub low: BigDecimal,
+    pub high: BigDecimal,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpendingForecast {
+    /// First day of the month.
+    pub month: NaiveDate,
+    pub as_of: NaiveDate,
+    pub days_elapsed: u32,
+    pub days_in_month: u32,
+    pub currency: String,
+    /// Past years whose same month informs `history`.
+    pub history_years: Vec<i32>,
+    pub actual: BigDecimal,
+    pub projected: BigDecimal,
+    /// Sums of the categories' ranges.
+    pub low: BigDecimal,
+    pub high: BigDecimal,
+    /// Highest projection first.
+    pub categories: Vec<CategoryForecast>,
+}
@@ -0,0 +1,153 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+use super::models::{MonthSpending, PastRemainder, ScheduledExpense};
+
+pub struct ForecastRepository {
+    pool: Arc<PgPool>,
+}
+
+// Amounts are converted to the base currency, like summaries; expenses
+// without a known rate are left out.
+impl ForecastRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        ForecastRepository { pool }
+    }
+
+    /// Spending per category from `month` (its first day) through `today`.
+    pub async fn find_month_spending(
+        &self,
+        user_id: i32,
+        month: NaiveDate,
+        today: NaiveDate,
+    ) -> anyhow::Result<Vec<MonthSpending>> {
+        sqlx::query_as!(
+            MonthSpending,
+            r#"
+    SELECT category_id,
+           COALESCE(SUM(convert_to_base(amount, currency, user_id, expense_date::DATE)), 0)
+               AS "total!",
+           COALESCE(SUM(convert_to_base(amount, currency, user_id, expense_date::DATE))
+                        FILTER (WHERE recurring_expense_id IS NULL), 0) AS "discretionary!"
+    FROM expenses
+    WHERE user_id = $1 AND deleted_at IS NULL
+      AND expense_date >= $2::DATE
+      AND expense_date < $3::DATE + 1
+    GROUP BY category_id;
+    "#,
+            user_id,
+            month,
+            today
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to get month spending by user_id: {}",
+            user_id
+        ))
+    }
+
+    /// Of the same month in each of the last `years` years, those with any
+    /// expense, most recent first.
+    pub async fn find_history_years(
+        &self,
+        user_id: i32,
+        month: NaiveDate,
+        years: i32,
+    ) -> anyhow::Result<Vec<i32>> {
+        sqlx::query_scalar!(
+            r#"
+    SELECT EXTRACT(YEAR FROM m.month_start)::INTEGER AS "year!"
+    FROM (
+        SELECT ($2::DATE - make_interval(years => y))::DATE AS month_start
+        FROM generate_series(1, $3::INTEGER) AS y
+    ) m
+    WHERE EXISTS (
+        SELECT 1 FROM expenses e
+        WHERE e.user_id = $1 AND e.deleted_at IS NULL
+          AND e.expense_date >= m.month_start
+          AND e.expense_date < m.month_start + INTERVAL '1 month'
+    )
+    ORDER BY 1 DESC;
+    "#,
+            user_id,
+            month,
+            years
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to get spending history by user_id: {}",
+            user_id
+        ))
+    }
+
+    /// Spending per year and category in the same month of the last `years`
+    /// years, after its `day`-th day, recurring expenses aside.
+    pub async fn find_past_remainders(
+        &self,
+        user_id: i32,
+        month: NaiveDate,
+        day: i32,
+        years: i32,
+    ) -> anyhow::Result<Vec<PastRemainder>> {
+        sqlx::query_as!(
+            PastRemainder,
+            r#"
+    SELECT EXTRACT(YEAR FROM m.month_start)::INTEGER AS "year!",
+           e.category_id AS "category_id!",
+           COALESCE(SUM(convert_to_base(e.amount, e.currency, e.user_id, e.expense_date::DATE)), 0)
+               AS "total!"
+    FROM (
+        SELECT ($2::DATE - make_interval(years => y))::DATE AS month_start
+        FROM generate_series(1, $4::INTEGER) AS y
+    ) m
+    JOIN expenses e
+      ON e.user_id = $1 AND e.deleted_at IS NULL AND e.recurring_expense_id IS NULL
+     AND e.expense_date >= m.month_start + $3::INTEGER
+     AND e.expense_date < m.month_start + INTERVAL '1 month'
+    GROUP BY 1, 2;
+    "#,
+            user_id,
+            month,
+            day,
+            years
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to get past spending by user_id: {}",
+            user_id
+        ))
+    }
+
+    /// Active recurring expenses with an occurrence left on or before `until`.
+    pub async fn find_scheduled(
+        &self,
+        user_id: i32,
+        until: NaiveDate,
+    ) -> anyhow::Result<Vec<ScheduledExpense>> {
+        sqlx::query_as!(
+            ScheduledExpense,
+            r#"
+    SELECT category_id,
+           convert_to_base(amount, currency, user_id, next_occurrence) AS amount_in_base,
+           frequency AS "frequency: _", day_of_month, start_date, end_date, next_occurrence
+    FROM recurring_expenses
+    WHERE user_id = $1 AND NOT paused AND next_occurrence <= $2
+      AND (end_date IS NULL OR next_occurrence <= end_date);
+    "#,
+            user_id,
+            until
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to get recurring expenses by user_id: {}",
+            user_id
+        ))
+    }
+}
@@ -0,0 +1,244 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get},
+};
+use bigdecimal::{BigDecimal, RoundingMode, Zero};
+use chrono::{Months, Utc};
+
+use crate::{
+    auth::token::claims::Claims, budget::utils::month_start, currency::handler::ensure_currency,
+    error::AppError, state::AppState, user::repository::UserRepository, validation::ValidatedJson,
+};
+
+use super::{
+    models::{Contribution, Goal, GoalProgress},
+    projection::Projection,
+    utils::{CreateContributionPayload, CreateGoalPayload, UpdateGoalPayload},
+};
+
+/// Full months of cash flow the suggested contribution is compared with.
+const NET_MONTHS: u32 = 3;
+
+pub async fn get_goals(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Vec<Goal>>), AppError> {
+    let goals = state.goal_repository.find_goals(claims.sub).await?;
+    Ok((StatusCode::OK, Json(goals)))
+}
+
+pub async fn create_goal(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(payload): ValidatedJson<CreateGoalPayload>,
+) -> Result<(StatusCode, Json<Goal>), AppError> {
+    if let Some(currency) = &payload.currency {
+        ensure_currency(&state, currency).await?;
+    }
+    if state
+        .goal_repository
+        .exists_by_name(claims.sub, &payload.name, None)
+        .await?
+    {
+        return Err(AppError::Conflict("Goal already exists".into()));
+    }
+
+    let goal = state
+        .goal_repository
+        .create_goal(payload, claims.sub)
+        .await?;
+    Ok((StatusCode::CREATED, Json(goal)))
+}
+
+pub async fn get_goal(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<Goal>), AppError> {
+    let goal = ensure_goal(&state, id, claims.sub).await?;
+    Ok((StatusCode::OK, Json(goal)))
+}
+
+pub async fn update_goal(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    ValidatedJson(payload): ValidatedJson<UpdateGoalPayload>,
+) -> Result<(StatusCode, Json<Goal>), AppError> {
+    let name_taken = match &payload.name {
+        Some(name) => {
+            state
+                .goal_repository
+                .exists_by_name(claims.sub, name, Some(id))
+                .await?
+        }
+        None => false,
+    };
+    if name_taken {
+        return Err(AppError::Conflict("Goal already exists".into()));
+    }
+
+    let goal = state
+        .goal_repository
+        .update_goal(id, claims.sub, payload)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Goal not found".into()))?;
+    Ok((StatusCode::OK, Json(goal)))
+}
+
+pub async fn delete_goal(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    match state.goal_repository.delete_goal(id, claims.sub).await? {
+        Some(_) => Ok(StatusCode::NO_CONTENT),
+        None => Ok(StatusCode::NOT_FOUND),
+    }
+}
+
+pub async fn get_contributions(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<Vec<Contribution>>), AppError> {
+    ensure_goal(&state, id, claims.sub).await?;
+    let contributions = state.goal_repository.find_contributions(id).await?;
+    Ok((StatusCode::OK, Json(contributions)))
+}
+
+pub async fn create_contribution(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    ValidatedJson(payload): ValidatedJson<CreateContributionPayload>,
+) -> Result<(StatusCode, Json<Contribution>), AppError> {
+    let goal = ensure_goal(&state, id, claims.sub).await?;
+    if &goal.saved + &payload.amount < BigDecimal::zero() {
+        return Err(AppError::BadRequest(
+            "Cannot take out more than was saved".into(),
+        ));
+    }
+
+    let contribution = state
+        .goal_repository
+        .create_contribution(id, payload)
+        .await?;
+    Ok((StatusCode::CREATED, Json(contribution)))
+}
+
+pub async fn delete_contribution(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path((id, contribution_id)): Path<(i32, i32)>,
+) -> Result<StatusCode, AppError> {
+    let goal = ensure_goal(&state, id, claims.sub).await?;
+    let Some(contribution) = state
+        .goal_repository
+        .find_contribution(contribution_id, id)
+        .await?
+    else {
+        return Ok(StatusCode::NOT_FOUND);
+    };
+    // The money taken out since would no longer have been there
+    if &goal.saved - &contribution.amount < BigDecimal::zero() {
+        return Err(AppError::Conflict(
+            "Delete the withdrawals made since first".into(),
+        ));
+    }
+    match state
+        .goal_repository
+        .delete_contribution(contribution_id, id)
+        .await?
+    {
+        Some(_) => Ok(StatusCode::NO_CONTENT),
+        None => Ok(StatusCode::NOT_FOUND),
+    }
+}
+
+/// Progress of the goal, its projection at the current rate, and the
+/// monthly contribution that would reach it on time compared with the
+/// user's recent cash flow.
+pub async fn get_progress(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<GoalProgress>), AppError> {
+    let goal = ensure_goal(&state, id, claims.sub).await?;
+    let user = state
+        .user_repository
+        .find_by_id(claims.sub)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".into()))?;
+
+    let today = Utc::now().date_naive();
+    let started = state.goal_repository.find_start(id).await?;
+    let projection = Projection::new(&goal, started, today);
+
+    let current_month = month_start(today);
+    let months = state
+        .income_repository
+        .find_cash_flow(
+            claims.sub,
+            current_month - Months::new(NET_MONTHS),
+            current_month - Months::new(1),
+        )
+        .await?;
+    let average_monthly_net = (months.iter().map(|m| &m.net).sum::<BigDecimal>()
+        / BigDecimal::from(NET_MONTHS))
+    .with_scale_round(2, RoundingMode::HalfUp);
+    let affordable = (goal.currency == user.base_currency)
+        .then(|| projection.suggested_monthly_contribution <= average_monthly_net);
+
+    Ok((
+        StatusCode::OK,
+        Json(GoalProgress {
+            goal_id: goal.id,
+            currency: goal.currency,
+            target_amount: goal.target_amount,
+            saved: goal.saved,
+            remaining: projection.remaining,
+            percent_saved: projection.percent_saved,
+            deadline: goal.deadline,
+            months_left: projection.months_left,
+            monthly_rate: projection.monthly_rate,
+            projected_amount: projection.projected_amount,
+            projected_completion: projection.projected_completion,
+            on_track: projection.on_track,
+            suggested_monthly_contribution: projection.suggested_monthly_contribution,
+            average_monthly_net,
+            base_currency: user.base_currency,
+            affordable,
+        }),
+    ))
+}
+
+async fn ensure_goal(state: &AppState, id: i32, user_id: i32) -> Result<Goal, AppError> {
+    state
+        .goal_repository
+        .find_goal(id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Goal not found".into()))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/goals", get(get_goals).post(create_goal))
+        .route(
+            "/goals/{id}",
+            get(get_goal).put(update_goal).delete(delete_goal),
+        )
+        .route(
+            "/goals/{id}/contributions",
+            get(get_contributions).post(create_contribution),
+        )
+        .route(
+            "/goals/{id}/contributions/{contribution_id}",
+            delete(delete_contribution),
+        )
+        .route("/goals/{id}/progress", get(get_progress))
+}
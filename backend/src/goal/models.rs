@@ -0,0 +1,57 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Goal {
+    pub id: i32,
+    pub name: String,
+    pub target_amount: BigDecimal,
+    pub currency: String,
+    pub deadline: NaiveDate,
+    /// Sum of the contributions.
+    pub saved: BigDecimal,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Contribution {
+    pub id: i32,
+    pub goal_id: i32,
+    /// Negative when money was taken back out.
+    pub amount: BigDecimal,
+    pub contribution_date: NaiveDate,
+    pub note: Option<String>,
+}
+
+/// Where a goal stands and where it is heading at the current rate.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GoalProgress {
+    pub goal_id: i32,
+    pub currency: String,
+    pub target_amount: BigDecimal,
+    pub saved: BigDecimal,
+    pub remaining: BigDecimal,
+    /// Share of the target already saved, in percent.
+    pub percent_saved: BigDecimal,
+    pub deadline: NaiveDate,
+    /// Months from the current one through the deadline's; 0 once it passed.
+    pub months_left: u32,
+    /// Average saved per month since the goal started.
+    pub monthly_rate: BigDecimal,
+    /// Saved by the deadline if the rate holds.
+    pub projected_amount: BigDecimal,
+    /// First day of the month the target would be reached at the current
+    /// rate; `None` once reached, or when nothing is being saved.
+    pub projected_completion: Option<NaiveDate>,
+    pub on_track: bool,
+    /// What to put aside each month left to reach the target on time; all
+    /// of the remainder once the deadline passed.
+    pub suggested_monthly_contribution: BigDecimal,
+    /// Income minus expenses per month over the last three full months, in
+    /// the base currency.
+    pub average_monthly_net: BigDecimal,
+    pub base_currency: String,
+    /// Whether the suggested contribution fits in the average net; `None`
+    /// when the goal is in another currency than the base one.
+    pub affordable: Option<bool>,
+}
@@ -0,0 +1,69 @@
+use bigdecimal::{BigDecimal, RoundingMode, ToPrimitive, Zero};
+use chrono::{Datelike, Months, NaiveDate};
+
+use crate::budget::utils::month_start;
+
+use super::models::Goal;
+
+/// Progress of a goal at `today`, at the rate it was saved for since
+/// `started`.
+pub struct Projection {
+    pub remaining: BigDecimal,
+    pub percent_saved: BigDecimal,
+    pub months_left: u32,
+    pub monthly_rate: BigDecimal,
+    pub projected_amount: BigDecimal,
+    pub projected_completion: Option<NaiveDate>,
+    pub on_track: bool,
+    pub suggested_monthly_contribution: BigDecimal,
+}
+
+impl Projection {
+    pub fn new(goal: &Goal, started: NaiveDate, today: NaiveDate) -> Self {
+        let zero = BigDecimal::zero();
+        let remaining = (&goal.target_amount - &goal.saved).max(zero.clone());
+        let percent_saved = (&goal.saved * BigDecimal::from(100) / &goal.target_amount)
+            .with_scale_round(1, RoundingMode::HalfUp);
+
+        // The current month counts as elapsed, with what was already put
+        // aside in it
+        let months_elapsed = months_between(started, today).max(0) + 1;
+        let months_left = if goal.deadline < today {
+            0
+        } else {
+            months_between(today, goal.deadline) as u32 + 1
+        };
+        let monthly_rate = (&goal.saved / BigDecimal::from(months_elapsed))
+            .max(zero.clone())
+            .with_scale_round(2, RoundingMode::HalfUp);
+
+        let projected_amount =
+            &goal.saved + &monthly_rate * BigDecimal::from(months_left.saturating_sub(1));
+        let projected_completion = if remaining.is_zero() || monthly_rate.is_zero() {
+            None
+        } else {
+            (&remaining / &monthly_rate)
+                .with_scale_round(0, RoundingMode::Ceiling)
+                .to_u32()
+                .and_then(|months| month_start(today).checked_add_months(Months::new(months)))
+        };
+        let suggested_monthly_contribution = (&remaining / BigDecimal::from(months_left.max(1)))
+            .with_scale_round(2, RoundingMode::Up);
+
+        Projection {
+            on_track: projected_amount >= goal.target_amount,
+            remaining,
+            percent_saved,
+            months_left,
+            monthly_rate,
+            projected_amount,
+            projected_completion,
+            suggested_monthly_contribution,
+        }
+    }
+}
+
+/// Calendar months from `from`'s month to `to`'s.
+fn months_between(from: NaiveDate, to: NaiveDate) -> i32 {
+    (to.year() - from.year()) * 12 + to.month() as i32 - from.month() as i32
+}
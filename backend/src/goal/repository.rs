@@ -0,0 +1,232 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+use super::{
+    models::{Contribution, Goal},
+    utils::{CreateContributionPayload, CreateGoalPayload, UpdateGoalPayload},
+};
+
+pub struct GoalRepository {
+    pool: Arc<PgPool>,
+}
+
+impl GoalRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        GoalRepository { pool }
+    }
+
+    pub async fn create_goal(
+        &self,
+        payload: CreateGoalPayload,
+        user_id: i32,
+    ) -> anyhow::Result<Goal> {
+        sqlx::query_as!(
+            Goal,
+            r#"
+    INSERT INTO goals (user_id, name, target_amount, currency, deadline)
+    VALUES ($1, $2, $3, COALESCE($4, (SELECT base_currency FROM users WHERE id = $1)), $5)
+    RETURNING id, name, target_amount, currency, deadline, 0::NUMERIC AS "saved!";
+    "#,
+            user_id,
+            payload.name,
+            payload.target_amount,
+            payload.currency,
+            payload.deadline,
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context("Failed to create goal")
+    }
+
+    /// Goals of the user, closest deadline first.
+    pub async fn find_goals(&self, user_id: i32) -> anyhow::Result<Vec<Goal>> {
+        sqlx::query_as!(
+            Goal,
+            r#"
+    SELECT id, name, target_amount, currency, deadline,
+           (SELECT COALESCE(SUM(amount), 0) FROM goal_contributions WHERE goal_id = g.id)
+               AS "saved!"
+    FROM goals g
+    WHERE user_id = $1
+    ORDER BY deadline, name;
+    "#,
+            user_id
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get goals by user_id: {}", user_id))
+    }
+
+    pub async fn find_goal(&self, id: i32, user_id: i32) -> anyhow::Result<Option<Goal>> {
+        sqlx::query_as!(
+            Goal,
+            r#"
+    SELECT id, name, target_amount, currency, deadline,
+           (SELECT COALESCE(SUM(amount), 0) FROM goal_contributions WHERE goal_id = g.id)
+               AS "saved!"
+    FROM goals g
+    WHERE id = $1 AND user_id = $2;
+    "#,
+            id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to get goal by id: {}", id))
+    }
+
+    pub async fn exists_by_name(
+        &self,
+        user_id: i32,
+        name: &str,
+        exclude_id: Option<i32>,
+    ) -> anyhow::Result<bool> {
+        sqlx::query_scalar!(
+            r#"
+    SELECT EXISTS (
+        SELECT 1 FROM goals
+        WHERE user_id = $1 AND name = $2 AND id IS DISTINCT FROM $3
+    ) AS "exists!";
+    "#,
+            user_id,
+            name,
+            exclude_id
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context("Failed to check if goal exists")
+    }
+
+    pub async fn update_goal(
+        &self,
+        id: i32,
+        user_id: i32,
+        payload: UpdateGoalPayload,
+    ) -> anyhow::Result<Option<Goal>> {
+        sqlx::query_as!(
+            Goal,
+            r#"
+    UPDATE goals g
+    SET name = COALESCE($1, name),
+        target_amount = COALESCE($2, target_amount),
+        deadline = COALESCE($3, deadline)
+    WHERE id = $4 AND user_id = $5
+    RETURNING id, name, target_amount, currency, deadline,
+              (SELECT COALESCE(SUM(amount), 0) FROM goal_contributions WHERE goal_id = g.id)
+                  AS "saved!";
+    "#,
+            payload.name,
+            payload.target_amount,
+            payload.deadline,
+            id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to update goal by id: {}", id))
+    }
+
+    pub async fn delete_goal(&self, id: i32, user_id: i32) -> anyhow::Result<Option<i32>> {
+        sqlx::query_scalar!(
+            "DELETE FROM goals WHERE id = $1 AND user_id = $2 RETURNING id;",
+            id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to delete goal by id: {}", id))
+    }
+
+    /// The earlier of the goal's creation and its first contribution.
+    pub async fn find_start(&self, goal_id: i32) -> anyhow::Result<NaiveDate> {
+        sqlx::query_scalar!(
+            r#"
+    SELECT LEAST(g.created_at::DATE, MIN(c.contribution_date)) AS "started!"
+    FROM goals g
+    LEFT JOIN goal_contributions c ON c.goal_id = g.id
+    WHERE g.id = $1
+    GROUP BY g.id;
+    "#,
+            goal_id
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context(format!("Failed to get start of goal id: {}", goal_id))
+    }
+
+    /// Contributions of the goal, newest first.
+    pub async fn find_contributions(&self, goal_id: i32) -> anyhow::Result<Vec<Contribution>> {
+        sqlx::query_as!(
+            Contribution,
+            r#"
+    SELECT id, goal_id, amount, contribution_date, note
+    FROM goal_contributions
+    WHERE goal_id = $1
+    ORDER BY contribution_date DESC, id DESC;
+    "#,
+            goal_id
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to get contributions of goal id: {}",
+            goal_id
+        ))
+    }
+
+    pub async fn find_contribution(
+        &self,
+        id: i32,
+        goal_id: i32,
+    ) -> anyhow::Result<Option<Contribution>> {
+        sqlx::query_as!(
+            Contribution,
+            r#"
+    SELECT id, goal_id, amount, contribution_date, note
+    FROM goal_contributions
+    WHERE id = $1 AND goal_id = $2;
+    "#,
+            id,
+            goal_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to get contribution by id: {}", id))
+    }
+
+    pub async fn create_contribution(
+        &self,
+        goal_id: i32,
+        payload: CreateContributionPayload,
+    ) -> anyhow::Result<Contribution> {
+        sqlx::query_as!(
+            Contribution,
+            r#"
+    INSERT INTO goal_contributions (goal_id, amount, contribution_date, note)
+    VALUES ($1, $2, COALESCE($3, CURRENT_DATE), $4)
+    RETURNING id, goal_id, amount, contribution_date, note;
+    "#,
+            goal_id,
+            payload.amount,
+            payload.contribution_date,
+            payload.note,
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context("Failed to create contribution")
+    }
+
+    pub async fn delete_contribution(&self, id: i32, goal_id: i32) -> anyhow::Result<Option<i32>> {
+        sqlx::query_scalar!(
+            "DELETE FROM goal_contributions WHERE id = $1 AND goal_id = $2 RETURNING id;",
+            id,
+            goal_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to delete contribution by id: {}", id))
+    }
+}
@@ -0,0 +1,79 @@
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+use crate::{currency::utils::validate_currency, expense::utils::validate_amount};
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateGoalPayload {
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Name must be between 1 and 100 characters"
+    ))]
+    pub name: String,
+
+    #[validate(custom(function = "validate_amount"))]
+    pub target_amount: BigDecimal,
+
+    /// Defaults to the user's base currency. Cannot be changed later.
+    #[validate(custom(function = "validate_currency"))]
+    pub currency: Option<String>,
+
+    #[validate(custom(function = "validate_deadline"))]
+    pub deadline: NaiveDate,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct UpdateGoalPayload {
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Name must be between 1 and 100 characters"
+    ))]
+    pub name: Option<String>,
+
+    #[validate(custom(function = "validate_amount"))]
+    pub target_amount: Option<BigDecimal>,
+
+    #[validate(custom(function = "validate_deadline"))]
+    pub deadline: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateContributionPayload {
+    /// Negative to take money back out.
+    #[validate(custom(function = "validate_contribution_amount"))]
+    pub amount: BigDecimal,
+
+    /// Defaults to today.
+    #[validate(custom(function = "validate_contribution_date"))]
+    pub contribution_date: Option<NaiveDate>,
+
+    #[validate(length(max = 255, message = "Note too long"))]
+    pub note: Option<String>,
+}
+
+fn validate_deadline(deadline: &NaiveDate) -> Result<(), ValidationError> {
+    if *deadline < Utc::now().date_naive() {
+        return Err(ValidationError::new("deadline_must_not_be_in_the_past"));
+    }
+    Ok(())
+}
+
+fn validate_contribution_amount(amount: &BigDecimal) -> Result<(), ValidationError> {
+    if amount.is_zero() {
+        return Err(ValidationError::new("amount_must_not_be_zero"));
+    }
+    Ok(())
+}
+
+fn validate_contribution_date(date: &NaiveDate) -> Result<(), ValidationError> {
+    if *date > Utc::now().date_naive() {
+        return Err(ValidationError::new(
+            "contribution_date_must_not_be_in_the_future",
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,349 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get, post, put},
+};
+
+use crate::{
+    auth::token::claims::Claims,
+    currency::handler::ensure_currency,
+    error::AppError,
+    expense::handler::ensure_expense,
+    state::AppState,
+    user::{model::User, repository::UserRepository},
+    validation::ValidatedJson,
+};
+
+use super::{
+    models::{Group, GroupBalances, GroupDetails, GroupInvitation, Settlement, SplitExpense},
+    split::{compute_shares, settle_up, to_cents},
+    utils::{CreateGroupPayload, CreateSettlementPayload, InvitePayload, SplitExpensePayload},
+};
+
+pub async fn create_group(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(payload): ValidatedJson<CreateGroupPayload>,
+) -> Result<(StatusCode, Json<Group>), AppError> {
+    if let Some(currency) = &payload.currency {
+        ensure_currency(&state, currency).await?;
+    }
+    let group = state
+        .group_repository
+        .create_group(payload.name.trim(), payload.currency.as_deref(), claims.sub)
+        .await?;
+    Ok((StatusCode::CREATED, Json(group)))
+}
+
+pub async fn get_groups(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Vec<Group>>), AppError> {
+    let groups = state.group_repository.find_groups(claims.sub).await?;
+    Ok((StatusCode::OK, Json(groups)))
+}
+
+pub async fn get_group(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<GroupDetails>), AppError> {
+    let group = ensure_group(&state, id, claims.sub).await?;
+    let members = state.group_repository.find_members(id).await?;
+    Ok((StatusCode::OK, Json(GroupDetails { group, members })))
+}
+
+pub async fn leave_group(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    ensure_group(&state, id, claims.sub).await?;
+    let balances = state.group_repository.find_balances(id).await?;
+    let owes_or_is_owed = balances
+        .iter()
+        .any(|b| b.user_id == claims.sub && to_cents(&b.balance) != Some(0));
+    if owes_or_is_owed {
+        return Err(AppError::Conflict(
+            "Settle your balance before leaving the group".into(),
+        ));
+    }
+
+    state.group_repository.leave_group(id, claims.sub).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn invite_member(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    ValidatedJson(payload): ValidatedJson<InvitePayload>,
+) -> Result<StatusCode, AppError> {
+    let group = ensure_group(&state, id, claims.sub).await?;
+    let email = payload.email.trim().to_lowercase();
+    if state.group_repository.is_member_email(id, &email).await? {
+        return Err(AppError::Conflict("Already a member of the group".into()));
+    }
+    if state
+        .group_repository
+        .create_invitation(id, &email, claims.sub)
+        .await?
+        .is_none()
+    {
+        return Err(AppError::Conflict("Already invited to the group".into()));
+    }
+
+    // Sent in the background, the invitation is listed even if the email fails
+    let inviter = current_user(&state, claims.sub).await?;
+    let frontend_url = state.get_cached_config("frontend_url").unwrap_or_default();
+    let mail_state = state.clone();
+    tokio::spawn(async move {
+        if let Err(e) = mail_state
+            .email_service
+            .send_group_invitation_email(&email, &inviter.name, &group.name, &frontend_url)
+            .await
+        {
+            tracing::error!("Failed to send group invitation: {:?}", e);
+        }
+    });
+
+    Ok(StatusCode::CREATED)
+}
+
+pub async fn get_invitations(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Vec<GroupInvitation>>), AppError> {
+    let user = current_user(&state, claims.sub).await?;
+    let invitations = state.group_repository.find_invitations(&user.email).await?;
+    Ok((StatusCode::OK, Json(invitations)))
+}
+
+pub async fn accept_invitation(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<Group>), AppError> {
+    let user = current_user(&state, claims.sub).await?;
+    let group_id = state
+        .group_repository
+        .accept_invitation(id, &user.email, claims.sub)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Invitation not found".into()))?;
+    let group = ensure_group(&state, group_id, claims.sub).await?;
+    Ok((StatusCode::OK, Json(group)))
+}
+
+pub async fn decline_invitation(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    let user = current_user(&state, claims.sub).await?;
+    match state
+        .group_repository
+        .delete_invitation(id, &user.email)
+        .await?
+    {
+        Some(_) => Ok(StatusCode::NO_CONTENT),
+        None => Ok(StatusCode::NOT_FOUND),
+    }
+}
+
+pub async fn split_expense(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    ValidatedJson(payload): ValidatedJson<SplitExpensePayload>,
+) -> Result<(StatusCode, Json<SplitExpense>), AppError> {
+    let expense = ensure_expense(&state, id, claims.sub).await?;
+    let group = state
+        .group_repository
+        .find_group(payload.group_id, claims.sub)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("Group not found".into()))?;
+    if expense.currency != group.currency {
+        return Err(AppError::BadRequest(format!(
+            "Only expenses in {} can be split in this group",
+            group.currency
+        )));
+    }
+
+    let members: Vec<i32> = state
+        .group_repository
+        .find_members(group.id)
+        .await?
+        .into_iter()
+        .map(|m| m.user_id)
+        .collect();
+    if let Some(share) = payload
+        .shares
+        .iter()
+        .find(|s| !members.contains(&s.user_id))
+    {
+        return Err(AppError::BadRequest(format!(
+            "User {} is not a member of the group",
+            share.user_id
+        )));
+    }
+    let shares = compute_shares(&expense.amount, payload.method, &payload.shares, &members)
+        .map_err(AppError::BadRequest)?;
+
+    state
+        .group_repository
+        .set_split(expense.id, claims.sub, group.id, &shares)
+        .await?;
+    Ok((
+        StatusCode::OK,
+        Json(SplitExpense {
+            expense_id: expense.id,
+            paid_by: claims.sub,
+            amount: expense.amount,
+            currency: expense.currency,
+            description: expense.description,
+            expense_date: expense.expense_date,
+            shares,
+        }),
+    ))
+}
+
+pub async fn unsplit_expense(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    match state.group_repository.remove_split(id, claims.sub).await? {
+        Some(_) => Ok(StatusCode::NO_CONTENT),
+        None => Ok(StatusCode::NOT_FOUND),
+    }
+}
+
+pub async fn get_group_expenses(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<Vec<SplitExpense>>), AppError> {
+    ensure_group(&state, id, claims.sub).await?;
+    let expenses = state.group_repository.find_group_expenses(id).await?;
+    Ok((StatusCode::OK, Json(expenses)))
+}
+
+pub async fn get_balances(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<GroupBalances>), AppError> {
+    let group = ensure_group(&state, id, claims.sub).await?;
+    let balances = state.group_repository.find_balances(id).await?;
+    let cents: Vec<(i32, i64)> = balances
+        .iter()
+        .map(|b| (b.user_id, to_cents(&b.balance).unwrap_or_default()))
+        .collect();
+    let transfers = settle_up(&cents);
+
+    Ok((
+        StatusCode::OK,
+        Json(GroupBalances {
+            currency: group.currency,
+            balances,
+            transfers,
+        }),
+    ))
+}
+
+pub async fn create_settlement(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    ValidatedJson(payload): ValidatedJson<CreateSettlementPayload>,
+) -> Result<(StatusCode, Json<Settlement>), AppError> {
+    ensure_group(&state, id, claims.sub).await?;
+    let from_user_id = payload.from_user_id.unwrap_or(claims.sub);
+    if from_user_id == payload.to_user_id {
+        return Err(AppError::BadRequest(
+            "A settlement must be between two members".into(),
+        ));
+    }
+    // Either side can record the payment, nobody else
+    if claims.sub != from_user_id && claims.sub != payload.to_user_id {
+        return Err(AppError::BadRequest(
+            "Only settlements you take part in can be recorded".into(),
+        ));
+    }
+    let members = state.group_repository.find_members(id).await?;
+    for user_id in [from_user_id, payload.to_user_id] {
+        if !members.iter().any(|m| m.user_id == user_id) {
+            return Err(AppError::BadRequest(format!(
+                "User {} is not a member of the group",
+                user_id
+            )));
+        }
+    }
+
+    let settlement = state
+        .group_repository
+        .create_settlement(
+            id,
+            from_user_id,
+            payload.to_user_id,
+            &payload.amount,
+            payload.note.as_deref(),
+        )
+        .await?;
+    Ok((StatusCode::CREATED, Json(settlement)))
+}
+
+pub async fn get_settlements(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<Vec<Settlement>>), AppError> {
+    ensure_group(&state, id, claims.sub).await?;
+    let settlements = state.group_repository.find_settlements(id).await?;
+    Ok((StatusCode::OK, Json(settlements)))
+}
+
+/// Rejects group ids the user is not a member of.
+pub(crate) async fn ensure_group(
+    state: &AppState,
+    id: i32,
+    user_id: i32,
+) -> Result<Group, AppError> {
+    state
+        .group_repository
+        .find_group(id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Group not found".into()))
+}
+
+async fn current_user(state: &AppState, user_id: i32) -> Result<User, AppError> {
+    state
+        .user_repository
+        .find_by_id(user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".into()))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/groups", get(get_groups).post(create_group))
+        .route("/groups/{id}", get(get_group))
+        .route("/groups/{id}/leave", post(leave_group))
+        .route("/groups/{id}/invitations", post(invite_member))
+        .route("/groups/{id}/expenses", get(get_group_expenses))
+        .route("/groups/{id}/balances", get(get_balances))
+        .route(
+            "/groups/{id}/settlements",
+            get(get_settlements).post(create_settlement),
+        )
+        .route("/invitations", get(get_invitations))
+        .route("/invitations/{id}/accept", post(accept_invitation))
+        .route("/invitations/{id}", delete(decline_invitation))
+        .route(
+            "/expenses/{id}/split",
+            put(split_expense).delete(unsplit_expense),
+        )
+}
@@ -0,0 +1,90 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Group {
+    pub id: i32,
+    pub name: String,
+    pub currency: String,
+    pub created_by: Option<i32>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupMember {
+    pub user_id: i32,
+    pub name: String,
+    pub joined_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupDetails {
+    #[serde(flatten)]
+    pub group: Group,
+    pub members: Vec<GroupMember>,
+}
+
+/// An invitation waiting for the invited user's answer.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupInvitation {
+    pub id: i32,
+    pub group_id: i32,
+    pub group_name: String,
+    pub email: String,
+    pub invited_by: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpenseShare {
+    pub user_id: i32,
+    pub amount: BigDecimal,
+}
+
+/// A group expense as every member sees it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SplitExpense {
+    pub expense_id: i32,
+    /// Member who paid and owns the expense.
+    pub paid_by: i32,
+    pub amount: BigDecimal,
+    pub currency: String,
+    pub description: Option<String>,
+    pub expense_date: NaiveDateTime,
+    pub shares: Vec<ExpenseShare>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MemberBalance {
+    pub user_id: i32,
+    pub name: String,
+    /// Positive when the member is owed money, negative when they owe.
+    pub balance: BigDecimal,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Transfer {
+    pub from_user_id: i32,
+    pub to_user_id: i32,
+    pub amount: BigDecimal,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupBalances {
+    pub currency: String,
+    pub balances: Vec<MemberBalance>,
+    /// Payments that settle every balance.
+    pub transfers: Vec<Transfer>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Settlement {
+    pub id: i32,
+    pub group_id: i32,
+    pub from_user_id: i32,
+    pub to_user_id: i32,
+    pub amount: BigDecimal,
+    pub note: Option<String>,
+    pub settled_at: NaiveDateTime,
+}
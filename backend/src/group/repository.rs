@@ -0,0 +1,511 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::Context;
+use bigdecimal::BigDecimal;
+use sqlx::PgPool;
+
+use crate::expense::{
+    repository::{lock_snapshot, record_history},
+    utils::HistoryAction,
+};
+
+use super::models::{
+    ExpenseShare, Group, GroupInvitation, GroupMember, MemberBalance, Settlement, SplitExpense,
+};
+
+pub struct GroupRepository {
+    pool: Arc<PgPool>,
+}
+
+impl GroupRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        GroupRepository { pool }
+    }
+
+    /// Creates the group with its creator as first member.
+    pub async fn create_group(
+        &self,
+        name: &str,
+        currency: Option<&str>,
+        user_id: i32,
+    ) -> anyhow::Result<Group> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to start transaction")?;
+        let group = sqlx::query_as!(
+            Group,
+            r#"
+    INSERT INTO groups (name, currency, created_by)
+    VALUES ($1, COALESCE($2, (SELECT base_currency FROM users WHERE id = $3)), $3)
+    RETURNING id, name, currency, created_by, created_at;
+    "#,
+            name,
+            currency,
+            user_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .context("Failed to create group")?;
+
+        sqlx::query!(
+            "INSERT INTO group_members (group_id, user_id) VALUES ($1, $2);",
+            group.id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await
+        .context(format!("Failed to add member to group id: {}", group.id))?;
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(group)
+    }
+
+    pub async fn find_groups(&self, user_id: i32) -> anyhow::Result<Vec<Group>> {
+        sqlx::query_as!(
+            Group,
+            r#"
+    SELECT g.id, g.name, g.currency, g.created_by, g.created_at
+    FROM groups g
+    JOIN group_members m ON m.group_id = g.id
+    WHERE m.user_id = $1
+    ORDER BY g.name;
+    "#,
+            user_id
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get groups by user_id: {}", user_id))
+    }
+
+    /// Only returns the group when `user_id` is one of its members.
+    pub async fn find_group(&self, id: i32, user_id: i32) -> anyhow::Result<Option<Group>> {
+        sqlx::query_as!(
+            Group,
+            r#"
+    SELECT g.id, g.name, g.currency, g.created_by, g.created_at
+    FROM groups g
+    JOIN group_members m ON m.group_id = g.id
+    WHERE g.id = $1 AND m.user_id = $2;
+    "#,
+            id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to get group by id: {}", id))
+    }
+
+    pub async fn find_members(&self, group_id: i32) -> anyhow::Result<Vec<GroupMember>> {
+        sqlx::query_as!(
+            GroupMember,
+            r#"
+    SELECT m.user_id, u.name, m.joined_at
+    FROM group_members m
+    JOIN users u ON u.id = m.user_id
+    WHERE m.group_id = $1
+    ORDER BY m.user_id;
+    "#,
+            group_id
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get members of group id: {}", group_id))
+    }
+
+    pub async fn is_member_email(&self, group_id: i32, email: &str) -> anyhow::Result<bool> {
+        let exists = sqlx::query_scalar!(
+            r#"
+    SELECT EXISTS(
+        SELECT 1 FROM group_members m
+        JOIN users u ON u.id = m.user_id
+        WHERE m.group_id = $1 AND LOWER(u.email) = LOWER($2)
+    ) AS "exists!";
+    "#,
+            group_id,
+            email
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context("Failed to check group membership")?;
+        Ok(exists)
+    }
+
+    /// `None` when the email already has a pending invitation to the group.
+    pub async fn create_invitation(
+        &self,
+        group_id: i32,
+        email: &str,
+        invited_by: i32,
+    ) -> anyhow::Result<Option<i32>> {
+        sqlx::query_scalar!(
+            r#"
+    INSERT INTO group_invitations (group_id, email, invited_by)
+    VALUES ($1, LOWER($2), $3)
+    ON CONFLICT (group_id, email) DO NOTHING
+    RETURNING id;
+    "#,
+            group_id,
+            email,
+            invited_by
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to invite to group id: {}", group_id))
+    }
+
+    pub async fn find_invitations(&self, email: &str) -> anyhow::Result<Vec<GroupInvitation>> {
+        sqlx::query_as!(
+            GroupInvitation,
+            r#"
+    SELECT i.id, i.group_id, g.name AS group_name, i.email,
+           u.name AS invited_by, i.created_at
+    FROM group_invitations i
+    JOIN groups g ON g.id = i.group_id
+    JOIN users u ON u.id = i.invited_by
+    WHERE i.email = LOWER($1)
+    ORDER BY i.created_at DESC;
+    "#,
+            email
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context("Failed to get group invitations")
+    }
+
+    /// Turns the invitation into a membership; returns the joined group id.
+    pub async fn accept_invitation(
+        &self,
+        id: i32,
+        email: &str,
+        user_id: i32,
+    ) -> anyhow::Result<Option<i32>> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to start transaction")?;
+        let group_id = sqlx::query_scalar!(
+            "DELETE FROM group_invitations WHERE id = $1 AND email = LOWER($2) RETURNING group_id;",
+            id,
+            email
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .context(format!("Failed to accept invitation id: {}", id))?;
+        let Some(group_id) = group_id else {
+            return Ok(None);
+        };
+
+        sqlx::query!(
+            r#"
+    INSERT INTO group_members (group_id, user_id) VALUES ($1, $2)
+    ON CONFLICT (group_id, user_id) DO NOTHING;
+    "#,
+            group_id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await
+        .context(format!("Failed to add member to group id: {}", group_id))?;
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(Some(group_id))
+    }
+
+    pub async fn delete_invitation(&self, id: i32, email: &str) -> anyhow::Result<Option<i32>> {
+        sqlx::query_scalar!(
+            "DELETE FROM group_invitations WHERE id = $1 AND email = LOWER($2) RETURNING id;",
+            id,
+            email
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to delete invitation id: {}", id))
+    }
+
+    /// Removes the member; the group itself goes away with its last member.
+    pub async fn leave_group(&self, group_id: i32, user_id: i32) -> anyhow::Result<()> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to start transaction")?;
+        sqlx::query!(
+            "DELETE FROM group_members WHERE group_id = $1 AND user_id = $2;",
+            group_id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await
+        .context(format!("Failed to leave group id: {}", group_id))?;
+
+        let remaining = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM group_members WHERE group_id = $1;"#,
+            group_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .context(format!("Failed to count members of group id: {}", group_id))?;
+        if remaining == 0 {
+            // Expenses stay with their owners, without the shares
+            sqlx::query!(
+                r#"
+    DELETE FROM expense_splits
+    WHERE expense_id IN (SELECT id FROM expenses WHERE group_id = $1);
+    "#,
+                group_id
+            )
+            .execute(&mut *tx)
+            .await
+            .context(format!("Failed to remove splits of group id: {}", group_id))?;
+            sqlx::query!("DELETE FROM groups WHERE id = $1;", group_id)
+                .execute(&mut *tx)
+                .await
+                .context(format!("Failed to delete group id: {}", group_id))?;
+        }
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(())
+    }
+
+    /// Attaches the user's expense to the group and replaces its shares.
+    pub async fn set_split(
+        &self,
+        expense_id: i32,
+        user_id: i32,
+        group_id: i32,
+        shares: &[ExpenseShare],
+    ) -> anyhow::Result<()> {
+        let user_ids: Vec<i32> = shares.iter().map(|s| s.user_id).collect();
+        let amounts: Vec<_> = shares.iter().map(|s| s.amount.clone()).collect();
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to start transaction")?;
+        let before = lock_snapshot(&mut tx, expense_id, user_id)
+            .await?
+            .context(format!("Expense not found by id: {}", expense_id))?;
+        sqlx::query!(
+            "UPDATE expenses SET group_id = $2, version = version + 1 WHERE id = $1;",
+            expense_id,
+            group_id
+        )
+        .execute(&mut *tx)
+        .await
+        .context(format!("Failed to split expense id: {}", expense_id))?;
+        sqlx::query!(
+            "DELETE FROM expense_splits WHERE expense_id = $1;",
+            expense_id
+        )
+        .execute(&mut *tx)
+        .await
+        .context(format!(
+            "Failed to clear splits of expense id: {}",
+            expense_id
+        ))?;
+        sqlx::query!(
+            r#"
+    INSERT INTO expense_splits (expense_id, user_id, amount)
+    SELECT $1, * FROM UNNEST($2::INTEGER[], $3::NUMERIC[]);
+    "#,
+            expense_id,
+            &user_ids,
+            &amounts
+        )
+        .execute(&mut *tx)
+        .await
+        .context(format!(
+            "Failed to save splits of expense id: {}",
+            expense_id
+        ))?;
+        record_history(
+            &mut tx,
+            expense_id,
+            user_id,
+            HistoryAction::Update,
+            Some(before),
+        )
+        .await?;
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(())
+    }
+
+    /// Makes the expense personal again; `None` when it was not split.
+    pub async fn remove_split(&self, expense_id: i32, user_id: i32) -> anyhow::Result<Option<i32>> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to start transaction")?;
+        let Some(before) = lock_snapshot(&mut tx, expense_id, user_id).await? else {
+            return Ok(None);
+        };
+        let updated = sqlx::query_scalar!(
+            r#"
+    UPDATE expenses SET group_id = NULL, version = version + 1
+    WHERE id = $1 AND group_id IS NOT NULL
+    RETURNING id;
+    "#,
+            expense_id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .context(format!("Failed to unsplit expense id: {}", expense_id))?;
+        if updated.is_none() {
+            return Ok(None);
+        }
+        sqlx::query!(
+            "DELETE FROM expense_splits WHERE expense_id = $1;",
+            expense_id
+        )
+        .execute(&mut *tx)
+        .await
+        .context(format!(
+            "Failed to clear splits of expense id: {}",
+            expense_id
+        ))?;
+        record_history(
+            &mut tx,
+            expense_id,
+            user_id,
+            HistoryAction::Update,
+            Some(before),
+        )
+        .await?;
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(updated)
+    }
+
+    pub async fn find_group_expenses(&self, group_id: i32) -> anyhow::Result<Vec<SplitExpense>> {
+        let rows = sqlx::query!(
+            r#"
+    SELECT id, user_id, amount, currency, description, expense_date
+    FROM expenses
+    WHERE group_id = $1 AND deleted_at IS NULL
+    ORDER BY expense_date DESC, id DESC;
+    "#,
+            group_id
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get expenses of group id: {}", group_id))?;
+
+        let share_rows = sqlx::query!(
+            r#"
+    SELECT s.expense_id, s.user_id, s.amount
+    FROM expense_splits s
+    JOIN expenses e ON e.id = s.expense_id
+    WHERE e.group_id = $1 AND e.deleted_at IS NULL
+    ORDER BY s.user_id;
+    "#,
+            group_id
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get splits of group id: {}", group_id))?;
+
+        let mut shares: HashMap<i32, Vec<ExpenseShare>> = HashMap::new();
+        for row in share_rows {
+            shares
+                .entry(row.expense_id)
+                .or_default()
+                .push(ExpenseShare {
+                    user_id: row.user_id,
+                    amount: row.amount,
+                });
+        }
+
+        Ok(rows
+            .into_iter()
+            .map(|row| SplitExpense {
+                expense_id: row.id,
+                paid_by: row.user_id,
+                amount: row.amount,
+                currency: row.currency,
+                description: row.description,
+                expense_date: row.expense_date,
+                shares: shares.remove(&row.id).unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    /// What each member paid minus their shares, corrected by the
+    /// settlements they sent and received.
+    pub async fn find_balances(&self, group_id: i32) -> anyhow::Result<Vec<MemberBalance>> {
+        sqlx::query_as!(
+            MemberBalance,
+            r#"
+    SELECT m.user_id, u.name,
+           COALESCE((SELECT SUM(e.amount) FROM expenses e
+                     WHERE e.group_id = $1 AND e.user_id = m.user_id
+                       AND e.deleted_at IS NULL), 0)
+         - COALESCE((SELECT SUM(s.amount) FROM expense_splits s
+                     JOIN expenses e ON e.id = s.expense_id
+                     WHERE e.group_id = $1 AND s.user_id = m.user_id
+                       AND e.deleted_at IS NULL), 0)
+         + COALESCE((SELECT SUM(st.amount) FROM settlements st
+                     WHERE st.group_id = $1 AND st.from_user_id = m.user_id), 0)
+         - COALESCE((SELECT SUM(st.amount) FROM settlements st
+                     WHERE st.group_id = $1 AND st.to_user_id = m.user_id), 0)
+           AS "balance!"
+    FROM group_members m
+    JOIN users u ON u.id = m.user_id
+    WHERE m.group_id = $1
+    ORDER BY m.user_id;
+    "#,
+            group_id
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get balances of group id: {}", group_id))
+    }
+
+    pub async fn create_settlement(
+        &self,
+        group_id: i32,
+        from_user_id: i32,
+        to_user_id: i32,
+        amount: &BigDecimal,
+        note: Option<&str>,
+    ) -> anyhow::Result<Settlement> {
+        sqlx::query_as!(
+            Settlement,
+            r#"
+    INSERT INTO settlements (group_id, from_user_id, to_user_id, amount, note)
+    VALUES ($1, $2, $3, $4, $5)
+    RETURNING id, group_id, from_user_id, to_user_id, amount, note, settled_at;
+    "#,
+            group_id,
+            from_user_id,
+            to_user_id,
+            amount,
+            note
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to create settlement in group id: {}",
+            group_id
+        ))
+    }
+
+    pub async fn find_settlements(&self, group_id: i32) -> anyhow::Result<Vec<Settlement>> {
+        sqlx::query_as!(
+            Settlement,
+            r#"
+    SELECT id, group_id, from_user_id, to_user_id, amount, note, settled_at
+    FROM settlements
+    WHERE group_id = $1
+    ORDER BY settled_at DESC, id DESC;
+    "#,
+            group_id
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to get settlements of group id: {}",
+            group_id
+        ))
+    }
+}
@@ -0,0 +1,270 @@
+//! Share and settlement arithmetic, done in integer cents so that shares
+//! always add up to the expense amount exactly.
+
+use std::collections::BTreeMap;
+
+use bigdecimal::{BigDecimal, ToPrimitive};
+
+use super::{
+    models::{ExpenseShare, Transfer},
+    utils::{SplitMethod, SplitShareInput},
+};
+
+/// `None` when the amount has more than two decimals or does not fit.
+pub fn to_cents(amount: &BigDecimal) -> Option<i64> {
+    let cents = amount * BigDecimal::from(100);
+    if !cents.is_integer() {
+        return None;
+    }
+    cents.to_i64()
+}
+
+pub fn from_cents(cents: i64) -> BigDecimal {
+    BigDecimal::new(cents.into(), 2)
+}
+
+/// Splits `amount` between the members of `shares`. With the `equal` method
+/// `members` is used when `shares` is empty. Returns the shares sorted by
+/// user id, or a message describing why the split is invalid.
+pub fn compute_shares(
+    amount: &BigDecimal,
+    method: SplitMethod,
+    shares: &[SplitShareInput],
+    members: &[i32],
+) -> Result<Vec<ExpenseShare>, String> {
+    let total = to_cents(amount).ok_or("Amount must have at most 2 decimals")?;
+
+    let mut inputs: BTreeMap<i32, Option<&BigDecimal>> = BTreeMap::new();
+    if shares.is_empty() && method == SplitMethod::Equal {
+        inputs.extend(members.iter().map(|&user_id| (user_id, None)));
+    }
+    for share in shares {
+        if inputs.insert(share.user_id, share.value.as_ref()).is_some() {
+            return Err(format!("User {} appears twice in the split", share.user_id));
+        }
+    }
+    if inputs.is_empty() {
+        return Err("A split needs at least one member".into());
+    }
+
+    let cents: Vec<(i32, i64)> = match method {
+        SplitMethod::Equal => {
+            let weights: Vec<(i32, i64)> = inputs.keys().map(|&user_id| (user_id, 1)).collect();
+            distribute(total, &weights)
+        }
+        SplitMethod::Percentage => {
+            // Percentages are weighted in hundredths of a percent
+            let mut weights = Vec::with_capacity(inputs.len());
+            for (&user_id, value) in &inputs {
+                let percent = value.ok_or(format!("Missing percentage for user {}", user_id))?;
+                let weight = to_cents(percent)
+                    .filter(|w| *w >= 0)
+                    .ok_or(format!("Invalid percentage for user {}", user_id))?;
+                weights.push((user_id, weight));
+            }
+            if weights.iter().map(|(_, w)| w).sum::<i64>() != 10_000 {
+                return Err("Percentages must add up to 100".into());
+            }
+            distribute(total, &weights)
+        }
+        SplitMethod::Exact => {
+            let mut exact = Vec::with_capacity(inputs.len());
+            for (&user_id, value) in &inputs {
+                let amount = value.ok_or(format!("Missing amount for user {}", user_id))?;
+                let cents = to_cents(amount)
+                    .filter(|c| *c >= 0)
+                    .ok_or(format!("Invalid amount for user {}", user_id))?;
+                exact.push((user_id, cents));
+            }
+            if exact.iter().map(|(_, c)| c).sum::<i64>() != total {
+                return Err("Amounts must add up to the expense amount".into());
+            }
+            exact
+        }
+    };
+
+    Ok(cents
+        .into_iter()
+        .map(|(user_id, cents)| ExpenseShare {
+            user_id,
+            amount: from_cents(cents),
+        })
+        .collect())
+}
+
+/// Shares `total` proportionally to `weights` (sorted by user id, summing to
+/// more than zero). Cents lost to rounding go one each to the first users.
+fn distribute(total: i64, weights: &[(i32, i64)]) -> Vec<(i32, i64)> {
+    let weight_sum: i64 = weights.iter().map(|(_, w)| w).sum();
+    let mut shares: Vec<(i32, i64)> = weights
+        .iter()
+        .map(|&(user_id, weight)| {
+            let cents = (total as i128 * weight as i128 / weight_sum as i128) as i64;
+            (user_id, cents)
+        })
+        .collect();
+
+    let mut remainder = total - shares.iter().map(|(_, c)| c).sum::<i64>();
+    for (_, cents) in shares.iter_mut() {
+        if remainder == 0 {
+            break;
+        }
+        *cents += 1;
+        remainder -= 1;
+    }
+    shares
+}
+
+/// Payments that bring every balance (in cents, summing to zero) back to
+/// zero: the largest debtor repeatedly pays the largest creditor, which
+/// needs at most one transfer fewer than there are members with a balance.
+pub fn settle_up(balances: &[(i32, i64)]) -> Vec<Transfer> {
+    let mut debtors: Vec<(i32, i64)> = balances
+        .iter()
+        .filter(|(_, b)| *b < 0)
+        .map(|&(user_id, b)| (user_id, -b))
+        .collect();
+    let mut creditors: Vec<(i32, i64)> = balances.iter().filter(|(_, b)| *b > 0).copied().collect();
+    // Largest last so they can be popped; ties broken by user id
+    debtors.sort_by_key(|&(user_id, owed)| (owed, -user_id));
+    creditors.sort_by_key(|&(user_id, due)| (due, -user_id));
+
+    let mut transfers = Vec::new();
+    while let (Some((debtor, owed)), Some((creditor, due))) = (debtors.pop(), creditors.pop()) {
+        let cents = owed.min(due);
+        transfers.push(Transfer {
+            from_user_id: debtor,
+            to_user_id: creditor,
+            amount: from_cents(cents),
+        });
+        if owed > cents {
+            insert_sorted(&mut debtors, (debtor, owed - cents));
+        }
+        if due > cents {
+            insert_sorted(&mut creditors, (creditor, due - cents));
+        }
+    }
+    transfers
+}
+
+fn insert_sorted(list: &mut Vec<(i32, i64)>, entry: (i32, i64)) {
+    let key = |&(user_id, cents): &(i32, i64)| (cents, -user_id);
+    let index = list.partition_point(|e| key(e) < key(&entry));
+    list.insert(index, entry);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn amount(raw: &str) -> BigDecimal {
+        BigDecimal::from_str(raw).unwrap()
+    }
+
+    fn share(user_id: i32, value: &str) -> SplitShareInput {
+        SplitShareInput {
+            user_id,
+            value: Some(amount(value)),
+        }
+    }
+
+    fn cents_of(shares: &[ExpenseShare]) -> Vec<(i32, i64)> {
+        shares
+            .iter()
+            .map(|s| (s.user_id, to_cents(&s.amount).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn equal_shares_sum_to_the_total() {
+        for (total, members) in [("100.00", 3), ("0.01", 2), ("10.00", 7), ("999.99", 4)] {
+            let members: Vec<i32> = (1..=members).collect();
+            let shares = compute_shares(&amount(total), SplitMethod::Equal, &[], &members).unwrap();
+            let sum: i64 = cents_of(&shares).iter().map(|(_, c)| c).sum();
+            assert_eq!(
+                sum,
+                to_cents(&amount(total)).unwrap(),
+                "{} between {:?}",
+                total,
+                members
+            );
+        }
+    }
+
+    #[test]
+    fn percentage_shares_sum_to_the_total() {
+        let shares = [share(1, "33.33"), share(2, "33.33"), share(3, "33.34")];
+        let split =
+            compute_shares(&amount("10.01"), SplitMethod::Percentage, &shares, &[]).unwrap();
+        assert_eq!(cents_of(&split).iter().map(|(_, c)| c).sum::<i64>(), 1001);
+    }
+
+    #[test]
+    fn leftover_cents_go_to_the_lowest_user_ids() {
+        // Given in any order, shares come back sorted by user id
+        let shares = [
+            SplitShareInput {
+                user_id: 9,
+                value: None,
+            },
+            SplitShareInput {
+                user_id: 2,
+                value: None,
+            },
+            SplitShareInput {
+                user_id: 5,
+                value: None,
+            },
+        ];
+        let split = compute_shares(&amount("100.00"), SplitMethod::Equal, &shares, &[]).unwrap();
+        assert_eq!(cents_of(&split), vec![(2, 3334), (5, 3333), (9, 3333)]);
+
+        assert_eq!(
+            distribute(5, &[(1, 1), (2, 1), (3, 1)]),
+            vec![(1, 2), (2, 2), (3, 1)]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_splits() {
+        let split = |method, shares: &[SplitShareInput]| {
+            compute_shares(&amount("10.00"), method, shares, &[])
+        };
+        assert!(split(SplitMethod::Percentage, &[share(1, "50"), share(2, "40")]).is_err());
+        assert!(split(SplitMethod::Exact, &[share(1, "5.00"), share(2, "4.99")]).is_err());
+        assert!(split(SplitMethod::Exact, &[share(1, "5.00"), share(1, "5.00")]).is_err());
+        assert!(split(SplitMethod::Equal, &[]).is_err());
+        assert!(compute_shares(&amount("1.005"), SplitMethod::Equal, &[], &[1, 2]).is_err());
+    }
+
+    #[test]
+    fn settlements_zero_every_balance() {
+        let cases: [&[(i32, i64)]; 4] = [
+            &[(1, 3000), (2, -1000), (3, -2000)],
+            &[(1, 1), (2, 1), (3, -2)],
+            &[(1, 2500), (2, -700), (3, 1200), (4, -3000), (5, 0)],
+            &[],
+        ];
+        for balances in cases {
+            let transfers = settle_up(balances);
+            let mut remaining: BTreeMap<i32, i64> = balances.iter().copied().collect();
+            for transfer in &transfers {
+                let cents = to_cents(&transfer.amount).unwrap();
+                assert!(cents > 0);
+                *remaining.get_mut(&transfer.from_user_id).unwrap() += cents;
+                *remaining.get_mut(&transfer.to_user_id).unwrap() -= cents;
+            }
+            assert!(
+                remaining.values().all(|b| *b == 0),
+                "{:?} left {:?}",
+                balances,
+                remaining
+            );
+
+            let with_balance = balances.iter().filter(|(_, b)| *b != 0).count();
+            assert!(transfers.len() < with_balance.max(1));
+        }
+    }
+}
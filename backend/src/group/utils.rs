@@ -0,0 +1,92 @@
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+use crate::{currency::utils::validate_currency, expense::utils::validate_amount};
+
+use super::split::to_cents;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateGroupPayload {
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Name must be between 1 and 100 characters"
+    ))]
+    pub name: String,
+
+    /// Defaults to the creator's base currency.
+    #[validate(custom(function = "validate_currency"))]
+    pub currency: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct InvitePayload {
+    #[validate(
+        email(message = "Invalid email format"),
+        length(max = 50, message = "Email too long")
+    )]
+    pub email: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitMethod {
+    /// Same amount for everyone, leftover cents going to the lowest user ids.
+    #[default]
+    Equal,
+    /// `value` of each share is a percentage; they must add up to 100.
+    Percentage,
+    /// `value` of each share is an amount; they must add up to the expense.
+    Exact,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitShareInput {
+    pub user_id: i32,
+
+    /// Ignored by the `equal` method.
+    pub value: Option<BigDecimal>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct SplitExpensePayload {
+    #[validate(range(min = 1, message = "Invalid group"))]
+    pub group_id: i32,
+
+    #[serde(default)]
+    pub method: SplitMethod,
+
+    /// With the `equal` method, an empty list splits between every member.
+    #[serde(default)]
+    #[validate(length(max = 50, message = "Too many shares"))]
+    pub shares: Vec<SplitShareInput>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_settlement"))]
+pub struct CreateSettlementPayload {
+    #[validate(range(min = 1, message = "Invalid member"))]
+    pub from_user_id: Option<i32>,
+
+    #[validate(range(min = 1, message = "Invalid member"))]
+    pub to_user_id: i32,
+
+    #[validate(custom(function = "validate_amount"))]
+    pub amount: BigDecimal,
+
+    #[validate(length(max = 255, message = "Note too long"))]
+    pub note: Option<String>,
+}
+
+fn validate_settlement(payload: &CreateSettlementPayload) -> Result<(), ValidationError> {
+    if to_cents(&payload.amount).is_none() {
+        return Err(ValidationError::new("amount_must_have_at_most_2_decimals"));
+    }
+    if payload.from_user_id == Some(payload.to_user_id) {
+        return Err(ValidationError::new(
+            "settlement_must_be_between_two_members",
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,171 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use axum::{
+    Json, Router,
+    extract::{DefaultBodyLimit, Multipart, State},
+    http::StatusCode,
+    routing::post,
+};
+use validator::Validate;
+
+use crate::{
+    auth::token::claims::Claims, currency::handler::ensure_currency, error::AppError,
+    rule::handler::load_rules, state::AppState,
+};
+
+use super::{
+    models::{ImportPreview, ImportResult, ImportRow},
+    parser::parse_csv,
+    utils::{ImportMapping, MAX_IMPORT_SIZE},
+};
+
+/// Returns every parsed line, with its category, the tags the rules add and
+/// whether it looks like an expense that was already recorded. Nothing is
+/// stored.
+pub async fn preview_import(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    multipart: Multipart,
+) -> Result<(StatusCode, Json<ImportPreview>), AppError> {
+    let (_, rows) = prepare_import(&state, claims.sub, multipart).await?;
+    Ok((StatusCode::OK, Json(ImportPreview::new(rows))))
+}
+
+/// Imports the valid lines of the file. Duplicates are skipped unless the
+/// mapping sets `include_duplicates`.
+pub async fn import_expenses(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    multipart: Multipart,
+) -> Result<(StatusCode, Json<ImportResult>), AppError> {
+    let (mapping, rows) = prepare_import(&state, claims.sub, multipart).await?;
+
+    let skipped_invalid = rows.iter().filter(|r| !r.is_valid()).count();
+    let (duplicates, to_import): (Vec<&ImportRow>, Vec<&ImportRow>) = rows
+        .iter()
+        .filter(|r| r.is_valid())
+        .partition(|r| r.duplicate && !mapping.include_duplicates);
+
+    let imported = state
+        .import_repository
+        .insert_expenses(&to_import, mapping.currency.as_deref(), claims.sub)
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ImportResult {
+            imported: imported as usize,
+            skipped_duplicates: duplicates.len(),
+            skipped_invalid,
+        }),
+    ))
+}
+
+/// Reads the `file` and `mapping` fields, parses the file, runs the rules
+/// and resolves categories and duplicates for each row.
+async fn prepare_import(
+    state: &AppState,
+    user_id: i32,
+    mut multipart: Multipart,
+) -> Result<(ImportMapping, Vec<ImportRow>), AppError> {
+    let mut file = None;
+    let mut mapping = None;
+    while let Some(mut field) = multipart.next_field().await? {
+        match field.name() {
+            Some("file") => {
+                let mut bytes = Vec::new();
+                while let Some(chunk) = field.chunk().await? {
+                    if bytes.len() + chunk.len() > MAX_IMPORT_SIZE {
+                        return Err(AppError::BadRequest(format!(
+                            "The file exceeds the {} MB limit",
+                            MAX_IMPORT_SIZE / (1024 * 1024)
+                        )));
+                    }
+                    bytes.extend_from_slice(&chunk);
+                }
+                file = Some(bytes);
+            }
+            Some("mapping") => {
+                let text = field.text().await?;
+                let parsed: ImportMapping = serde_json::from_str(&text)
+                    .map_err(|e| AppError::BadRequest(format!("Invalid mapping: {}", e)))?;
+                parsed.validate()?;
+                mapping = Some(parsed);
+            }
+            _ => continue,
+        }
+    }
+    let file = file.ok_or_else(|| AppError::BadRequest("No file uploaded".into()))?;
+    let mapping = mapping.ok_or_else(|| AppError::BadRequest("Missing column mapping".into()))?;
+    if let Some(currency) = &mapping.currency {
+        ensure_currency(state, currency).await?;
+    }
+
+    let mut rows = parse_csv(&file, &mapping).map_err(AppError::BadRequest)?;
+
+    let categories = state.category_repository.find_categories(user_id).await?;
+    let default_category = categories.iter().find(|c| c.is_default).map(|c| c.id);
+    let by_name: HashMap<String, i32> = categories
+        .iter()
+        .map(|c| (c.name.to_lowercase(), c.id))
+        .collect();
+    let rules = load_rules(state, user_id).await?;
+    for row in rows.iter_mut() {
+        let matched = match &row.amount {
+            Some(amount) => rules.evaluate(row.description.as_deref(), amount),
+            None => Default::default(),
+        };
+        row.category_id = row
+            .category
+            .as_ref()
+            .and_then(|name| by_name.get(&name.to_lowercase()).copied())
+            .or(matched.category_id)
+            .or(default_category);
+        row.tags = matched.tags;
+        row.rule_ids = matched.rule_ids;
+        if row.is_valid() && row.category_id.is_none() {
+            row.error = Some("No matching category".to_string());
+        }
+    }
+
+    let duplicates = state
+        .import_repository
+        .find_duplicates(&rows, user_id)
+        .await?;
+    for position in duplicates {
+        rows[position as usize].duplicate = true;
+    }
+
+    // Repeats within the file, after their first occurrence
+    let mut seen = HashSet::new();
+    for row in rows.iter_mut() {
+        if let (Some(date), Some(amount)) = (row.expense_date, &row.amount)
+            && row.is_valid()
+            && !seen.insert((
+                date,
+                amount.clone(),
+                row.description.as_deref().map(str::to_lowercase),
+            ))
+        {
+            row.duplicate = true;
+        }
+    }
+
+    Ok((mapping, rows))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route(
+            "/expenses/import/preview",
+            // Files are bounded by MAX_IMPORT_SIZE rather than axum's 2 MB default
+            post(preview_import).layer(DefaultBodyLimit::disable()),
+        )
+        .route(
+            "/expenses/import",
+            post(import_expenses).layer(DefaultBodyLimit::disable()),
+        )
+}
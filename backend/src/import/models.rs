@@ -0,0 +1,61 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// One CSV line as it would be imported.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportRow {
+    /// 1-based line number in the file.
+    pub line: u64,
+    pub expense_date: Option<NaiveDate>,
+    pub amount: Option<BigDecimal>,
+    pub description: Option<String>,
+    /// Raw value of the category column, if mapped.
+    pub category: Option<String>,
+    /// Category matched by name, or else set by the rules, or else the
+    /// user's default category.
+    pub category_id: Option<i32>,
+    /// Tags added by the rules.
+    pub tags: Vec<String>,
+    /// Rules matching the line, in priority order.
+    pub rule_ids: Vec<i32>,
+    /// An expense with the same date, amount and description already exists,
+    /// or appears earlier in the file.
+    pub duplicate: bool,
+    /// Why the line cannot be imported.
+    pub error: Option<String>,
+}
+
+impl ImportRow {
+    pub fn is_valid(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportPreview {
+    pub rows: Vec<ImportRow>,
+    pub valid: usize,
+    pub invalid: usize,
+    pub duplicates: usize,
+}
+
+impl ImportPreview {
+    pub fn new(rows: Vec<ImportRow>) -> Self {
+        let invalid = rows.iter().filter(|r| !r.is_valid()).count();
+        let duplicates = rows.iter().filter(|r| r.is_valid() && r.duplicate).count();
+        Self {
+            valid: rows.len() - invalid,
+            invalid,
+            duplicates,
+            rows,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportResult {
+    pub imported: usize,
+    pub skipped_duplicates: usize,
+    pub skipped_invalid: usize,
+}
@@ -0,0 +1,327 @@
+use std::str::FromStr;
+
+use bigdecimal::{BigDecimal, RoundingMode, Zero};
+use chrono::{Datelike, NaiveDate};
+
+use crate::expense::utils::validate_amount;
+
+use super::{
+    models::ImportRow,
+    utils::{ColumnRef, ImportMapping, MAX_IMPORT_ROWS},
+};
+
+/// Tried in order when the mapping has no `date_format`.
+const DATE_FORMATS: [&str; 5] = ["%d/%m/%Y", "%Y-%m-%d", "%d-%m-%Y", "%d.%m.%Y", "%d/%m/%y"];
+
+/// Column positions resolved against the header.
+struct Columns {
+    date: usize,
+    amount: usize,
+    description: usize,
+    category: Option<usize>,
+}
+
+/// Parses a bank export into rows. Errors concern the whole file (unknown
+/// column, unreadable CSV); problems with a single line are reported on its row.
+pub fn parse_csv(bytes: &[u8], mapping: &ImportMapping) -> Result<Vec<ImportRow>, String> {
+    let text = decode(bytes);
+    let delimiter = mapping.delimiter.unwrap_or_else(|| detect_delimiter(&text));
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter as u8)
+        .has_headers(mapping.has_header)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(text.as_bytes());
+
+    let headers = if mapping.has_header {
+        Some(
+            reader
+                .headers()
+                .map_err(|e| format!("Invalid CSV: {}", e))?
+                .clone(),
+        )
+    } else {
+        None
+    };
+    let columns = Columns {
+        date: resolve_column(&mapping.date, headers.as_ref())?,
+        amount: resolve_column(&mapping.amount, headers.as_ref())?,
+        description: resolve_column(&mapping.description, headers.as_ref())?,
+        category: mapping
+            .category
+            .as_ref()
+            .map(|column| resolve_column(column, headers.as_ref()))
+            .transpose()?,
+    };
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("Invalid CSV: {}", e))?;
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+        if rows.len() == MAX_IMPORT_ROWS {
+            return Err(format!(
+                "A file can contain at most {} lines",
+                MAX_IMPORT_ROWS
+            ));
+        }
+        let line = record.position().map(|p| p.line()).unwrap_or_default();
+        rows.push(parse_record(&record, line, &columns, mapping));
+    }
+    Ok(rows)
+}
+
+fn parse_record(
+    record: &csv::StringRecord,
+    line: u64,
+    columns: &Columns,
+    mapping: &ImportMapping,
+) -> ImportRow {
+    let field = |index: usize| record.get(index).filter(|v| !v.is_empty());
+
+    let expense_date =
+        field(columns.date).and_then(|raw| parse_date(raw, mapping.date_format.as_deref()));
+    let signed_amount =
+        field(columns.amount).and_then(|raw| parse_amount(raw, mapping.decimal_comma));
+    let description = field(columns.description).map(str::to_string);
+    let category = columns.category.and_then(field).map(str::to_string);
+
+    let error = if expense_date.is_none() {
+        Some("Invalid date".to_string())
+    } else if signed_amount.is_none()
+        && mapping.decimal_comma.is_none()
+        && field(columns.amount).is_some_and(|raw| is_ambiguous_comma(&clean_amount(raw)))
+    {
+        Some("Ambiguous amount, set decimal_comma".to_string())
+    } else if signed_amount.is_none() {
+        Some("Invalid amount".to_string())
+    } else if mapping.debits_only
+        && signed_amount
+            .as_ref()
+            .is_some_and(|a| *a >= BigDecimal::zero())
+    {
+        Some("Not a debit".to_string())
+    } else {
+        None
+    };
+
+    let amount = signed_amount.map(|a| a.abs().with_scale_round(2, RoundingMode::HalfUp));
+    let error = error.or_else(|| {
+        amount
+            .as_ref()
+            .filter(|a| validate_amount(a).is_err())
+            .map(|_| "Amount must be greater than zero".to_string())
+    });
+
+    ImportRow {
+        line,
+        expense_date,
+        amount,
+        description,
+        category,
+        category_id: None,
+        tags: Vec::new(),
+        rule_ids: Vec::new(),
+        duplicate: false,
+        error,
+    }
+}
+
+fn resolve_column(
+    column: &ColumnRef,
+    headers: Option<&csv::StringRecord>,
+) -> Result<usize, String> {
+    match column {
+        ColumnRef::Index(index) => Ok(*index),
+        ColumnRef::Name(name) => headers
+            .and_then(|headers| {
+                headers
+                    .iter()
+                    .position(|header| header.trim().eq_ignore_ascii_case(name.trim()))
+            })
+            .ok_or_else(|| format!("Column not found: {}", name)),
+    }
+}
+
+/// UTF-8 (with or without BOM), falling back to Latin-1, which many banks still export.
+fn decode(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => bytes.iter().map(|&b| b as char).collect(),
+    }
+}
+
+/// French exports use `;`, since `,` is their decimal separator.
+fn detect_delimiter(text: &str) -> char {
+    let first_line = text.lines().next().unwrap_or_default();
+    [';', ',', '\t']
+        .into_iter()
+        .max_by_key(|d| first_line.matches(*d).count())
+        .unwrap_or(',')
+}
+
+/// Parses `-1 234,56`, `1,234.56`, `12.50 €`... When `decimal_comma` is not
+/// given, the last of `,` and `.` is taken as the decimal separator, and a
+/// lone `,` followed by three digits (`1,234`) is rejected as ambiguous.
+pub fn parse_amount(raw: &str, decimal_comma: Option<bool>) -> Option<BigDecimal> {
+    let cleaned = clean_amount(raw);
+    let decimal_comma = match decimal_comma {
+        Some(decimal_comma) => decimal_comma,
+        None if is_ambiguous_comma(&cleaned) => return None,
+        None => match (cleaned.rfind(','), cleaned.rfind('.')) {
+            (Some(comma), Some(dot)) => comma > dot,
+            (Some(_), None) => true,
+            _ => false,
+        },
+    };
+    let normalized = if decimal_comma {
+        cleaned.replace('.', "").replace(',', ".")
+    } else {
+        cleaned.replace(',', "")
+    };
+    BigDecimal::from_str(&normalized).ok()
+}
+
+/// Keeps the digits, separators and sign.
+fn clean_amount(raw: &str) -> String {
+    raw.chars()
+        .filter(|c| c.is_ascii_digit() || matches!(c, ',' | '.' | '-' | '+'))
+        .collect()
+}
+
+/// `1,234` reads as 1234 with a thousands separator or 1.234 with a decimal
+/// comma.
+fn is_ambiguous_comma(cleaned: &str) -> bool {
+    !cleaned.contains('.')
+        && cleaned.matches(',').count() == 1
+        && cleaned.split_once(',').is_some_and(|(_, decimals)| {
+            decimals.len() == 3 && decimals.chars().all(|c| c.is_ascii_digit())
+        })
+}
+
+/// Ignores a trailing time (`2025-01-31 00:00:00`).
+pub fn parse_date(raw: &str, format: Option<&str>) -> Option<NaiveDate> {
+    let raw = raw.split_whitespace().next()?;
+    match format {
+        Some(format) => NaiveDate::parse_from_str(raw, format).ok(),
+        None => DATE_FORMATS
+            .iter()
+            .filter_map(|format| NaiveDate::parse_from_str(raw, format).ok())
+            // `%Y` also accepts two-digit years, which `%y` handles
+            .find(|date| date.year() >= 1970),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn amount(raw: &str) -> BigDecimal {
+        BigDecimal::from_str(raw).unwrap()
+    }
+
+    fn mapping(json: serde_json::Value) -> ImportMapping {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn parses_french_and_english_amounts() {
+        assert_eq!(parse_amount("1 234,56", None), Some(amount("1234.56")));
+        assert_eq!(parse_amount("-12.50 €", None), Some(amount("-12.50")));
+        assert_eq!(parse_amount("1.234,56", None), Some(amount("1234.56")));
+        assert_eq!(parse_amount("1,234.56", None), Some(amount("1234.56")));
+        assert_eq!(parse_amount("12,5", None), Some(amount("12.5")));
+        assert_eq!(parse_amount("abc", None), None);
+    }
+
+    #[test]
+    fn rejects_a_lone_comma_before_three_digits_unless_told() {
+        assert_eq!(parse_amount("1,234", None), None);
+        assert_eq!(parse_amount("1,234", Some(false)), Some(amount("1234")));
+        assert_eq!(parse_amount("1,234", Some(true)), Some(amount("1.234")));
+    }
+
+    #[test]
+    fn parses_day_first_dates() {
+        assert_eq!(parse_date("31/01/2025", None), Some(date(2025, 1, 31)));
+        assert_eq!(parse_date("05/03/24", None), Some(date(2024, 3, 5)));
+        assert_eq!(
+            parse_date("2025-01-31 00:00:00", None),
+            Some(date(2025, 1, 31))
+        );
+        assert_eq!(
+            parse_date("01/31/2025", Some("%m/%d/%Y")),
+            Some(date(2025, 1, 31))
+        );
+        assert_eq!(parse_date("31/02/2025", None), None);
+    }
+
+    #[test]
+    fn reads_latin1_with_a_detected_semicolon() {
+        let bytes = b"Date;Libell\xE9;Montant\n31/01/2025;Caf\xE9;-3,50\n";
+        let rows = parse_csv(
+            bytes,
+            &mapping(serde_json::json!({ "date": "Date", "amount": "Montant", "description": "Libellé" })),
+        )
+        .unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].description.as_deref(), Some("Café"));
+        assert_eq!(rows[0].amount, Some(amount("3.50")));
+        assert_eq!(rows[0].expense_date, Some(date(2025, 1, 31)));
+    }
+
+    #[test]
+    fn strips_the_utf8_bom_and_detects_commas() {
+        let bytes = "\u{FEFF}date,label,amount\n2025-01-31,Boulangerie,\"2,10\"\n".as_bytes();
+        let rows = parse_csv(
+            bytes,
+            &mapping(
+                serde_json::json!({ "date": "date", "amount": "amount", "description": "label" }),
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].description.as_deref(), Some("Boulangerie"));
+        assert_eq!(rows[0].amount, Some(amount("2.10")));
+    }
+
+    #[test]
+    fn keeps_only_debits_when_asked() {
+        let bytes = b"date;label;amount\n31/01/2025;Loyer;-800,00\n31/01/2025;Salaire;2 000,00\n";
+        let rows = parse_csv(
+            bytes,
+            &mapping(serde_json::json!({
+                "date": 0, "amount": 2, "description": 1, "debits_only": true
+            })),
+        )
+        .unwrap();
+
+        assert!(rows[0].is_valid());
+        assert_eq!(rows[0].amount, Some(amount("800.00")));
+        assert_eq!(rows[1].error.as_deref(), Some("Not a debit"));
+    }
+
+    #[test]
+    fn reports_an_ambiguous_amount_on_its_row() {
+        let bytes = b"date;label;amount\n31/01/2025;Loyer;1,234\n";
+        let rows = parse_csv(
+            bytes,
+            &mapping(serde_json::json!({ "date": 0, "amount": 2, "description": 1 })),
+        )
+        .unwrap();
+
+        assert_eq!(
+            rows[0].error.as_deref(),
+            Some("Ambiguous amount, set decimal_comma")
+        );
+    }
+}
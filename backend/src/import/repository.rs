@@ -0,0 +1,165 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+use crate::expense::{repository::record_history_many, utils::HistoryAction};
+
+use super::models::ImportRow;
+
+pub struct ImportRepository {
+    pool: Arc<PgPool>,
+}
+
+impl ImportRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        ImportRepository { pool }
+    }
+
+    /// Positions in `rows` of the valid rows matching an existing expense on
+    /// the same day, with the same amount and description (ignoring case).
+    pub async fn find_duplicates(
+        &self,
+        rows: &[ImportRow],
+        user_id: i32,
+    ) -> anyhow::Result<Vec<i32>> {
+        let mut positions = Vec::new();
+        let mut dates = Vec::new();
+        let mut amounts = Vec::new();
+        let mut descriptions = Vec::new();
+        for (position, row) in rows.iter().enumerate() {
+            if let (true, Some(date), Some(amount)) =
+                (row.is_valid(), row.expense_date, row.amount.clone())
+            {
+                positions.push(position as i32);
+                dates.push(date);
+                amounts.push(amount);
+                descriptions.push(row.description.clone().unwrap_or_default());
+            }
+        }
+
+        sqlx::query_scalar!(
+            r#"
+    SELECT r.position AS "position!"
+    FROM UNNEST($2::INT[], $3::DATE[], $4::NUMERIC[], $5::TEXT[])
+        AS r(position, expense_date, amount, description)
+    WHERE EXISTS (
+        SELECT 1 FROM expenses e
+        WHERE e.user_id = $1 AND e.deleted_at IS NULL
+          AND e.expense_date >= r.expense_date
+          AND e.expense_date < r.expense_date + 1
+          AND e.amount = r.amount
+          AND lower(trim(COALESCE(e.description, ''))) = lower(trim(r.description))
+    );
+    "#,
+            user_id,
+            &positions,
+            &dates,
+            &amounts,
+            &descriptions
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context("Failed to look for duplicate expenses")
+    }
+
+    /// Inserts the rows in a single statement, then their tags;
+    /// `currency` defaults to the user's base currency.
+    pub async fn insert_expenses(
+        &self,
+        rows: &[&ImportRow],
+        currency: Option<&str>,
+        user_id: i32,
+    ) -> anyhow::Result<u64> {
+        let category_ids: Vec<i32> = rows.iter().filter_map(|r| r.category_id).collect();
+        let dates: Vec<NaiveDate> = rows.iter().filter_map(|r| r.expense_date).collect();
+        let amounts: Vec<BigDecimal> = rows.iter().filter_map(|r| r.amount.clone()).collect();
+        let descriptions: Vec<Option<String>> =
+            rows.iter().map(|r| r.description.clone()).collect();
+        if [category_ids.len(), dates.len(), amounts.len()]
+            .iter()
+            .any(|&len| len != rows.len())
+        {
+            anyhow::bail!("Cannot import incomplete rows");
+        }
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to start transaction")?;
+        // Ids are taken up front so the tags can be matched to their row
+        let ids = sqlx::query_scalar!(
+            r#"
+    SELECT nextval(pg_get_serial_sequence('expenses', 'id'))::INTEGER AS "id!"
+    FROM generate_series(1, $1);
+    "#,
+            rows.len() as i32
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .context("Failed to reserve expense ids")?;
+
+        let result = sqlx::query!(
+            r#"
+    INSERT INTO expenses (id, user_id, category_id, amount, currency, description, expense_date)
+    SELECT r.id, $1, r.category_id, r.amount,
+           COALESCE($2, (SELECT base_currency FROM users WHERE id = $1)),
+           NULLIF(r.description, ''), r.expense_date::TIMESTAMP
+    FROM UNNEST($3::INT[], $4::INT[], $5::NUMERIC[], $6::TEXT[], $7::DATE[])
+        AS r(id, category_id, amount, description, expense_date);
+    "#,
+            user_id,
+            currency,
+            &ids,
+            &category_ids,
+            &amounts,
+            &descriptions as &[Option<String>],
+            &dates
+        )
+        .execute(&mut *tx)
+        .await
+        .context("Failed to import expenses")?;
+
+        let (tagged_ids, tags): (Vec<i32>, Vec<String>) = ids
+            .iter()
+            .zip(rows)
+            .flat_map(|(id, row)| row.tags.iter().map(move |tag| (*id, tag.clone())))
+            .unzip();
+        if !tags.is_empty() {
+            sqlx::query!(
+                r#"
+    INSERT INTO tags (user_id, name)
+    SELECT DISTINCT $1::INTEGER, UNNEST($2::TEXT[])
+    ON CONFLICT (user_id, name) DO NOTHING;
+    "#,
+                user_id,
+                &tags
+            )
+            .execute(&mut *tx)
+            .await
+            .context("Failed to create tags")?;
+
+            sqlx::query!(
+                r#"
+    INSERT INTO expense_tags (expense_id, tag_id)
+    SELECT r.expense_id, t.id
+    FROM UNNEST($2::INT[], $3::TEXT[]) AS r(expense_id, name)
+    JOIN tags t ON t.user_id = $1 AND t.name = r.name;
+    "#,
+                user_id,
+                &tagged_ids,
+                &tags
+            )
+            .execute(&mut *tx)
+            .await
+            .context("Failed to tag imported expenses")?;
+        }
+        record_history_many(&mut tx, &ids, user_id, HistoryAction::Create, &[]).await?;
+
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(result.rows_affected())
+    }
+}
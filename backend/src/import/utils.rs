@@ -0,0 +1,66 @@
+use serde::Deserialize;
+use validator::{Validate, ValidationError};
+
+use crate::currency::utils::validate_currency;
+
+/// Largest CSV file accepted, in bytes.
+pub const MAX_IMPORT_SIZE: usize = 2 * 1024 * 1024;
+
+/// Largest number of data lines accepted in one file.
+pub const MAX_IMPORT_ROWS: usize = 5_000;
+
+/// A CSV column, by 0-based position or by header name.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ColumnRef {
+    Index(usize),
+    Name(String),
+}
+
+/// How to read a bank export, sent as the `mapping` JSON field next to the file.
+#[derive(Debug, Deserialize, Validate)]
+pub struct ImportMapping {
+    pub date: ColumnRef,
+    pub amount: ColumnRef,
+    pub description: ColumnRef,
+    /// Matched against the user's category names; unmatched values and
+    /// a missing column use the default category.
+    pub category: Option<ColumnRef>,
+
+    /// Detected from the first line (`;` or `,`) when omitted.
+    #[validate(custom(function = "validate_delimiter"))]
+    pub delimiter: Option<char>,
+
+    #[serde(default = "default_has_header")]
+    pub has_header: bool,
+
+    /// chrono format such as `%d/%m/%Y`; common formats are tried when omitted.
+    #[validate(length(min = 2, max = 32, message = "Invalid date format"))]
+    pub date_format: Option<String>,
+
+    /// Whether amounts use a decimal comma (`1 234,56`); detected per value when omitted.
+    pub decimal_comma: Option<bool>,
+
+    /// Currency of every amount; defaults to the user's base currency.
+    #[validate(custom(function = "validate_currency"))]
+    pub currency: Option<String>,
+
+    /// Only import negative amounts (debits), as positive expenses.
+    #[serde(default)]
+    pub debits_only: bool,
+
+    /// When committing, also import the rows flagged as duplicates.
+    #[serde(default)]
+    pub include_duplicates: bool,
+}
+
+fn default_has_header() -> bool {
+    true
+}
+
+fn validate_delimiter(delimiter: &char) -> Result<(), ValidationError> {
+    if !delimiter.is_ascii() || delimiter.is_ascii_alphanumeric() || *delimiter == '"' {
+        return Err(ValidationError::new("invalid_delimiter"));
+    }
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, put},
+};
+
+use crate::{
+    account::handler::check_account,
+    auth::token::claims::Claims,
+    currency::handler::ensure_currency,
+    error::AppError,
+    state::AppState,
+    user::repository::UserRepository,
+    validation::{ValidatedJson, ValidatedQuery},
+};
+
+use super::{
+    models::{CashFlowReport, Income},
+    utils::{CashFlowQuery, CreateIncomePayload, IncomeListQuery, UpdateIncomePayload},
+};
+
+pub async fn create_income(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(payload): ValidatedJson<CreateIncomePayload>,
+) -> Result<(StatusCode, Json<Income>), AppError> {
+    if let Some(currency) = &payload.currency {
+        ensure_currency(&state, currency).await?;
+    }
+    check_account(
+        &state,
+        claims.sub,
+        payload.account_id,
+        payload.currency.as_deref(),
+    )
+    .await?;
+    let income = state
+        .income_repository
+        .create_income(payload, claims.sub)
+        .await?;
+    Ok((StatusCode::CREATED, Json(income)))
+}
+
+pub async fn get_incomes(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedQuery(query): ValidatedQuery<IncomeListQuery>,
+) -> Result<(StatusCode, Json<Vec<Income>>), AppError> {
+    let incomes = state
+        .income_repository
+        .find_incomes(claims.sub, &query)
+        .await?;
+    Ok((StatusCode::OK, Json(incomes)))
+}
+
+pub async fn update_income(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    ValidatedJson(payload): ValidatedJson<UpdateIncomePayload>,
+) -> Result<(StatusCode, Json<Income>), AppError> {
+    if let Some(currency) = &payload.currency {
+        ensure_currency(&state, currency).await?;
+    }
+    let income = state
+        .income_repository
+        .find_income(id, claims.sub)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Income not found".into()))?;
+    let account_id = payload.account_id.unwrap_or(income.account_id);
+    let currency = payload.currency.as_deref().unwrap_or(&income.currency);
+    check_account(&state, claims.sub, account_id, Some(currency)).await?;
+
+    let income = state
+        .income_repository
+        .update_income(id, claims.sub, payload)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Income not found".into()))?;
+    Ok((StatusCode::OK, Json(income)))
+}
+
+pub async fn delete_income(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    match state
+        .income_repository
+        .delete_income(id, claims.sub)
+        .await?
+    {
+        Some(_) => Ok(StatusCode::NO_CONTENT),
+        None => Ok(StatusCode::NOT_FOUND),
+    }
+}
+
+pub async fn get_cash_flow(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedQuery(query): ValidatedQuery<CashFlowQuery>,
+) -> Result<(StatusCode, Json<CashFlowReport>), AppError> {
+    let user = state
+        .user_repository
+        .find_by_id(claims.sub)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".into()))?;
+
+    let (from, to) = query
+        .months()
+        .ok_or_else(|| AppError::BadRequest("Invalid month".into()))?;
+    let months = state
+        .income_repository
+        .find_cash_flow(claims.sub, from, to)
+        .await?;
+    Ok((
+        StatusCode::OK,
+        Json(CashFlowReport {
+            currency: user.base_currency,
+            months,
+        }),
+    ))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/incomes", get(get_incomes).post(create_income))
+        .route("/incomes/{id}", put(update_income).delete(delete_income))
+        .route("/cash-flow", get(get_cash_flow))
+}
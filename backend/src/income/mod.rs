@@ -0,0 +1,4 @@
+pub mod handler;
+pub mod models;
+pub mod repository;
+pub mod utils;
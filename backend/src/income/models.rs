@@ -0,0 +1,32 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Income {
+    pub id: i32,
+    pub source: String,
+    pub amount: BigDecimal,
+    pub currency: String,
+    /// `amount` in the user's base currency; `None` when no rate is known.
+    pub amount_in_base: Option<BigDecimal>,
+    pub income_date: NaiveDate,
+    pub recurring: bool,
+    /// Account the income was paid into.
+    pub account_id: Option<i32>,
+}
+
+/// Totals of one month, in the user's base currency.
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CashFlowMonth {
+    pub month: NaiveDate,
+    pub income: BigDecimal,
+    pub expenses: BigDecimal,
+    pub net: BigDecimal,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CashFlowReport {
+    pub currency: String,
+    pub months: Vec<CashFlowMonth>,
+}
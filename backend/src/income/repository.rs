@@ -0,0 +1,192 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+use super::{
+    models::{CashFlowMonth, Income},
+    utils::{CreateIncomePayload, IncomeListQuery, UpdateIncomePayload},
+};
+
+pub struct IncomeRepository {
+    pool: Arc<PgPool>,
+}
+
+impl IncomeRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        IncomeRepository { pool }
+    }
+
+    pub async fn create_income(
+        &self,
+        payload: CreateIncomePayload,
+        user_id: i32,
+    ) -> anyhow::Result<Income> {
+        sqlx::query_as!(
+            Income,
+            r#"
+    INSERT INTO incomes (user_id, source, amount, currency, income_date, recurring, account_id)
+    VALUES ($1, $2, $3,
+            COALESCE($4, (SELECT currency FROM accounts WHERE id = $7),
+                     (SELECT base_currency FROM users WHERE id = $1)),
+            COALESCE($5, CURRENT_DATE), $6, $7)
+    RETURNING id, source, amount, currency,
+              convert_to_base(amount, currency, user_id, income_date) AS amount_in_base,
+              income_date, recurring, account_id;
+    "#,
+            user_id,
+            payload.source,
+            payload.amount,
+            payload.currency,
+            payload.income_date,
+            payload.recurring,
+            payload.account_id,
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context("Failed to create income")
+    }
+
+    pub async fn find_incomes(
+        &self,
+        user_id: i32,
+        query: &IncomeListQuery,
+    ) -> anyhow::Result<Vec<Income>> {
+        sqlx::query_as!(
+            Income,
+            r#"
+    SELECT id, source, amount, currency,
+           convert_to_base(amount, currency, user_id, income_date) AS amount_in_base,
+           income_date, recurring, account_id
+    FROM incomes
+    WHERE user_id = $1
+      AND ($2::DATE IS NULL OR income_date >= $2)
+      AND ($3::DATE IS NULL OR income_date <= $3)
+    ORDER BY income_date DESC, id DESC;
+    "#,
+            user_id,
+            query.from,
+            query.to
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get incomes by user_id: {}", user_id))
+    }
+
+    pub async fn find_income(&self, id: i32, user_id: i32) -> anyhow::Result<Option<Income>> {
+        sqlx::query_as!(
+            Income,
+            r#"
+    SELECT id, source, amount, currency,
+           convert_to_base(amount, currency, user_id, income_date) AS amount_in_base,
+           income_date, recurring, account_id
+    FROM incomes
+    WHERE id = $1 AND user_id = $2;
+    "#,
+            id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to get income by id: {}", id))
+    }
+
+    pub async fn update_income(
+        &self,
+        id: i32,
+        user_id: i32,
+        payload: UpdateIncomePayload,
+    ) -> anyhow::Result<Option<Income>> {
+        sqlx::query_as!(
+            Income,
+            r#"
+    UPDATE incomes
+    SET source = COALESCE($1, source),
+        amount = COALESCE($2, amount),
+        currency = COALESCE($3, currency),
+        income_date = COALESCE($4, income_date),
+        recurring = COALESCE($5, recurring),
+        account_id = CASE WHEN $6 THEN $7 ELSE account_id END
+    WHERE id = $8 AND user_id = $9
+    RETURNING id, source, amount, currency,
+              convert_to_base(amount, currency, user_id, income_date) AS amount_in_base,
+              income_date, recurring, account_id;
+    "#,
+            payload.source,
+            payload.amount,
+            payload.currency,
+            payload.income_date,
+            payload.recurring,
+            payload.account_id.is_some(),
+            payload.account_id.flatten(),
+            id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context("Failed to update income")
+    }
+
+    pub async fn delete_income(&self, id: i32, user_id: i32) -> anyhow::Result<Option<i32>> {
+        sqlx::query_scalar!(
+            "DELETE FROM incomes WHERE id = $1 AND user_id = $2 RETURNING id;",
+            id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to delete income by id: {}", id))
+    }
+
+    /// Income, expenses and net per month from `from` to `to` (first days of
+    /// months, inclusive), converted to the base currency. Months without
+    /// activity are returned with zeros.
+    pub async fn find_cash_flow(
+        &self,
+        user_id: i32,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> anyhow::Result<Vec<CashFlowMonth>> {
+        sqlx::query_as!(
+            CashFlowMonth,
+            r#"
+    WITH months AS (
+        SELECT generate_series($2::DATE, $3::DATE, INTERVAL '1 month')::DATE AS month
+    ),
+    income AS (
+        SELECT date_trunc('month', income_date::TIMESTAMP)::DATE AS month,
+               SUM(convert_to_base(amount, currency, user_id, income_date)) AS total
+        FROM incomes
+        WHERE user_id = $1
+          AND income_date >= $2::DATE
+          AND income_date < $3::DATE + INTERVAL '1 month'
+        GROUP BY 1
+    ),
+    spending AS (
+        SELECT date_trunc('month', expense_date)::DATE AS month,
+               SUM(convert_to_base(amount, currency, user_id, expense_date::DATE)) AS total
+        FROM expenses
+        WHERE user_id = $1 AND deleted_at IS NULL
+          AND expense_date >= $2::DATE
+          AND expense_date < $3::DATE + INTERVAL '1 month'
+        GROUP BY 1
+    )
+    SELECT m.month AS "month!",
+           COALESCE(i.total, 0) AS "income!",
+           COALESCE(s.total, 0) AS "expenses!",
+           COALESCE(i.total, 0) - COALESCE(s.total, 0) AS "net!"
+    FROM months m
+    LEFT JOIN income i ON i.month = m.month
+    LEFT JOIN spending s ON s.month = m.month
+    ORDER BY m.month;
+    "#,
+            user_id,
+            from,
+            to
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get cash flow by user_id: {}", user_id))
+    }
+}
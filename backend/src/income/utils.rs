@@ -0,0 +1,126 @@
+use bigdecimal::BigDecimal;
+use chrono::{Months, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+use crate::{
+    budget::utils::{month_start, parse_month, validate_month},
+    currency::utils::validate_currency,
+    expense::utils::{deserialize_nullable, validate_amount, validate_date_range},
+};
+
+/// Months returned by a cash-flow request when `from` is omitted.
+const DEFAULT_CASH_FLOW_MONTHS: u32 = 12;
+const MAX_CASH_FLOW_MONTHS: u32 = 120;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateIncomePayload {
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Source must be between 1 and 100 characters"
+    ))]
+    pub source: String,
+
+    #[validate(custom(function = "validate_amount"))]
+    pub amount: BigDecimal,
+
+    /// Defaults to the currency of the account, or else the user's base
+    /// currency.
+    #[validate(custom(function = "validate_currency"))]
+    pub currency: Option<String>,
+
+    /// Defaults to today.
+    pub income_date: Option<NaiveDate>,
+
+    #[serde(default)]
+    pub recurring: bool,
+
+    /// Account the income was paid into.
+    #[validate(range(min = 1, message = "Invalid account"))]
+    pub account_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct UpdateIncomePayload {
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Source must be between 1 and 100 characters"
+    ))]
+    pub source: Option<String>,
+
+    #[validate(custom(function = "validate_amount"))]
+    pub amount: Option<BigDecimal>,
+
+    #[validate(custom(function = "validate_currency"))]
+    pub currency: Option<String>,
+
+    pub income_date: Option<NaiveDate>,
+
+    pub recurring: Option<bool>,
+
+    /// An explicit `null` detaches the income from its account.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    #[validate(range(min = 1, message = "Invalid account"))]
+    pub account_id: Option<Option<i32>>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[validate(schema(function = "validate_income_bounds"))]
+pub struct IncomeListQuery {
+    /// Inclusive lower bound on `income_date`.
+    pub from: Option<NaiveDate>,
+
+    /// Inclusive upper bound on `income_date`.
+    pub to: Option<NaiveDate>,
+}
+
+fn validate_income_bounds(query: &IncomeListQuery) -> Result<(), ValidationError> {
+    validate_date_range(query.from, query.to)
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[validate(schema(function = "validate_cash_flow_range"))]
+pub struct CashFlowQuery {
+    /// First month, `YYYY-MM`; defaults to eleven months before `to`.
+    #[validate(custom(function = "validate_month"))]
+    pub from: Option<String>,
+
+    /// Last month, `YYYY-MM`; defaults to the current month.
+    #[validate(custom(function = "validate_month"))]
+    pub to: Option<String>,
+}
+
+impl CashFlowQuery {
+    /// First days of the first and last months; `None` when the default
+    /// first month is out of range.
+    pub fn months(&self) -> Option<(NaiveDate, NaiveDate)> {
+        let to = self
+            .to
+            .as_deref()
+            .and_then(parse_month)
+            .unwrap_or_else(|| month_start(Utc::now().date_naive()));
+        let from = match self.from.as_deref().and_then(parse_month) {
+            Some(from) => from,
+            None => to.checked_sub_months(Months::new(DEFAULT_CASH_FLOW_MONTHS - 1))?,
+        };
+        Some((from, to))
+    }
+}
+
+fn validate_cash_flow_range(query: &CashFlowQuery) -> Result<(), ValidationError> {
+    let (from, to) = query
+        .months()
+        .ok_or_else(|| ValidationError::new("invalid_month"))?;
+    if from > to {
+        return Err(ValidationError::new("from_must_not_be_after_to"));
+    }
+    let limit = from
+        .checked_add_months(Months::new(MAX_CASH_FLOW_MONTHS))
+        .ok_or_else(|| ValidationError::new("invalid_month"))?;
+    if limit <= to {
+        return Err(ValidationError::new("range_too_long"));
+    }
+    Ok(())
+}
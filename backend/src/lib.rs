@@ -1,14 +1,32 @@
+mod account;
+mod anomaly;
+mod attachment;
 mod auth;
+mod budget;
+mod category;
 pub mod config;
+mod currency;
 mod database;
+mod digest;
+pub mod email;
 mod error;
 mod expense;
+mod export;
+mod forecast;
+mod goal;
+mod group;
+mod import;
+mod income;
+mod recurring;
 mod redis;
+mod report;
+mod rule;
 pub mod server;
 mod state;
+mod summary;
+mod tag;
 mod user;
 mod validation;
-pub mod email;
 
 // Use mimalloc as the global allocator for better performance
 #[global_allocator]
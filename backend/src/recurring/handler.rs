@@ -0,0 +1,179 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post, put},
+};
+use chrono::Utc;
+
+use crate::{
+    auth::token::claims::Claims, currency::handler::ensure_currency, error::AppError,
+    expense::handler::ensure_category, state::AppState, validation::ValidatedJson,
+};
+
+use super::{
+    models::RecurringExpense,
+    schedule::Schedule,
+    utils::{CreateRecurringExpensePayload, UpdateRecurringExpensePayload},
+};
+
+pub async fn get_recurring_expenses(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Vec<RecurringExpense>>), AppError> {
+    let templates = state
+        .recurring_expense_repository
+        .find_recurring_expenses(claims.sub)
+        .await?;
+    Ok((StatusCode::OK, Json(templates)))
+}
+
+pub async fn create_recurring_expense(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(payload): ValidatedJson<CreateRecurringExpensePayload>,
+) -> Result<(StatusCode, Json<RecurringExpense>), AppError> {
+    ensure_category(&state, payload.category_id, claims.sub).await?;
+    if let Some(currency) = &payload.currency {
+        ensure_currency(&state, currency).await?;
+    }
+
+    let schedule = Schedule::new(payload.frequency, payload.day_of_month, payload.start_date);
+    let next_occurrence = schedule.first_on_or_after(payload.start_date);
+
+    let template = state
+        .recurring_expense_repository
+        .create_recurring_expense(payload, next_occurrence, claims.sub)
+        .await?;
+    Ok((StatusCode::CREATED, Json(template)))
+}
+
+pub async fn update_recurring_expense(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    ValidatedJson(payload): ValidatedJson<UpdateRecurringExpensePayload>,
+) -> Result<(StatusCode, Json<RecurringExpense>), AppError> {
+    let existing = find_template(&state, id, claims.sub).await?;
+    if let Some(category_id) = payload.category_id {
+        ensure_category(&state, category_id, claims.sub).await?;
+    }
+    if let Some(currency) = &payload.currency {
+        ensure_currency(&state, currency).await?;
+    }
+    if let Some(Some(end_date)) = payload.end_date
+        && end_date < existing.start_date
+    {
+        return Err(AppError::BadRequest(
+            "End date must not be before the start date".into(),
+        ));
+    }
+
+    // A new rule restarts from today; otherwise keep the pending occurrence
+    let rule_changed = payload.frequency.is_some_and(|f| f != existing.frequency)
+        || payload
+            .day_of_month
+            .is_some_and(|d| d != existing.day_of_month);
+    let next_occurrence = if rule_changed {
+        Schedule::new(
+            payload.frequency.unwrap_or(existing.frequency),
+            payload.day_of_month.unwrap_or(existing.day_of_month),
+            existing.start_date,
+        )
+        .first_on_or_after(Utc::now().date_naive())
+    } else {
+        existing.next_occurrence
+    };
+
+    let template = state
+        .recurring_expense_repository
+        .update_recurring_expense(id, claims.sub, payload, next_occurrence)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Recurring expense not found".into()))?;
+    Ok((StatusCode::OK, Json(template)))
+}
+
+pub async fn pause_recurring_expense(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<RecurringExpense>), AppError> {
+    let existing = find_template(&state, id, claims.sub).await?;
+    let template = state
+        .recurring_expense_repository
+        .set_paused(id, claims.sub, true, existing.next_occurrence)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Recurring expense not found".into()))?;
+    Ok((StatusCode::OK, Json(template)))
+}
+
+pub async fn resume_recurring_expense(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<RecurringExpense>), AppError> {
+    let existing = find_template(&state, id, claims.sub).await?;
+    // Occurrences skipped while paused are not caught up
+    let next_occurrence = if existing.paused {
+        existing
+            .schedule()
+            .first_on_or_after(Utc::now().date_naive().max(existing.next_occurrence))
+    } else {
+        existing.next_occurrence
+    };
+    let template = state
+        .recurring_expense_repository
+        .set_paused(id, claims.sub, false, next_occurrence)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Recurring expense not found".into()))?;
+    Ok((StatusCode::OK, Json(template)))
+}
+
+pub async fn delete_recurring_expense(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    match state
+        .recurring_expense_repository
+        .delete_recurring_expense(id, claims.sub)
+        .await?
+    {
+        Some(_) => Ok(StatusCode::NO_CONTENT),
+        None => Ok(StatusCode::NOT_FOUND),
+    }
+}
+
+async fn find_template(
+    state: &AppState,
+    id: i32,
+    user_id: i32,
+) -> Result<RecurringExpense, AppError> {
+    state
+        .recurring_expense_repository
+        .find_recurring_expense(id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Recurring expense not found".into()))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route(
+            "/recurring-expenses",
+            get(get_recurring_expenses).post(create_recurring_expense),
+        )
+        .route(
+            "/recurring-expenses/{id}",
+            put(update_recurring_expense).delete(delete_recurring_expense),
+        )
+        .route(
+            "/recurring-expenses/{id}/pause",
+            post(pause_recurring_expense),
+        )
+        .route(
+            "/recurring-expenses/{id}/resume",
+            post(resume_recurring_expense),
+        )
+}
@@ -0,0 +1,6 @@
+pub mod handler;
+pub mod models;
+pub mod repository;
+pub mod schedule;
+pub mod scheduler;
+pub mod utils;
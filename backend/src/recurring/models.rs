@@ -0,0 +1,38 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+
+use super::schedule::Schedule;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[sqlx(type_name = "recurrence_frequency", rename_all = "PascalCase")]
+pub enum RecurrenceFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RecurringExpense {
+    pub id: i32,
+    #[serde(skip)]
+    pub user_id: i32,
+    pub category_id: i32,
+    pub amount: BigDecimal,
+    pub currency: String,
+    pub description: Option<String>,
+    pub frequency: RecurrenceFrequency,
+    pub day_of_month: Option<i16>,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub next_occurrence: NaiveDate,
+    pub paused: bool,
+}
+
+impl RecurringExpense {
+    pub fn schedule(&self) -> Schedule {
+        Schedule::new(self.frequency, self.day_of_month, self.start_date)
+    }
+}
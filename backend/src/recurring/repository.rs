@@ -0,0 +1,306 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use chrono::NaiveDate;
+use sqlx::{Acquire, PgPool, Postgres, Transaction};
+
+use crate::expense::{repository::record_history, utils::HistoryAction};
+
+use super::{
+    models::{RecurrenceFrequency, RecurringExpense},
+    utils::{CreateRecurringExpensePayload, UpdateRecurringExpensePayload},
+};
+
+pub struct RecurringExpenseRepository {
+    pool: Arc<PgPool>,
+}
+
+impl RecurringExpenseRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        RecurringExpenseRepository { pool }
+    }
+
+    pub async fn find_recurring_expenses(
+        &self,
+        user_id: i32,
+    ) -> anyhow::Result<Vec<RecurringExpense>> {
+        sqlx::query_as!(
+            RecurringExpense,
+            r#"
+            SELECT id, user_id, category_id, amount, currency, description,
+                   frequency AS "frequency: _", day_of_month, start_date, end_date,
+                   next_occurrence, paused
+            FROM recurring_expenses WHERE user_id = $1
+            ORDER BY next_occurrence, id;
+            "#,
+            user_id
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to get recurring expenses by user_id: {}",
+            user_id
+        ))
+    }
+
+    pub async fn find_recurring_expense(
+        &self,
+        id: i32,
+        user_id: i32,
+    ) -> anyhow::Result<Option<RecurringExpense>> {
+        sqlx::query_as!(
+            RecurringExpense,
+            r#"
+            SELECT id, user_id, category_id, amount, currency, description,
+                   frequency AS "frequency: _", day_of_month, start_date, end_date,
+                   next_occurrence, paused
+            FROM recurring_expenses WHERE id = $1 AND user_id = $2;
+            "#,
+            id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to get recurring expense by id: {}", id))
+    }
+
+    pub async fn create_recurring_expense(
+        &self,
+        payload: CreateRecurringExpensePayload,
+        next_occurrence: NaiveDate,
+        user_id: i32,
+    ) -> anyhow::Result<RecurringExpense> {
+        sqlx::query_as!(
+            RecurringExpense,
+            r#"
+    INSERT INTO recurring_expenses
+        (user_id, category_id, amount, currency, description, frequency, day_of_month,
+         start_date, end_date, next_occurrence)
+    VALUES ($1, $2, $3, COALESCE($4, (SELECT base_currency FROM users WHERE id = $1)),
+            $5, $6, $7, $8, $9, $10)
+    RETURNING id, user_id, category_id, amount, currency, description,
+              frequency AS "frequency: _", day_of_month, start_date, end_date,
+              next_occurrence, paused;
+    "#,
+            user_id,
+            payload.category_id,
+            payload.amount,
+            payload.currency,
+            payload.description,
+            payload.frequency as RecurrenceFrequency,
+            payload.day_of_month,
+            payload.start_date,
+            payload.end_date,
+            next_occurrence,
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context("Failed to create recurring expense")
+    }
+
+    pub async fn update_recurring_expense(
+        &self,
+        id: i32,
+        user_id: i32,
+        payload: UpdateRecurringExpensePayload,
+        next_occurrence: NaiveDate,
+    ) -> anyhow::Result<Option<RecurringExpense>> {
+        sqlx::query_as!(
+            RecurringExpense,
+            r#"
+    UPDATE recurring_expenses
+    SET category_id = COALESCE($1, category_id),
+        amount = COALESCE($2, amount),
+        currency = COALESCE($3, currency),
+        description = COALESCE($4, description),
+        frequency = COALESCE($5, frequency),
+        day_of_month = CASE WHEN $6 THEN $7 ELSE day_of_month END,
+        end_date = CASE WHEN $8 THEN $9 ELSE end_date END,
+        next_occurrence = $10
+    WHERE id = $11 AND user_id = $12
+    RETURNING id, user_id, category_id, amount, currency, description,
+              frequency AS "frequency: _", day_of_month, start_date, end_date,
+              next_occurrence, paused;
+    "#,
+            payload.category_id,
+            payload.amount,
+            payload.currency,
+            payload.description,
+            payload.frequency as Option<RecurrenceFrequency>,
+            payload.day_of_month.is_some(),
+            payload.day_of_month.flatten(),
+            payload.end_date.is_some(),
+            payload.end_date.flatten(),
+            next_occurrence,
+            id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context("Failed to update recurring expense")
+    }
+
+    pub async fn set_paused(
+        &self,
+        id: i32,
+        user_id: i32,
+        paused: bool,
+        next_occurrence: NaiveDate,
+    ) -> anyhow::Result<Option<RecurringExpense>> {
+        sqlx::query_as!(
+            RecurringExpense,
+            r#"
+    UPDATE recurring_expenses
+    SET paused = $1, next_occurrence = $2
+    WHERE id = $3 AND user_id = $4
+    RETURNING id, user_id, category_id, amount, currency, description,
+              frequency AS "frequency: _", day_of_month, start_date, end_date,
+              next_occurrence, paused;
+    "#,
+            paused,
+            next_occurrence,
+            id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to pause recurring expense by id: {}", id))
+    }
+
+    pub async fn delete_recurring_expense(
+        &self,
+        id: i32,
+        user_id: i32,
+    ) -> anyhow::Result<Option<i32>> {
+        sqlx::query_scalar!(
+            "DELETE FROM recurring_expenses WHERE id = $1 AND user_id = $2 RETURNING id;",
+            id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to delete recurring expense by id: {}", id))
+    }
+
+    /// Inserts every occurrence due on or before `today`, including the ones
+    /// missed while the server was down, and advances `next_occurrence`. A
+    /// template that fails is logged and left for the next run.
+    pub async fn materialize_due(&self, today: NaiveDate) -> anyhow::Result<usize> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to start transaction")?;
+
+        // SKIP LOCKED lets several backend instances share the work
+        let due = sqlx::query_as!(
+            RecurringExpense,
+            r#"
+            SELECT id, user_id, category_id, amount, currency, description,
+                   frequency AS "frequency: _", day_of_month, start_date, end_date,
+                   next_occurrence, paused
+            FROM recurring_expenses
+            WHERE NOT paused
+              AND next_occurrence <= $1
+              AND (end_date IS NULL OR next_occurrence <= end_date)
+            FOR UPDATE SKIP LOCKED;
+            "#,
+            today
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .context("Failed to get due recurring expenses")?;
+
+        let mut created = 0;
+        for template in due {
+            // Behind a savepoint so that a failing template is retried at the
+            // next run without holding back the others
+            let mut savepoint = Acquire::begin(&mut tx)
+                .await
+                .context("Failed to create savepoint")?;
+            match materialize_template(&mut savepoint, &template, today).await {
+                Ok(count) => {
+                    savepoint
+                        .commit()
+                        .await
+                        .context("Failed to release savepoint")?;
+                    created += count;
+                }
+                Err(e) => {
+                    savepoint
+                        .rollback()
+                        .await
+                        .context("Failed to roll back savepoint")?;
+                    tracing::error!("{:?}", e);
+                }
+            }
+        }
+
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(created)
+    }
+}
+
+/// Inserts the occurrences of `template` due on or before `today` and
+/// advances its `next_occurrence`. Returns how many expenses were created.
+async fn materialize_template(
+    tx: &mut Transaction<'_, Postgres>,
+    template: &RecurringExpense,
+    today: NaiveDate,
+) -> anyhow::Result<usize> {
+    let mut created = 0;
+    let schedule = template.schedule();
+    let mut occurrence = template.next_occurrence;
+    while occurrence <= today && template.end_date.is_none_or(|end| occurrence <= end) {
+        let inserted = sqlx::query_scalar!(
+            r#"
+    INSERT INTO expenses
+        (user_id, category_id, amount, currency, description, expense_date, recurring_expense_id)
+    SELECT $1, $2, $3, $4, $5, $6::DATE, $7
+    -- Trashed occurrences count, so deleting one does not bring it back
+    WHERE NOT EXISTS (
+        SELECT 1 FROM expenses WHERE recurring_expense_id = $7 AND expense_date = $6::DATE
+    )
+    RETURNING id;
+    "#,
+            template.user_id,
+            template.category_id,
+            template.amount,
+            template.currency,
+            template.description,
+            occurrence,
+            template.id
+        )
+        .fetch_optional(&mut **tx)
+        .await
+        .context(format!(
+            "Failed to materialize recurring expense id: {}",
+            template.id
+        ))?;
+        if let Some(expense_id) = inserted {
+            record_history(
+                tx,
+                expense_id,
+                template.user_id,
+                HistoryAction::Create,
+                None,
+            )
+            .await?;
+            created += 1;
+        }
+        occurrence = schedule.next_after(occurrence);
+    }
+
+    sqlx::query!(
+        "UPDATE recurring_expenses SET next_occurrence = $1 WHERE id = $2;",
+        occurrence,
+        template.id
+    )
+    .execute(&mut **tx)
+    .await
+    .context(format!(
+        "Failed to advance recurring expense id: {}",
+        template.id
+    ))?;
+    Ok(created)
+}
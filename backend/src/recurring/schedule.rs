@@ -0,0 +1,135 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
+
+use super::models::RecurrenceFrequency;
+
+/// Occurrence dates of a recurrence rule, anchored on its start date.
+///
+/// Weekly rules repeat on the start date's weekday and yearly rules on its
+/// anniversary. Monthly rules use `day_of_month`, falling back to the start
+/// date's day. Days past the end of a month are clamped to its last day.
+#[derive(Debug, Clone, Copy)]
+pub struct Schedule {
+    frequency: RecurrenceFrequency,
+    day_of_month: u32,
+    anchor: NaiveDate,
+}
+
+impl Schedule {
+    pub fn new(
+        frequency: RecurrenceFrequency,
+        day_of_month: Option<i16>,
+        anchor: NaiveDate,
+    ) -> Self {
+        let day_of_month = day_of_month
+            .and_then(|day| u32::try_from(day).ok())
+            .unwrap_or_else(|| anchor.day());
+        Self {
+            frequency,
+            day_of_month,
+            anchor,
+        }
+    }
+
+    /// First occurrence that falls on or after `date` (and never before the anchor).
+    pub fn first_on_or_after(&self, date: NaiveDate) -> NaiveDate {
+        let date = date.max(self.anchor);
+        match self.frequency {
+            RecurrenceFrequency::Daily => date,
+            RecurrenceFrequency::Weekly => {
+                let offset = (7 + self.anchor.weekday().num_days_from_monday()
+                    - date.weekday().num_days_from_monday())
+                    % 7;
+                date + Days::new(u64::from(offset))
+            }
+            RecurrenceFrequency::Monthly => {
+                let candidate = clamped_date(date.year(), date.month(), self.day_of_month);
+                if candidate >= date {
+                    candidate
+                } else {
+                    let next_month = date.with_day(1).unwrap_or(date) + Months::new(1);
+                    clamped_date(next_month.year(), next_month.month(), self.day_of_month)
+                }
+            }
+            RecurrenceFrequency::Yearly => {
+                let (month, day) = (self.anchor.month(), self.anchor.day());
+                let candidate = clamped_date(date.year(), month, day);
+                if candidate >= date {
+                    candidate
+                } else {
+                    clamped_date(date.year() + 1, month, day)
+                }
+            }
+        }
+    }
+
+    /// Occurrence following `date`.
+    pub fn next_after(&self, date: NaiveDate) -> NaiveDate {
+        self.first_on_or_after(date + Days::new(1))
+    }
+}
+
+fn clamped_date(year: i32, month: u32, day: u32) -> NaiveDate {
+    (1..=day.min(31))
+        .rev()
+        .find_map(|d| NaiveDate::from_ymd_opt(year, month, d))
+        .unwrap_or(NaiveDate::MIN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn monthly_on_the_31st_clamps_to_the_end_of_february() {
+        let schedule = Schedule::new(RecurrenceFrequency::Monthly, None, date(2025, 1, 31));
+        assert_eq!(schedule.next_after(date(2025, 1, 31)), date(2025, 2, 28));
+        assert_eq!(schedule.next_after(date(2025, 2, 28)), date(2025, 3, 31));
+
+        let schedule = Schedule::new(RecurrenceFrequency::Monthly, Some(31), date(2024, 1, 1));
+        assert_eq!(
+            schedule.first_on_or_after(date(2024, 2, 1)),
+            date(2024, 2, 29)
+        );
+        assert_eq!(schedule.next_after(date(2024, 2, 29)), date(2024, 3, 31));
+        assert_eq!(schedule.next_after(date(2024, 3, 31)), date(2024, 4, 30));
+    }
+
+    #[test]
+    fn yearly_on_february_29th_falls_back_to_the_28th() {
+        let schedule = Schedule::new(RecurrenceFrequency::Yearly, None, date(2024, 2, 29));
+        assert_eq!(
+            schedule.first_on_or_after(date(2024, 1, 1)),
+            date(2024, 2, 29)
+        );
+        assert_eq!(schedule.next_after(date(2024, 2, 29)), date(2025, 2, 28));
+        assert_eq!(schedule.next_after(date(2025, 2, 28)), date(2026, 2, 28));
+        assert_eq!(
+            schedule.first_on_or_after(date(2027, 3, 1)),
+            date(2028, 2, 29)
+        );
+    }
+
+    #[test]
+    fn weekly_keeps_the_start_weekday() {
+        // 2025-01-01 is a Wednesday
+        let schedule = Schedule::new(RecurrenceFrequency::Weekly, None, date(2025, 1, 1));
+        assert_eq!(schedule.next_after(date(2025, 1, 1)), date(2025, 1, 8));
+        assert_eq!(
+            schedule.first_on_or_after(date(2025, 1, 9)),
+            date(2025, 1, 15)
+        );
+    }
+
+    #[test]
+    fn never_starts_before_the_anchor() {
+        let schedule = Schedule::new(RecurrenceFrequency::Daily, None, date(2025, 6, 1));
+        assert_eq!(
+            schedule.first_on_or_after(date(2025, 1, 1)),
+            date(2025, 6, 1)
+        );
+    }
+}
@@ -0,0 +1,23 @@
+use std::time::Duration;
+
+use chrono::Utc;
+
+use super::repository::RecurringExpenseRepository;
+
+const MATERIALIZE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Materializes due recurring expenses at startup, then every hour.
+pub fn spawn_materializer(repository: RecurringExpenseRepository) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(MATERIALIZE_INTERVAL);
+        loop {
+            interval.tick().await;
+            let today = Utc::now().date_naive();
+            match repository.materialize_due(today).await {
+                Ok(0) => {}
+                Ok(created) => tracing::info!("Materialized {created} recurring expense(s)"),
+                Err(e) => tracing::error!("Failed to materialize recurring expenses: {:?}", e),
+            }
+        }
+    });
+}
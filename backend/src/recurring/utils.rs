@@ -0,0 +1,74 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+use crate::{
+    currency::utils::validate_currency,
+    expense::utils::{deserialize_nullable, validate_amount},
+};
+
+use super::models::RecurrenceFrequency;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_create_rule"))]
+pub struct CreateRecurringExpensePayload {
+    #[validate(range(min = 1, message = "Invalid category"))]
+    pub category_id: i32,
+
+    #[validate(custom(function = "validate_amount"))]
+    pub amount: BigDecimal,
+
+    /// Defaults to the user's base currency.
+    #[validate(custom(function = "validate_currency"))]
+    pub currency: Option<String>,
+
+    #[validate(length(max = 255, message = "Description too long"))]
+    pub description: Option<String>,
+
+    pub frequency: RecurrenceFrequency,
+
+    #[validate(range(min = 1, max = 31, message = "Day of month must be between 1 and 31"))]
+    pub day_of_month: Option<i16>,
+
+    pub start_date: NaiveDate,
+
+    pub end_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct UpdateRecurringExpensePayload {
+    #[validate(range(min = 1, message = "Invalid category"))]
+    pub category_id: Option<i32>,
+
+    #[validate(custom(function = "validate_amount"))]
+    pub amount: Option<BigDecimal>,
+
+    #[validate(custom(function = "validate_currency"))]
+    pub currency: Option<String>,
+
+    #[validate(length(max = 255, message = "Description too long"))]
+    pub description: Option<String>,
+
+    pub frequency: Option<RecurrenceFrequency>,
+
+    /// An explicit `null` falls back to the day of the start date.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    #[validate(range(min = 1, max = 31, message = "Day of month must be between 1 and 31"))]
+    pub day_of_month: Option<Option<i16>>,
+
+    /// An explicit `null` makes the rule open-ended.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub end_date: Option<Option<NaiveDate>>,
+}
+
+fn validate_create_rule(payload: &CreateRecurringExpensePayload) -> Result<(), ValidationError> {
+    if let Some(end_date) = payload.end_date
+        && end_date < payload.start_date
+    {
+        return Err(ValidationError::new(
+            "end_date_must_not_be_before_start_date",
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use axum::{
+    Router,
+    extract::State,
+    http::{
+        StatusCode,
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    },
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use chrono::Utc;
+
+use crate::{
+    attachment::utils::content_disposition, auth::token::claims::Claims,
+    budget::utils::next_month_start, error::AppError, state::AppState,
+    user::repository::UserRepository, validation::ValidatedQuery,
+};
+
+use super::{models::Statement, pdf::render_statement, utils::StatementQuery};
+
+/// Printable statement of a month: the expenses grouped by category with
+/// subtotals, a grand total and a chart, as a PDF.
+pub async fn get_statement(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedQuery(query): ValidatedQuery<StatementQuery>,
+) -> Result<Response, AppError> {
+    let month = query
+        .month_start()
+        .ok_or_else(|| AppError::BadRequest("Invalid month".into()))?;
+    let user = state
+        .user_repository
+        .find_by_id(claims.sub)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".into()))?;
+
+    let lines = state
+        .report_repository
+        .find_statement_lines(claims.sub, month, next_month_start(month))
+        .await?;
+    let statement = Statement::new(
+        user.name,
+        month,
+        user.base_currency,
+        lines,
+        Utc::now().naive_utc(),
+    );
+    let pdf = tokio::task::spawn_blocking(move || render_statement(&statement))
+        .await
+        .map_err(anyhow::Error::from)??;
+
+    let file_name = format!("statement-{}.pdf", month.format("%Y-%m"));
+    Ok((
+        StatusCode::OK,
+        [
+            (CONTENT_TYPE, "application/pdf".to_string()),
+            (CONTENT_DISPOSITION, content_disposition(&file_name)),
+        ],
+        pdf,
+    )
+        .into_response())
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route("/reports/statement", get(get_statement))
+}
@@ -0,0 +1,82 @@
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{NaiveDate, NaiveDateTime};
+
+/// An expense of the statement month, ordered by category then date.
+#[derive(Debug, sqlx::FromRow)]
+pub struct StatementLine {
+    pub category_id: i32,
+    pub category_name: String,
+    /// `#rrggbb`.
+    pub category_color: String,
+    pub expense_date: NaiveDateTime,
+    pub description: Option<String>,
+    pub amount: BigDecimal,
+    pub currency: String,
+    /// `None` when no rate to the base currency is known.
+    pub amount_in_base: Option<BigDecimal>,
+}
+
+/// Expenses of one month grouped by category, in the user's base currency.
+#[derive(Debug)]
+pub struct Statement {
+    pub user_name: String,
+    pub month: NaiveDate,
+    pub currency: String,
+    /// Largest subtotal first.
+    pub categories: Vec<StatementCategory>,
+    pub total: BigDecimal,
+    pub generated_at: NaiveDateTime,
+}
+
+#[derive(Debug)]
+pub struct StatementCategory {
+    pub name: String,
+    pub color: String,
+    pub lines: Vec<StatementLine>,
+    /// Lines without a known rate are left out.
+    pub subtotal: BigDecimal,
+}
+
+impl Statement {
+    pub fn new(
+        user_name: String,
+        month: NaiveDate,
+        currency: String,
+        lines: Vec<StatementLine>,
+        generated_at: NaiveDateTime,
+    ) -> Self {
+        let mut categories: Vec<StatementCategory> = Vec::new();
+        for line in lines {
+            let subtotal = line.amount_in_base.clone().unwrap_or_default();
+            match categories.last_mut() {
+                Some(category) if category.lines[0].category_id == line.category_id => {
+                    category.subtotal += subtotal;
+                    category.lines.push(line);
+                }
+                _ => categories.push(StatementCategory {
+                    name: line.category_name.clone(),
+                    color: line.category_color.clone(),
+                    lines: vec![line],
+                    subtotal,
+                }),
+            }
+        }
+        categories.sort_by(|a, b| {
+            b.subtotal
+                .cmp(&a.subtotal)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        let total = categories
+            .iter()
+            .fold(BigDecimal::zero(), |total, c| total + &c.subtotal);
+
+        Self {
+            user_name,
+            month,
+            currency,
+            categories,
+            total,
+            generated_at,
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use bigdecimal::{BigDecimal, RoundingMode, ToPrimitive};
+use printpdf::{
+    BuiltinFont, Color, IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference,
+    PdfLayerReference, Point, Rect, Rgb,
+};
+
+use crate::{digest::render::month_name, export::utils::ExportLocale};
+
+use super::models::Statement;
+
+// A4 portrait, in millimetres from the bottom left corner
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 15.0;
+const RIGHT: f32 = PAGE_WIDTH - MARGIN;
+
+const ROW: f32 = 6.0;
+const FONT_SIZE: f32 = 9.0;
+
+// Table columns: left edges, then right edges of the amounts
+const DATE_X: f32 = MARGIN;
+const DESCRIPTION_X: f32 = 38.0;
+const AMOUNT_RIGHT: f32 = 150.0;
+const BASE_AMOUNT_RIGHT: f32 = RIGHT;
+
+/// Characters of a description that fit before the amount column.
+const DESCRIPTION_CHARS: usize = 55;
+
+// Chart: category name, then a bar up to `BAR_WIDTH` long, then the amount
+const BAR_X: f32 = 65.0;
+const BAR_WIDTH: f32 = 95.0;
+
+const MM_PER_POINT: f32 = 0.352_778;
+
+/// Renders the statement as a PDF with the built-in Helvetica fonts: a
+/// chart of the categories, then their expenses with subtotals and the
+/// grand total.
+pub fn render_statement(statement: &Statement) -> anyhow::Result<Vec<u8>> {
+    let title = format!("Relevé de dépenses - {}", month_name(statement.month));
+    let (doc, page, layer) = PdfDocument::new(&title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Page 1");
+    let mut writer = Writer {
+        layers: vec![doc.get_page(page).get_layer(layer)],
+        regular: doc.add_builtin_font(BuiltinFont::Helvetica)?,
+        bold: doc.add_builtin_font(BuiltinFont::HelveticaBold)?,
+        doc,
+        y: PAGE_HEIGHT - MARGIN,
+    };
+
+    writer.text(&title, 16.0, MARGIN, true);
+    writer.advance(8.0);
+    writer.text(&statement.user_name, 11.0, MARGIN, false);
+    writer.advance(5.0);
+    writer.text(
+        &format!(
+            "Montants en {}, édité le {}",
+            statement.currency,
+            statement.generated_at.format("%d/%m/%Y à %H:%M")
+        ),
+        FONT_SIZE,
+        MARGIN,
+        false,
+    );
+    writer.advance(12.0);
+
+    if statement.categories.is_empty() {
+        writer.text("Aucune dépense ce mois-ci.", 11.0, MARGIN, false);
+        return writer.finish();
+    }
+
+    chart(&mut writer, statement);
+    expenses(&mut writer, statement);
+    writer.finish()
+}
+
+/// Horizontal bars of the category subtotals, scaled to the largest.
+fn chart(writer: &mut Writer, statement: &Statement) {
+    writer.heading("Répartition par catégorie");
+    let largest = statement
+        .categories
+        .iter()
+        .map(|c| c.subtotal.to_f32().unwrap_or_default())
+        .fold(0.0, f32::max);
+
+    for category in &statement.categories {
+        writer.ensure_space(ROW);
+        let subtotal = category.subtotal.to_f32().unwrap_or_default();
+        writer.text(&truncate(&category.name, 24), FONT_SIZE, MARGIN, false);
+        if largest > 0.0 && subtotal > 0.0 {
+            let width = (BAR_WIDTH * subtotal / largest).max(0.5);
+            writer.rect(
+                BAR_X,
+                writer.y - 1.0,
+                BAR_X + width,
+                writer.y + 3.0,
+                color(&category.color),
+            );
+        }
+        writer.text_right(&money(&category.subtotal), FONT_SIZE, RIGHT, false);
+        writer.advance(ROW);
+    }
+    writer.advance(ROW);
+}
+
+fn expenses(writer: &mut Writer, statement: &Statement) {
+    writer.heading("Détail des dépenses");
+    writer.ensure_space(ROW * 2.0);
+    writer.text("Date", FONT_SIZE, DATE_X, true);
+    writer.text("Description", FONT_SIZE, DESCRIPTION_X, true);
+    writer.text_right("Montant", FONT_SIZE, AMOUNT_RIGHT, true);
+    writer.text_right(&statement.currency, FONT_SIZE, BASE_AMOUNT_RIGHT, true);
+    writer.advance(2.0);
+    writer.rule(0.5);
+    writer.advance(ROW);
+
+    let mut unconverted = false;
+    for category in &statement.categories {
+        // Keeps the category name with its first expense
+        writer.ensure_space(ROW * 2.0);
+        writer.rect(
+            MARGIN,
+            writer.y - 0.5,
+            MARGIN + 3.0,
+            writer.y + 2.5,
+            color(&category.color),
+        );
+        writer.text(&category.name, FONT_SIZE + 1.0, MARGIN + 5.0, true);
+        writer.advance(ROW);
+
+        for line in &category.lines {
+            writer.ensure_space(ROW);
+            writer.text(
+                &ExportLocale::Fr.format_date(&line.expense_date),
+                FONT_SIZE,
+                DATE_X,
+                false,
+            );
+            writer.text(
+                &truncate(line.description.as_deref().unwrap_or(""), DESCRIPTION_CHARS),
+                FONT_SIZE,
+                DESCRIPTION_X,
+                false,
+            );
+            writer.text_right(
+                &format!("{} {}", money(&line.amount), line.currency),
+                FONT_SIZE,
+                AMOUNT_RIGHT,
+                false,
+            );
+            let base_amount = match &line.amount_in_base {
+                Some(amount) => money(amount),
+                None => {
+                    unconverted = true;
+                    "*".to_string()
+                }
+            };
+            writer.text_right(&base_amount, FONT_SIZE, BASE_AMOUNT_RIGHT, false);
+            writer.advance(ROW);
+        }
+
+        writer.ensure_space(ROW);
+        writer.text_right(
+            &format!("Sous-total {}", category.name),
+            FONT_SIZE,
+            AMOUNT_RIGHT,
+            true,
+        );
+        writer.text_right(
+            &money(&category.subtotal),
+            FONT_SIZE,
+            BASE_AMOUNT_RIGHT,
+            true,
+        );
+        writer.advance(ROW + 2.0);
+    }
+
+    writer.ensure_space(ROW * 2.0);
+    writer.rule(0.8);
+    writer.advance(ROW);
+    writer.text_right("Total", 11.0, AMOUNT_RIGHT, true);
+    writer.text_right(
+        &format!("{} {}", money(&statement.total), statement.currency),
+        11.0,
+        BASE_AMOUNT_RIGHT,
+        true,
+    );
+    if unconverted {
+        writer.advance(ROW * 2.0);
+        writer.ensure_space(ROW);
+        writer.text(
+            "* Taux de change inconnu : dépense exclue des totaux.",
+            FONT_SIZE - 1.0,
+            MARGIN,
+            false,
+        );
+    }
+}
+
+/// Lays out the statement top to bottom, adding pages as needed.
+struct Writer {
+    doc: PdfDocumentReference,
+    layers: Vec<PdfLayerReference>,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    /// Baseline of the current line.
+    y: f32,
+}
+
+impl Writer {
+    fn layer(&self) -> &PdfLayerReference {
+        self.layers.last().expect("the document has a first page")
+    }
+
+    fn advance(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    /// Starts a new page when `height` does not fit above the bottom margin
+    /// and the page footer.
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height >= MARGIN + ROW {
+            return;
+        }
+        let name = format!("Page {}", self.layers.len() + 1);
+        let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), name);
+        self.layers.push(self.doc.get_page(page).get_layer(layer));
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    fn heading(&mut self, text: &str) {
+        self.ensure_space(ROW * 3.0);
+        self.text(text, 12.0, MARGIN, true);
+        self.advance(ROW + 2.0);
+    }
+
+    fn text(&self, text: &str, size: f32, x: f32, bold: bool) {
+        let font = if bold { &self.bold } else { &self.regular };
+        self.layer().set_fill_color(black());
+        self.layer().use_text(text, size, Mm(x), Mm(self.y), font);
+    }
+
+    /// Text ending at `right`.
+    fn text_right(&self, text: &str, size: f32, right: f32, bold: bool) {
+        self.text(text, size, right - text_width(text, size, bold), bold);
+    }
+
+    fn rect(&self, left: f32, bottom: f32, right: f32, top: f32, fill: Color) {
+        self.layer().set_fill_color(fill);
+        self.layer()
+            .add_rect(Rect::new(Mm(left), Mm(bottom), Mm(right), Mm(top)));
+    }
+
+    /// Horizontal line across the page, just above the current line.
+    fn rule(&self, thickness: f32) {
+        let y = self.y + 4.0;
+        self.layer().set_outline_color(black());
+        self.layer().set_outline_thickness(thickness);
+        self.layer().add_line(Line {
+            points: vec![
+                (Point::new(Mm(MARGIN), Mm(y)), false),
+                (Point::new(Mm(RIGHT), Mm(y)), false),
+            ],
+            is_closed: false,
+        });
+    }
+
+    /// Numbers the pages and returns the document.
+    fn finish(self) -> anyhow::Result<Vec<u8>> {
+        let count = self.layers.len();
+        for (index, layer) in self.layers.iter().enumerate() {
+            let text = format!("Page {} / {}", index + 1, count);
+            layer.set_fill_color(black());
+            layer.use_text(
+                &text,
+                FONT_SIZE - 1.0,
+                Mm(RIGHT - text_width(&text, FONT_SIZE - 1.0, false)),
+                Mm(MARGIN - 5.0),
+                &self.regular,
+            );
+        }
+        Ok(self.doc.save_to_bytes()?)
+    }
+}
+
+/// Approximate width of Helvetica text, from its glyph widths in
+/// thousandths of the font size. Only used to right-align short labels and
+/// amounts.
+fn text_width(text: &str, size: f32, bold: bool) -> f32 {
+    let units: u32 = text
+        .chars()
+        .map(|c| match c {
+            '0'..='9' => 556,
+            ' ' | ',' | '.' | '/' => 278,
+            '-' => 333,
+            'A'..='Z' => 700,
+            'i' | 'j' | 'l' => 240,
+            'f' | 't' | 'r' => 350,
+            'm' | 'w' => 850,
+            _ => 556,
+        })
+        .sum();
+    let scale = if bold { 1.05 } else { 1.0 };
+    units as f32 / 1000.0 * size * MM_PER_POINT * scale
+}
+
+fn money(amount: &BigDecimal) -> String {
+    ExportLocale::Fr.format_amount(&amount.with_scale_round(2, RoundingMode::HalfUp))
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars - 3).collect();
+    truncated.push_str("...");
+    truncated
+}
+
+/// Fill colour of a `#rrggbb` category colour, grey when it cannot be read.
+fn color(hex: &str) -> Color {
+    let channel = |range: std::ops::Range<usize>| {
+        hex.get(range)
+            .and_then(|c| u8::from_str_radix(c, 16).ok())
+            .map(|c| c as f32 / 255.0)
+    };
+    match (
+        hex.starts_with('#'),
+        channel(1..3),
+        channel(3..5),
+        channel(5..7),
+    ) {
+        (true, Some(r), Some(g), Some(b)) => Color::Rgb(Rgb::new(r, g, b, None)),
+        _ => Color::Rgb(Rgb::new(0.42, 0.46, 0.49, None)),
+    }
+}
+
+fn black() -> Color {
+    Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None))
+}
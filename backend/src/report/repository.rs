@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+use super::models::StatementLine;
+
+pub struct ReportRepository {
+    pool: Arc<PgPool>,
+}
+
+impl ReportRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        ReportRepository { pool }
+    }
+
+    /// Live expenses over `[from, to)`, grouped by category.
+    pub async fn find_statement_lines(
+        &self,
+        user_id: i32,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> anyhow::Result<Vec<StatementLine>> {
+        sqlx::query_as!(
+            StatementLine,
+            r#"
+    SELECT e.category_id, c.name AS category_name, c.color AS category_color,
+           e.expense_date, e.description, e.amount, e.currency,
+           convert_to_base(e.amount, e.currency, e.user_id, e.expense_date::DATE) AS amount_in_base
+    FROM expenses e
+    JOIN categories c ON c.id = e.category_id
+    WHERE e.user_id = $1 AND e.deleted_at IS NULL
+      AND e.expense_date >= $2::DATE AND e.expense_date < $3::DATE
+    ORDER BY c.name, e.category_id, e.expense_date, e.id;
+    "#,
+            user_id,
+            from,
+            to
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get statement by user_id: {}", user_id))
+    }
+}
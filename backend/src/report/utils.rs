@@ -0,0 +1,18 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+use validator::Validate;
+
+use crate::budget::utils::{parse_month, validate_month};
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct StatementQuery {
+    /// `YYYY-MM`.
+    #[validate(custom(function = "validate_month"))]
+    pub month: String,
+}
+
+impl StatementQuery {
+    pub fn month_start(&self) -> Option<NaiveDate> {
+        parse_month(&self.month)
+    }
+}
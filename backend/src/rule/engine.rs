@@ -0,0 +1,137 @@
+use bigdecimal::BigDecimal;
+use regex::{Regex, RegexBuilder};
+
+use crate::tag::utils::{MAX_TAGS_PER_EXPENSE, normalize_tag};
+
+use super::{
+    models::{Rule, RuleCandidate, RuleChange},
+    utils::MAX_REGEX_SIZE,
+};
+
+/// Enabled rules of a user, compiled once, in priority order.
+pub struct RuleSet {
+    rules: Vec<CompiledRule>,
+}
+
+struct CompiledRule {
+    rule: Rule,
+    contains: Option<String>,
+    regex: Option<Regex>,
+}
+
+/// What the matching rules decide for one expense.
+#[derive(Debug, Default)]
+pub struct RuleMatch {
+    /// Set by the first matching rule that has a category.
+    pub category_id: Option<i32>,
+    /// Added by every matching rule, normalized and without duplicates.
+    pub tags: Vec<String>,
+    pub rule_ids: Vec<i32>,
+}
+
+impl RuleSet {
+    /// `rules` must already be sorted by priority. Disabled rules are left
+    /// out.
+    pub fn new(rules: Vec<Rule>) -> Self {
+        let rules = rules
+            .into_iter()
+            .filter(|rule| rule.enabled)
+            .map(|rule| CompiledRule {
+                contains: rule.description_contains.as_deref().map(str::to_lowercase),
+                // Checked when the rule was saved
+                regex: rule.description_regex.as_deref().and_then(|pattern| {
+                    RegexBuilder::new(pattern)
+                        .case_insensitive(true)
+                        .size_limit(MAX_REGEX_SIZE)
+                        .build()
+                        .ok()
+                }),
+                rule,
+            })
+            .collect();
+        RuleSet { rules }
+    }
+
+    pub fn evaluate(&self, description: Option<&str>, amount: &BigDecimal) -> RuleMatch {
+        let description = description.unwrap_or_default();
+        let lowercase = description.to_lowercase();
+        let mut matched = RuleMatch::default();
+        for compiled in self
+            .rules
+            .iter()
+            .filter(|c| c.matches(description, &lowercase, amount))
+        {
+            if matched.category_id.is_none() {
+                matched.category_id = compiled.rule.category_id;
+            }
+            for tag in &compiled.rule.add_tags {
+                let tag = normalize_tag(tag);
+                if !matched.tags.contains(&tag) {
+                    matched.tags.push(tag);
+                }
+            }
+            matched.rule_ids.push(compiled.rule.id);
+        }
+        matched
+    }
+
+    /// What the rules would change on an existing expense, whose category
+    /// they replace; `None` when nothing.
+    pub fn change_for(&self, candidate: RuleCandidate) -> Option<RuleChange> {
+        let matched = self.evaluate(candidate.description.as_deref(), &candidate.amount);
+        let new_category_id = matched
+            .category_id
+            .filter(|&category_id| category_id != candidate.category_id);
+        let added_tags: Vec<String> = merge_tags(&candidate.tags, &matched.tags)
+            .into_iter()
+            .skip(candidate.tags.len())
+            .collect();
+        if new_category_id.is_none() && added_tags.is_empty() {
+            return None;
+        }
+        Some(RuleChange {
+            expense_id: candidate.id,
+            description: candidate.description,
+            amount: candidate.amount,
+            currency: candidate.currency,
+            expense_date: candidate.expense_date,
+            category_id: candidate.category_id,
+            new_category_id,
+            added_tags,
+            rule_ids: matched.rule_ids,
+        })
+    }
+}
+
+impl CompiledRule {
+    fn matches(&self, description: &str, lowercase: &str, amount: &BigDecimal) -> bool {
+        let rule = &self.rule;
+        if rule.description_regex.is_some() && self.regex.is_none() {
+            return false;
+        }
+        self.contains
+            .as_deref()
+            .is_none_or(|text| lowercase.contains(text))
+            && self
+                .regex
+                .as_ref()
+                .is_none_or(|regex| regex.is_match(description))
+            && rule.min_amount.as_ref().is_none_or(|min| amount >= min)
+            && rule.max_amount.as_ref().is_none_or(|max| amount <= max)
+    }
+}
+
+/// `existing` followed by the `added` tags it lacks, up to the most tags an
+/// expense can carry.
+pub fn merge_tags(existing: &[String], added: &[String]) -> Vec<String> {
+    let mut tags = existing.to_vec();
+    for tag in added {
+        if tags.len() >= MAX_TAGS_PER_EXPENSE {
+            break;
+        }
+        if !tags.contains(tag) {
+            tags.push(tag.clone());
+        }
+    }
+    tags
+}
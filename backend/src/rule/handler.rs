@@ -0,0 +1,190 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post, put},
+};
+
+use crate::{
+    auth::token::claims::Claims,
+    error::AppError,
+    expense::{handler::ensure_category, utils::PatchExpensePayload},
+    state::AppState,
+    validation::ValidatedJson,
+};
+
+use super::{
+    engine::{RuleSet, merge_tags},
+    models::{Rule, RuleChange},
+    utils::{ReorderRulesPayload, RulePayload, RuleRunPayload},
+};
+
+pub async fn get_rules(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Vec<Rule>>), AppError> {
+    let rules = state.rule_repository.find_rules(claims.sub).await?;
+    Ok((StatusCode::OK, Json(rules)))
+}
+
+pub async fn create_rule(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(payload): ValidatedJson<RulePayload>,
+) -> Result<(StatusCode, Json<Rule>), AppError> {
+    if let Some(category_id) = payload.category_id {
+        ensure_category(&state, category_id, claims.sub).await?;
+    }
+    let rule = state
+        .rule_repository
+        .create_rule(&payload, claims.sub)
+        .await?;
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+pub async fn update_rule(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    ValidatedJson(payload): ValidatedJson<RulePayload>,
+) -> Result<(StatusCode, Json<Rule>), AppError> {
+    if let Some(category_id) = payload.category_id {
+        ensure_category(&state, category_id, claims.sub).await?;
+    }
+    let rule = state
+        .rule_repository
+        .update_rule(id, claims.sub, &payload)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Rule not found".into()))?;
+    Ok((StatusCode::OK, Json(rule)))
+}
+
+pub async fn delete_rule(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    match state.rule_repository.delete_rule(id, claims.sub).await? {
+        Some(_) => Ok(StatusCode::NO_CONTENT),
+        None => Ok(StatusCode::NOT_FOUND),
+    }
+}
+
+/// Sets the priority of every rule from its position in `ids`.
+pub async fn reorder_rules(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(payload): ValidatedJson<ReorderRulesPayload>,
+) -> Result<(StatusCode, Json<Vec<Rule>>), AppError> {
+    let mut current: Vec<i32> = state
+        .rule_repository
+        .find_rules(claims.sub)
+        .await?
+        .iter()
+        .map(|rule| rule.id)
+        .collect();
+    let mut requested = payload.ids.clone();
+    current.sort_unstable();
+    requested.sort_unstable();
+    if current != requested {
+        return Err(AppError::BadRequest(
+            "The order must list every rule exactly once".into(),
+        ));
+    }
+
+    let rules = state
+        .rule_repository
+        .reorder_rules(claims.sub, &payload.ids)
+        .await?;
+    Ok((StatusCode::OK, Json(rules)))
+}
+
+/// Dry run: the existing expenses the rules would change, and how. Nothing
+/// is stored.
+pub async fn preview_rules(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(payload): ValidatedJson<RuleRunPayload>,
+) -> Result<(StatusCode, Json<Vec<RuleChange>>), AppError> {
+    let rules = select_rules(&state, claims.sub, payload.rule_ids.as_deref()).await?;
+    let changes = state
+        .rule_repository
+        .find_candidates(claims.sub, payload.from, payload.to)
+        .await?
+        .into_iter()
+        .filter_map(|candidate| rules.change_for(candidate))
+        .collect();
+    Ok((StatusCode::OK, Json(changes)))
+}
+
+/// Runs the rules on existing expenses: a matching category replaces the
+/// current one and matching tags are added. Returns what was changed.
+pub async fn apply_rules(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(payload): ValidatedJson<RuleRunPayload>,
+) -> Result<(StatusCode, Json<Vec<RuleChange>>), AppError> {
+    let rules = select_rules(&state, claims.sub, payload.rule_ids.as_deref()).await?;
+    let candidates = state
+        .rule_repository
+        .find_candidates(claims.sub, payload.from, payload.to)
+        .await?;
+
+    let mut changes = Vec::new();
+    let mut patches = Vec::new();
+    for candidate in candidates {
+        let tags = candidate.tags.clone();
+        let Some(change) = rules.change_for(candidate) else {
+            continue;
+        };
+        let patch = PatchExpensePayload {
+            category_id: change.new_category_id,
+            tags: (!change.added_tags.is_empty()).then(|| merge_tags(&tags, &change.added_tags)),
+            ..Default::default()
+        };
+        patches.push((change.expense_id, patch));
+        changes.push(change);
+    }
+
+    state
+        .expense_repository
+        .update_expenses(claims.sub, &patches)
+        .await?;
+    Ok((StatusCode::OK, Json(changes)))
+}
+
+/// Enabled rules of the user, compiled in priority order.
+pub(crate) async fn load_rules(state: &AppState, user_id: i32) -> Result<RuleSet, AppError> {
+    let rules = state.rule_repository.find_rules(user_id).await?;
+    Ok(RuleSet::new(rules))
+}
+
+/// The rules among `rule_ids`, or all of them; unknown ids are rejected.
+async fn select_rules(
+    state: &AppState,
+    user_id: i32,
+    rule_ids: Option<&[i32]>,
+) -> Result<RuleSet, AppError> {
+    let mut rules = state.rule_repository.find_rules(user_id).await?;
+    if let Some(rule_ids) = rule_ids {
+        if let Some(unknown) = rule_ids
+            .iter()
+            .find(|id| !rules.iter().any(|r| r.id == **id))
+        {
+            return Err(AppError::NotFound(format!("Rule not found: {}", unknown)));
+        }
+        rules.retain(|rule| rule_ids.contains(&rule.id));
+    }
+    Ok(RuleSet::new(rules))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/rules", get(get_rules).post(create_rule))
+        .route("/rules/order", put(reorder_rules))
+        .route("/rules/preview", post(preview_rules))
+        .route("/rules/apply", post(apply_rules))
+        .route("/rules/{id}", put(update_rule).delete(delete_rule))
+}
@@ -0,0 +1,5 @@
+pub mod engine;
+pub mod handler;
+pub mod models;
+pub mod repository;
+pub mod utils;
@@ -0,0 +1,51 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Rule {
+    pub id: i32,
+    pub name: String,
+    /// Lower runs first.
+    pub priority: i32,
+    /// Case-insensitive.
+    pub description_contains: Option<String>,
+    /// Case-insensitive.
+    pub description_regex: Option<String>,
+    pub min_amount: Option<BigDecimal>,
+    pub max_amount: Option<BigDecimal>,
+    /// Category to set.
+    pub category_id: Option<i32>,
+    /// Tags to add.
+    pub add_tags: Vec<String>,
+    pub enabled: bool,
+}
+
+/// An existing expense the rules are run against.
+#[derive(Debug, sqlx::FromRow)]
+pub struct RuleCandidate {
+    pub id: i32,
+    pub description: Option<String>,
+    pub amount: BigDecimal,
+    pub currency: String,
+    pub expense_date: NaiveDateTime,
+    pub category_id: i32,
+    pub tags: Vec<String>,
+}
+
+/// What running the rules does, or would do, to an existing expense.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RuleChange {
+    pub expense_id: i32,
+    pub description: Option<String>,
+    pub amount: BigDecimal,
+    pub currency: String,
+    pub expense_date: NaiveDateTime,
+    pub category_id: i32,
+    /// `None` when the category stays.
+    pub new_category_id: Option<i32>,
+    /// Tags the expense does not carry yet.
+    pub added_tags: Vec<String>,
+    /// Matching rules, in priority order.
+    pub rule_ids: Vec<i32>,
+}
@@ -0,0 +1,168 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+use crate::tag::utils::normalize_tags;
+
+use super::{
+    models::{Rule, RuleCandidate},
+    utils::RulePayload,
+};
+
+pub struct RuleRepository {
+    pool: Arc<PgPool>,
+}
+
+impl RuleRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        RuleRepository { pool }
+    }
+
+    /// Rules of the user, first to run first.
+    pub async fn find_rules(&self, user_id: i32) -> anyhow::Result<Vec<Rule>> {
+        sqlx::query_as!(
+            Rule,
+            r#"
+    SELECT id, name, priority, description_contains, description_regex, min_amount, max_amount,
+           category_id, add_tags, enabled
+    FROM expense_rules
+    WHERE user_id = $1
+    ORDER BY priority, id;
+    "#,
+            user_id
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get rules by user_id: {}", user_id))
+    }
+
+    pub async fn create_rule(&self, payload: &RulePayload, user_id: i32) -> anyhow::Result<Rule> {
+        sqlx::query_as!(
+            Rule,
+            r#"
+    INSERT INTO expense_rules
+        (user_id, name, priority, description_contains, description_regex, min_amount,
+         max_amount, category_id, add_tags, enabled)
+    VALUES ($1, $2,
+            COALESCE($3, (SELECT COALESCE(MAX(priority), 0) + 1
+                          FROM expense_rules WHERE user_id = $1)),
+            $4, $5, $6, $7, $8, $9, $10)
+    RETURNING id, name, priority, description_contains, description_regex, min_amount,
+              max_amount, category_id, add_tags, enabled;
+    "#,
+            user_id,
+            payload.name,
+            payload.priority,
+            payload.description_contains,
+            payload.description_regex,
+            payload.min_amount,
+            payload.max_amount,
+            payload.category_id,
+            &normalize_tags(&payload.add_tags),
+            payload.enabled,
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context("Failed to create rule")
+    }
+
+    /// Replaces every field of the rule; an omitted priority stays.
+    pub async fn update_rule(
+        &self,
+        id: i32,
+        user_id: i32,
+        payload: &RulePayload,
+    ) -> anyhow::Result<Option<Rule>> {
+        sqlx::query_as!(
+            Rule,
+            r#"
+    UPDATE expense_rules
+    SET name = $1,
+        priority = COALESCE($2, priority),
+        description_contains = $3,
+        description_regex = $4,
+        min_amount = $5,
+        max_amount = $6,
+        category_id = $7,
+        add_tags = $8,
+        enabled = $9
+    WHERE id = $10 AND user_id = $11
+    RETURNING id, name, priority, description_contains, description_regex, min_amount,
+              max_amount, category_id, add_tags, enabled;
+    "#,
+            payload.name,
+            payload.priority,
+            payload.description_contains,
+            payload.description_regex,
+            payload.min_amount,
+            payload.max_amount,
+            payload.category_id,
+            &normalize_tags(&payload.add_tags),
+            payload.enabled,
+            id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to update rule by id: {}", id))
+    }
+
+    pub async fn delete_rule(&self, id: i32, user_id: i32) -> anyhow::Result<Option<i32>> {
+        sqlx::query_scalar!(
+            "DELETE FROM expense_rules WHERE id = $1 AND user_id = $2 RETURNING id;",
+            id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to delete rule by id: {}", id))
+    }
+
+    /// Numbers the rules from 1 in the order of `ids`, which must hold every
+    /// rule of the user.
+    pub async fn reorder_rules(&self, user_id: i32, ids: &[i32]) -> anyhow::Result<Vec<Rule>> {
+        sqlx::query!(
+            r#"
+    UPDATE expense_rules r
+    SET priority = o.position
+    FROM UNNEST($2::INTEGER[]) WITH ORDINALITY AS o(id, position)
+    WHERE r.id = o.id AND r.user_id = $1;
+    "#,
+            user_id,
+            ids
+        )
+        .execute(&*self.pool)
+        .await
+        .context(format!("Failed to reorder rules of user_id: {}", user_id))?;
+        self.find_rules(user_id).await
+    }
+
+    /// Live expenses dated between `from` and `to` (inclusive), oldest first.
+    pub async fn find_candidates(
+        &self,
+        user_id: i32,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> anyhow::Result<Vec<RuleCandidate>> {
+        sqlx::query_as!(
+            RuleCandidate,
+            r#"
+    SELECT id, description, amount, currency, expense_date, category_id,
+           expense_tag_names(id) AS "tags!"
+    FROM expenses
+    WHERE user_id = $1 AND deleted_at IS NULL
+      AND ($2::DATE IS NULL OR expense_date >= $2)
+      AND ($3::DATE IS NULL OR expense_date < $3 + INTERVAL '1 day')
+    ORDER BY expense_date, id;
+    "#,
+            user_id,
+            from,
+            to
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get expenses by user_id: {}", user_id))
+    }
+}
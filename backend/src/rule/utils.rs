@@ -0,0 +1,116 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+use crate::tag::utils::validate_tags;
+
+/// Compiled size limit of a rule's regex.
+pub const MAX_REGEX_SIZE: usize = 1 << 20;
+
+/// A rule as created, or replaced by an update.
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_rule"))]
+pub struct RulePayload {
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Name must be between 1 and 100 characters"
+    ))]
+    pub name: String,
+
+    /// Defaults to after every other rule on creation, and to the current
+    /// priority on update.
+    pub priority: Option<i32>,
+
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Text must be between 1 and 255 characters"
+    ))]
+    pub description_contains: Option<String>,
+
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Regex must be between 1 and 255 characters"
+    ))]
+    pub description_regex: Option<String>,
+
+    pub min_amount: Option<BigDecimal>,
+
+    pub max_amount: Option<BigDecimal>,
+
+    #[validate(range(min = 1, message = "Invalid category"))]
+    pub category_id: Option<i32>,
+
+    #[serde(default)]
+    #[validate(custom(function = "validate_tags"))]
+    pub add_tags: Vec<String>,
+
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn validate_rule(rule: &RulePayload) -> Result<(), ValidationError> {
+    if rule.description_contains.is_none()
+        && rule.description_regex.is_none()
+        && rule.min_amount.is_none()
+        && rule.max_amount.is_none()
+    {
+        return Err(ValidationError::new("rule_needs_a_condition"));
+    }
+    if rule.category_id.is_none() && rule.add_tags.is_empty() {
+        return Err(ValidationError::new("rule_needs_an_action"));
+    }
+    if matches!((&rule.min_amount, &rule.max_amount), (Some(min), Some(max)) if min > max) {
+        return Err(ValidationError::new(
+            "min_amount_must_not_exceed_max_amount",
+        ));
+    }
+    if let Some(pattern) = &rule.description_regex {
+        RegexBuilder::new(pattern)
+            .size_limit(MAX_REGEX_SIZE)
+            .build()
+            .map_err(|_| ValidationError::new("invalid_regex"))?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ReorderRulesPayload {
+    /// Every rule id of the user, first to run first.
+    #[validate(length(min = 1, max = 500, message = "Between 1 and 500 rules"))]
+    pub ids: Vec<i32>,
+}
+
+/// Which rules to run on which existing expenses.
+#[derive(Debug, Default, Deserialize, Validate)]
+#[validate(schema(function = "validate_run_bounds"))]
+pub struct RuleRunPayload {
+    /// Defaults to every enabled rule; still run in priority order.
+    #[validate(length(min = 1, max = 500, message = "Between 1 and 500 rules"))]
+    pub rule_ids: Option<Vec<i32>>,
+
+    /// Inclusive lower bound on `expense_date`.
+    pub from: Option<NaiveDate>,
+
+    /// Inclusive upper bound on `expense_date`.
+    pub to: Option<NaiveDate>,
+}
+
+fn validate_run_bounds(payload: &RuleRunPayload) -> Result<(), ValidationError> {
+    if payload
+        .from
+        .zip(payload.to)
+        .is_some_and(|(from, to)| from > to)
+    {
+        return Err(ValidationError::new("from_must_not_be_after_to"));
+    }
+    Ok(())
+}
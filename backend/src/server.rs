@@ -1,27 +1,36 @@
 use std::sync::Arc;
 
-use anyhow::{Context, Ok};
-use axum::Router;
-use sqlx::Postgres;
-use tokio::{net::TcpListener, signal};
-use tower_http::{
-    cors::CorsLayer,
-    compression::CompressionLayer,
-    timeout::TimeoutLayer,
-    limit::RequestBodyLimitLayer,
-};
-use axum::http::Method;
-use axum::http::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE};
-use axum_server::tls_rustls::RustlsConfig;
 use crate::{
+    account, anomaly,
+    attachment::{self, storage::LocalStorage},
     auth,
+    budget::{self, repository::BudgetRepository},
+    category,
     config::Config,
+    currency::{self, rates::load_rate_files, repository::CurrencyRepository},
     database::{DatabaseConnection, PgDatabase},
-    expense,
+    digest::{self, repository::DigestRepository},
+    email::EmailService,
+    expense::{self, repository::ExpenseRepository},
+    export, forecast, goal, group, import, income,
+    recurring::{self, repository::RecurringExpenseRepository},
     redis::{CacheConnection, RedisClient},
+    report, rule,
     state::AppState,
+    summary, tag,
 };
+use anyhow::{Context, Ok};
+use axum::Router;
+use axum::http::Method;
+use axum::http::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE, ETAG, IF_MATCH};
+use axum_server::tls_rustls::RustlsConfig;
+use sqlx::Postgres;
 use std::time::Duration;
+use tokio::{net::TcpListener, signal};
+use tower_http::{
+    compression::CompressionLayer, cors::CorsLayer, limit::RequestBodyLimitLayer,
+    timeout::TimeoutLayer,
+};
 
 pub struct Server<C, D, R>
 where
@@ -50,10 +59,38 @@ impl<C: Config + std::marker::Sync + 'static> Server<C, PgDatabase, RedisClient>
         let listener = TcpListener::bind(&addr)
             .await
             .context("Failed to start tcp connection")?;
-        
+
+        recurring::scheduler::spawn_materializer(RecurringExpenseRepository::new(self.db.pool()));
+        expense::scheduler::spawn_trash_purger(
+            ExpenseRepository::new(self.db.pool()),
+            Box::new(LocalStorage::new(self.config.attachments_dir())),
+            self.config.trash_retention_days(),
+        );
+        digest::scheduler::spawn_digest_sender(
+            DigestRepository::new(self.db.pool()),
+            BudgetRepository::new(self.db.pool()),
+            EmailService::new(
+                self.config.smtp_username().to_string(),
+                self.config.smtp_password().to_string(),
+                self.config.from_email().to_string(),
+            ),
+        );
+
+        if let Some(paths) = self.config.exchange_rates_path() {
+            let repository = CurrencyRepository::new(self.db.pool());
+            let paths = paths.to_string();
+            tokio::spawn(async move {
+                if let Err(e) = load_rate_files(&repository, &paths).await {
+                    tracing::error!("Failed to load exchange rates: {:?}", e);
+                }
+            });
+        }
+
         // Check if HTTPS is enabled
-        
-        if let (Some(cert_path), Some(key_path)) = (self.config.tls_cert_path(), self.config.tls_key_path()) {
+
+        if let (Some(cert_path), Some(key_path)) =
+            (self.config.tls_cert_path(), self.config.tls_key_path())
+        {
             tracing::info!("Starting HTTPS server on https://{addr}");
             self.run_https(listener, cert_path, key_path).await
         } else {
@@ -70,7 +107,12 @@ impl<C: Config + std::marker::Sync + 'static> Server<C, PgDatabase, RedisClient>
         Ok(())
     }
 
-    async fn run_https(&self, listener: TcpListener, cert_path: &str, key_path: &str) -> anyhow::Result<()> {
+    async fn run_https(
+        &self,
+        listener: TcpListener,
+        cert_path: &str,
+        key_path: &str,
+    ) -> anyhow::Result<()> {
         let config = RustlsConfig::from_pem_file(cert_path, key_path)
             .await
             .context("Failed to load TLS certificates")?;
@@ -91,14 +133,15 @@ impl<C: Config + std::marker::Sync + 'static> Server<C, PgDatabase, RedisClient>
             self.redis.client(),
             &self.config,
         ));
-        
+
         // Configure CORS based on protocol
-        let allowed_origin = if self.config.tls_cert_path().is_some() && self.config.tls_key_path().is_some() {
-            format!("https://{}:{}", self.config.host(), self.config.port())
-        } else {
-            "http://localhost:8080".to_string()
-        };
-        
+        let allowed_origin =
+            if self.config.tls_cert_path().is_some() && self.config.tls_key_path().is_some() {
+                format!("https://{}:{}", self.config.host(), self.config.port())
+            } else {
+                "http://localhost:8080".to_string()
+            };
+
         let cors = CorsLayer::new()
             .allow_origin(allowed_origin.parse::<axum::http::HeaderValue>().unwrap())
             .allow_methods([
@@ -109,11 +152,30 @@ impl<C: Config + std::marker::Sync + 'static> Server<C, PgDatabase, RedisClient>
                 Method::DELETE,
             ])
             .allow_credentials(true)
-            .allow_headers([AUTHORIZATION, ACCEPT, CONTENT_TYPE]);
-            
+            .allow_headers([AUTHORIZATION, ACCEPT, CONTENT_TYPE, IF_MATCH])
+            .expose_headers([ETAG]);
+
         Router::new()
             .merge(auth::handler::router())
+            .merge(export::handler::router())
+            .merge(summary::handler::router())
+            .merge(forecast::handler::router())
             .merge(expense::handler::router())
+            .merge(category::handler::router())
+            .merge(tag::handler::router())
+            .merge(budget::handler::router())
+            .merge(currency::handler::router())
+            .merge(income::handler::router())
+            .merge(import::handler::router())
+            .merge(attachment::handler::router())
+            .merge(recurring::handler::router())
+            .merge(group::handler::router())
+            .merge(account::handler::router())
+            .merge(rule::handler::router())
+            .merge(goal::handler::router())
+            .merge(anomaly::handler::router())
+            .merge(digest::handler::router())
+            .merge(report::handler::router())
             // Add performance layers
             .layer(CompressionLayer::new()) // Enable gzip/brotli compression
             .layer(RequestBodyLimitLayer::new(1024 * 1024 * 10)) // 10MB request limit
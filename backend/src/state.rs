@@ -1,15 +1,21 @@
-use std::sync::Arc;
+use ahash::AHasher;
 use dashmap::DashMap;
 use once_cell::sync::Lazy;
 use parking_lot::RwLock;
-use ahash::AHasher;
 use std::hash::BuildHasherDefault;
+use std::sync::Arc;
 
 use fred::prelude::Client as RedisClient;
-use sqlx::{postgres::PgPoolOptions, Pool, Postgres};
 use sqlx::PgPool;
+use sqlx::{Pool, Postgres, postgres::PgPoolOptions};
 
 use crate::{
+    account::repository::AccountRepository,
+    anomaly::repository::AnomalyRepository,
+    attachment::{
+        repository::AttachmentRepository,
+        storage::{AttachmentStorage, LocalStorage},
+    },
     auth::{
         password::PasswordServiceImpl,
         token::{
@@ -19,14 +25,28 @@ use crate::{
             },
         },
     },
+    budget::repository::BudgetRepository,
+    category::repository::CategoryRepository,
     config::Config,
+    currency::repository::CurrencyRepository,
+    digest::repository::DigestRepository,
     email::EmailService,
     expense::repository::ExpenseRepository,
+    forecast::repository::ForecastRepository,
+    goal::repository::GoalRepository,
+    group::repository::GroupRepository,
+    import::repository::ImportRepository,
+    income::repository::IncomeRepository,
+    recurring::repository::RecurringExpenseRepository,
+    report::repository::ReportRepository,
+    rule::repository::RuleRepository,
+    summary::repository::SummaryRepository,
+    tag::repository::TagRepository,
     user::repository::UserRepositoryImpl,
 };
 
 // Global cache for frequently accessed data
-static GLOBAL_CACHE: Lazy<DashMap<String, String, BuildHasherDefault<AHasher>>> = 
+static GLOBAL_CACHE: Lazy<DashMap<String, String, BuildHasherDefault<AHasher>>> =
     Lazy::new(|| DashMap::with_hasher(BuildHasherDefault::default()));
 
 pub struct AppState {
@@ -35,7 +55,26 @@ pub struct AppState {
     pub refresh_token_service: RefreshTokenServiceImpl<RedisRefreshTokenRepository>,
     pub password_service: PasswordServiceImpl,
     pub expense_repository: ExpenseRepository,
+    pub category_repository: CategoryRepository,
+    pub budget_repository: BudgetRepository,
+    pub currency_repository: CurrencyRepository,
+    pub income_repository: IncomeRepository,
+    pub import_repository: ImportRepository,
+    pub attachment_repository: AttachmentRepository,
+    pub attachment_storage: Box<dyn AttachmentStorage>,
+    pub recurring_expense_repository: RecurringExpenseRepository,
+    pub summary_repository: SummaryRepository,
+    pub tag_repository: TagRepository,
+    pub group_repository: GroupRepository,
+    pub account_repository: AccountRepository,
+    pub rule_repository: RuleRepository,
+    pub goal_repository: GoalRepository,
+    pub forecast_repository: ForecastRepository,
+    pub anomaly_repository: AnomalyRepository,
+    pub digest_repository: DigestRepository,
+    pub report_repository: ReportRepository,
     pub email_service: EmailService,
+    pub trash_retention_days: i64,
     // Use RwLock for better read performance when writes are infrequent
     pub config_cache: Arc<RwLock<DashMap<String, String, BuildHasherDefault<AHasher>>>>,
     // Connection pools are already optimized
@@ -45,13 +84,18 @@ pub struct AppState {
 
 impl AppState {
     pub fn new<C: Config>(db: Arc<PgPool>, redis_client: Arc<RedisClient>, config: &C) -> Self {
-        let config_cache = Arc::new(RwLock::new(DashMap::with_hasher(BuildHasherDefault::default())));
-        
+        let config_cache = Arc::new(RwLock::new(DashMap::with_hasher(
+            BuildHasherDefault::default(),
+        )));
+
         // Pre-populate config cache with frequently accessed values
         {
             let mut cache = config_cache.write();
             cache.insert("jwt_secret".to_string(), config.jwt_secret().to_string());
-            cache.insert("frontend_url".to_string(), config.frontend_url().to_string());
+            cache.insert(
+                "frontend_url".to_string(),
+                config.frontend_url().to_string(),
+            );
         }
 
         let user_repository = UserRepositoryImpl::new(db.clone());
@@ -61,6 +105,24 @@ impl AppState {
             RefreshTokenServiceImpl::new(refresh_token_repo, config.refresh_secret());
         let password_service = PasswordServiceImpl::new();
         let expense_repository = ExpenseRepository::new(db.clone());
+        let category_repository = CategoryRepository::new(db.clone());
+        let budget_repository = BudgetRepository::new(db.clone());
+        let currency_repository = CurrencyRepository::new(db.clone());
+        let income_repository = IncomeRepository::new(db.clone());
+        let import_repository = ImportRepository::new(db.clone());
+        let attachment_repository = AttachmentRepository::new(db.clone());
+        let attachment_storage = Box::new(LocalStorage::new(config.attachments_dir()));
+        let recurring_expense_repository = RecurringExpenseRepository::new(db.clone());
+        let summary_repository = SummaryRepository::new(db.clone());
+        let tag_repository = TagRepository::new(db.clone());
+        let group_repository = GroupRepository::new(db.clone());
+        let account_repository = AccountRepository::new(db.clone());
+        let rule_repository = RuleRepository::new(db.clone());
+        let goal_repository = GoalRepository::new(db.clone());
+        let forecast_repository = ForecastRepository::new(db.clone());
+        let anomaly_repository = AnomalyRepository::new(db.clone());
+        let digest_repository = DigestRepository::new(db.clone());
+        let report_repository = ReportRepository::new(db.clone());
         let email_service = EmailService::new(
             config.smtp_username().to_string(),
             config.smtp_password().to_string(),
@@ -73,7 +135,26 @@ impl AppState {
             refresh_token_service,
             password_service,
             expense_repository,
+            category_repository,
+            budget_repository,
+            currency_repository,
+            income_repository,
+            import_repository,
+            attachment_repository,
+            attachment_storage,
+            recurring_expense_repository,
+            summary_repository,
+            tag_repository,
+            group_repository,
+            account_repository,
+            rule_repository,
+            goal_repository,
+            forecast_repository,
+            anomaly_repository,
+            digest_repository,
+            report_repository,
             email_service,
+            trash_retention_days: config.trash_retention_days(),
             config_cache,
             db_pool: (*db).clone(),
             redis_client: (*redis_client).clone(),
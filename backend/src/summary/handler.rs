@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use axum::{Json, Router, extract::State, http::StatusCode, routing::get};
+
+use crate::{
+    auth::token::claims::Claims, error::AppError, state::AppState,
+    user::repository::UserRepository, validation::ValidatedQuery,
+};
+
+use super::{
+    models::{
+        CategoryTotal, ExpenseSummary, PeriodTotal, SummaryBreakdown, TagTotal, WeekdayTotal,
+    },
+    utils::SummaryQuery,
+};
+
+pub async fn get_summary(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedQuery(query): ValidatedQuery<SummaryQuery>,
+) -> Result<(StatusCode, Json<ExpenseSummary>), AppError> {
+    let currency = base_currency(&state, claims.sub).await?;
+    let totals = state
+        .summary_repository
+        .find_totals(claims.sub, query.from, query.to)
+        .await?;
+    Ok((
+        StatusCode::OK,
+        Json(ExpenseSummary {
+            currency,
+            total: totals.total,
+            count: totals.count,
+            average: totals.average,
+            unconverted: totals.unconverted,
+        }),
+    ))
+}
+
+pub async fn get_category_summary(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedQuery(query): ValidatedQuery<SummaryQuery>,
+) -> Result<(StatusCode, Json<SummaryBreakdown<CategoryTotal>>), AppError> {
+    let currency = base_currency(&state, claims.sub).await?;
+    let items = state
+        .summary_repository
+        .find_category_totals(claims.sub, query.from, query.to)
+        .await?;
+    Ok((StatusCode::OK, Json(SummaryBreakdown { currency, items })))
+}
+
+pub async fn get_tag_summary(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedQuery(query): ValidatedQuery<SummaryQuery>,
+) -> Result<(StatusCode, Json<SummaryBreakdown<TagTotal>>), AppError> {
+    let currency = base_currency(&state, claims.sub).await?;
+    let items = state
+        .summary_repository
+        .find_tag_totals(claims.sub, query.from, query.to)
+        .await?;
+    Ok((StatusCode::OK, Json(SummaryBreakdown { currency, items })))
+}
+
+pub async fn get_period_summary(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedQuery(query): ValidatedQuery<SummaryQuery>,
+) -> Result<(StatusCode, Json<SummaryBreakdown<PeriodTotal>>), AppError> {
+    let currency = base_currency(&state, claims.sub).await?;
+    let items = state
+        .summary_repository
+        .find_period_totals(claims.sub, query.from, query.to, query.period)
+        .await?;
+    Ok((StatusCode::OK, Json(SummaryBreakdown { currency, items })))
+}
+
+pub async fn get_weekday_summary(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedQuery(query): ValidatedQuery<SummaryQuery>,
+) -> Result<(StatusCode, Json<SummaryBreakdown<WeekdayTotal>>), AppError> {
+    let currency = base_currency(&state, claims.sub).await?;
+    let items = state
+        .summary_repository
+        .find_weekday_totals(claims.sub, query.from, query.to)
+        .await?;
+    Ok((StatusCode::OK, Json(SummaryBreakdown { currency, items })))
+}
+
+async fn base_currency(state: &AppState, user_id: i32) -> Result<String, AppError> {
+    let user = state
+        .user_repository
+        .find_by_id(user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".into()))?;
+    Ok(user.base_currency)
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/expenses/summary", get(get_summary))
+        .route("/expenses/summary/categories", get(get_category_summary))
+        .route("/expenses/summary/tags", get(get_tag_summary))
+        .route("/expenses/summary/periods", get(get_period_summary))
+        .route("/expenses/summary/weekdays", get(get_weekday_summary))
+}
@@ -0,0 +1,66 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Amounts are in the user's base currency. Expenses without a known
+/// exchange rate are counted but left out of the totals.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExpenseSummary {
+    pub currency: String,
+    pub total: BigDecimal,
+    pub count: i64,
+    /// `None` when there is no expense in the range.
+    pub average: Option<BigDecimal>,
+    /// Expenses left out of `total` for lack of an exchange rate.
+    pub unconverted: i64,
+}
+
+/// Totals row behind `ExpenseSummary`.
+#[derive(Debug)]
+pub struct SummaryTotals {
+    pub total: BigDecimal,
+    pub count: i64,
+    pub average: Option<BigDecimal>,
+    pub unconverted: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CategoryTotal {
+    pub category_id: i32,
+    pub category_name: String,
+    pub total: BigDecimal,
+    pub count: i64,
+    /// Percentage of the range total, one decimal.
+    pub share: BigDecimal,
+}
+
+/// An expense with several tags counts toward each of them.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagTotal {
+    pub tag_id: i32,
+    pub tag_name: String,
+    pub total: BigDecimal,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PeriodTotal {
+    /// First day of the month, or Monday of the week.
+    pub period_start: NaiveDate,
+    pub total: BigDecimal,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WeekdayTotal {
+    /// ISO day of week, 1 (Monday) to 7 (Sunday).
+    pub weekday: i32,
+    pub total: BigDecimal,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SummaryBreakdown<T> {
+    pub currency: String,
+    pub items: Vec<T>,
+}
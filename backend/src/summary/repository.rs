@@ -0,0 +1,185 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+use super::{
+    models::{CategoryTotal, PeriodTotal, SummaryTotals, TagTotal, WeekdayTotal},
+    utils::SummaryPeriod,
+};
+
+pub struct SummaryRepository {
+    pool: Arc<PgPool>,
+}
+
+// Every query converts amounts to the base currency, like budgets and cash
+// flow, and bounds `expense_date` by the optional inclusive `[from, to]`.
+impl SummaryRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        SummaryRepository { pool }
+    }
+
+    pub async fn find_totals(
+        &self,
+        user_id: i32,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> anyhow::Result<SummaryTotals> {
+        sqlx::query_as!(
+            SummaryTotals,
+            r#"
+    WITH filtered AS (
+        SELECT convert_to_base(amount, currency, user_id, expense_date::DATE) AS amount
+        FROM expenses
+        WHERE user_id = $1 AND deleted_at IS NULL
+          AND ($2::DATE IS NULL OR expense_date >= $2::DATE)
+          AND ($3::DATE IS NULL OR expense_date < $3::DATE + 1)
+    )
+    SELECT COALESCE(SUM(amount), 0) AS "total!",
+           COUNT(*) AS "count!",
+           ROUND(AVG(amount), 2) AS average,
+           COUNT(*) FILTER (WHERE amount IS NULL) AS "unconverted!"
+    FROM filtered;
+    "#,
+            user_id,
+            from,
+            to
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context(format!(
+            "Failed to get expense totals by user_id: {}",
+            user_id
+        ))
+    }
+
+    pub async fn find_category_totals(
+        &self,
+        user_id: i32,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> anyhow::Result<Vec<CategoryTotal>> {
+        sqlx::query_as!(
+            CategoryTotal,
+            r#"
+    WITH totals AS (
+        SELECT category_id,
+               COALESCE(SUM(convert_to_base(amount, currency, user_id, expense_date::DATE)), 0) AS total,
+               COUNT(*) AS count
+        FROM expenses
+        WHERE user_id = $1 AND deleted_at IS NULL
+          AND ($2::DATE IS NULL OR expense_date >= $2::DATE)
+          AND ($3::DATE IS NULL OR expense_date < $3::DATE + 1)
+        GROUP BY category_id
+    )
+    SELECT t.category_id AS "category_id!",
+           c.name AS category_name,
+           t.total AS "total!",
+           t.count AS "count!",
+           COALESCE(ROUND(t.total * 100 / NULLIF(SUM(t.total) OVER (), 0), 1), 0) AS "share!"
+    FROM totals t
+    JOIN categories c ON c.id = t.category_id
+    ORDER BY t.total DESC, c.name;
+    "#,
+            user_id,
+            from,
+            to
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get category totals by user_id: {}", user_id))
+    }
+
+    /// Tags without expenses in the range are omitted.
+    pub async fn find_tag_totals(
+        &self,
+        user_id: i32,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> anyhow::Result<Vec<TagTotal>> {
+        sqlx::query_as!(
+            TagTotal,
+            r#"
+    SELECT t.id AS tag_id,
+           t.name AS tag_name,
+           COALESCE(SUM(convert_to_base(e.amount, e.currency, e.user_id, e.expense_date::DATE)), 0) AS "total!",
+           COUNT(*) AS "count!"
+    FROM tags t
+    JOIN expense_tags et ON et.tag_id = t.id
+    JOIN expenses e ON e.id = et.expense_id
+    WHERE t.user_id = $1 AND e.deleted_at IS NULL
+      AND ($2::DATE IS NULL OR e.expense_date >= $2::DATE)
+      AND ($3::DATE IS NULL OR e.expense_date < $3::DATE + 1)
+    GROUP BY t.id
+    ORDER BY 3 DESC, t.name;
+    "#,
+            user_id,
+            from,
+            to
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get tag totals by user_id: {}", user_id))
+    }
+
+    /// Periods without expenses are omitted.
+    pub async fn find_period_totals(
+        &self,
+        user_id: i32,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        period: SummaryPeriod,
+    ) -> anyhow::Result<Vec<PeriodTotal>> {
+        sqlx::query_as!(
+            PeriodTotal,
+            r#"
+    SELECT date_trunc($4, expense_date)::DATE AS "period_start!",
+           COALESCE(SUM(convert_to_base(amount, currency, user_id, expense_date::DATE)), 0) AS "total!",
+           COUNT(*) AS "count!"
+    FROM expenses
+    WHERE user_id = $1 AND deleted_at IS NULL
+      AND ($2::DATE IS NULL OR expense_date >= $2::DATE)
+      AND ($3::DATE IS NULL OR expense_date < $3::DATE + 1)
+    GROUP BY 1
+    ORDER BY 1;
+    "#,
+            user_id,
+            from,
+            to,
+            period.as_str()
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get period totals by user_id: {}", user_id))
+    }
+
+    /// Days of week without expenses are omitted.
+    pub async fn find_weekday_totals(
+        &self,
+        user_id: i32,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> anyhow::Result<Vec<WeekdayTotal>> {
+        sqlx::query_as!(
+            WeekdayTotal,
+            r#"
+    SELECT EXTRACT(ISODOW FROM expense_date)::INTEGER AS "weekday!",
+           COALESCE(SUM(convert_to_base(amount, currency, user_id, expense_date::DATE)), 0) AS "total!",
+           COUNT(*) AS "count!"
+    FROM expenses
+    WHERE user_id = $1 AND deleted_at IS NULL
+      AND ($2::DATE IS NULL OR expense_date >= $2::DATE)
+      AND ($3::DATE IS NULL OR expense_date < $3::DATE + 1)
+    GROUP BY 1
+    ORDER BY 1;
+    "#,
+            user_id,
+            from,
+            to
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get weekday totals by user_id: {}", user_id))
+    }
+}
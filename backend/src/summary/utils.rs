@@ -0,0 +1,41 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+use validator::{Validate, ValidationError};
+
+use crate::expense::utils::validate_date_range;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryPeriod {
+    #[default]
+    Month,
+    Week,
+}
+
+impl SummaryPeriod {
+    /// Unit understood by `date_trunc`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SummaryPeriod::Month => "month",
+            SummaryPeriod::Week => "week",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[validate(schema(function = "validate_summary_range"))]
+pub struct SummaryQuery {
+    /// Inclusive lower bound on `expense_date`.
+    pub from: Option<NaiveDate>,
+
+    /// Inclusive upper bound on `expense_date`.
+    pub to: Option<NaiveDate>,
+
+    /// Grouping of `/expenses/summary/periods`.
+    #[serde(default)]
+    pub period: SummaryPeriod,
+}
+
+fn validate_summary_range(query: &SummaryQuery) -> Result<(), ValidationError> {
+    validate_date_range(query.from, query.to)
+}
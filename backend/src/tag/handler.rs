@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, put},
+};
+
+use crate::{
+    auth::token::claims::Claims, error::AppError, state::AppState, validation::ValidatedJson,
+};
+
+use super::{
+    models::Tag,
+    utils::{CreateTagPayload, UpdateTagPayload, normalize_tag},
+};
+
+pub async fn get_tags(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Vec<Tag>>), AppError> {
+    let tags = state.tag_repository.find_tags(claims.sub).await?;
+    Ok((StatusCode::OK, Json(tags)))
+}
+
+pub async fn create_tag(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(payload): ValidatedJson<CreateTagPayload>,
+) -> Result<(StatusCode, Json<Tag>), AppError> {
+    let name = normalize_tag(&payload.name);
+    if state
+        .tag_repository
+        .exists_by_name(claims.sub, &name, None)
+        .await?
+    {
+        return Err(AppError::Conflict("Tag already exists".into()));
+    }
+
+    let tag = state.tag_repository.create_tag(&name, claims.sub).await?;
+    Ok((StatusCode::CREATED, Json(tag)))
+}
+
+pub async fn update_tag(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    ValidatedJson(payload): ValidatedJson<UpdateTagPayload>,
+) -> Result<(StatusCode, Json<Tag>), AppError> {
+    let name = normalize_tag(&payload.name);
+    if state
+        .tag_repository
+        .exists_by_name(claims.sub, &name, Some(id))
+        .await?
+    {
+        return Err(AppError::Conflict("Tag already exists".into()));
+    }
+
+    let tag = state
+        .tag_repository
+        .rename_tag(id, claims.sub, &name)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Tag not found".into()))?;
+    Ok((StatusCode::OK, Json(tag)))
+}
+
+pub async fn delete_tag(
+    claims: Claims,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    match state.tag_repository.delete_tag(id, claims.sub).await? {
+        Some(_) => Ok(StatusCode::NO_CONTENT),
+        None => Ok(StatusCode::NOT_FOUND),
+    }
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/tags", get(get_tags).post(create_tag))
+        .route("/tags/{id}", put(update_tag).delete(delete_tag))
+}
@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use sqlx::PgPool;
+
+use super::models::Tag;
+
+pub struct TagRepository {
+    pool: Arc<PgPool>,
+}
+
+impl TagRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        TagRepository { pool }
+    }
+
+    pub async fn find_tags(&self, user_id: i32) -> anyhow::Result<Vec<Tag>> {
+        sqlx::query_as!(
+            Tag,
+            r#"
+    SELECT t.id, t.name, COUNT(e.id) AS "expense_count!"
+    FROM tags t
+    LEFT JOIN expense_tags et ON et.tag_id = t.id
+    LEFT JOIN expenses e ON e.id = et.expense_id AND e.deleted_at IS NULL
+    WHERE t.user_id = $1
+    GROUP BY t.id
+    ORDER BY t.name;
+    "#,
+            user_id
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .context(format!("Failed to get tags by user_id: {}", user_id))
+    }
+
+    pub async fn exists_by_name(
+        &self,
+        user_id: i32,
+        name: &str,
+        exclude_id: Option<i32>,
+    ) -> anyhow::Result<bool> {
+        let exists = sqlx::query_scalar!(
+            r#"
+    SELECT EXISTS(
+        SELECT 1 FROM tags
+        WHERE user_id = $1 AND name = $2 AND id IS DISTINCT FROM $3
+    ) AS "exists!";
+    "#,
+            user_id,
+            name,
+            exclude_id
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context("Failed to check if tag exists")?;
+        Ok(exists)
+    }
+
+    pub async fn create_tag(&self, name: &str, user_id: i32) -> anyhow::Result<Tag> {
+        sqlx::query_as!(
+            Tag,
+            r#"
+    INSERT INTO tags (user_id, name)
+    VALUES ($1, $2)
+    RETURNING id, name, 0::BIGINT AS "expense_count!";
+    "#,
+            user_id,
+            name
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .context("Failed to create tag")
+    }
+
+    pub async fn rename_tag(
+        &self,
+        id: i32,
+        user_id: i32,
+        name: &str,
+    ) -> anyhow::Result<Option<Tag>> {
+        sqlx::query_as!(
+            Tag,
+            r#"
+    UPDATE tags SET name = $3
+    WHERE id = $1 AND user_id = $2
+    RETURNING id, name,
+              (SELECT COUNT(*) FROM expense_tags et
+               JOIN expenses e ON e.id = et.expense_id
+               WHERE et.tag_id = tags.id AND e.deleted_at IS NULL) AS "expense_count!";
+    "#,
+            id,
+            user_id,
+            name
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to rename tag by id: {}", id))
+    }
+
+    /// The tag is removed from its expenses.
+    pub async fn delete_tag(&self, id: i32, user_id: i32) -> anyhow::Result<Option<i32>> {
+        sqlx::query_scalar!(
+            "DELETE FROM tags WHERE id = $1 AND user_id = $2 RETURNING id;",
+            id,
+            user_id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .context(format!("Failed to delete tag by id: {}", id))
+    }
+}
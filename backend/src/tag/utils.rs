@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+/// Most tags a single expense can carry.
+pub const MAX_TAGS_PER_EXPENSE: usize = 20;
+
+const MAX_TAG_LENGTH: usize = 50;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateTagPayload {
+    #[validate(custom(function = "validate_tag"))]
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct UpdateTagPayload {
+    #[validate(custom(function = "validate_tag"))]
+    pub name: String,
+}
+
+/// Tags are compared and stored trimmed and lowercased.
+pub fn normalize_tag(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// Normalized, sorted and without duplicates.
+pub fn normalize_tags(names: &[String]) -> Vec<String> {
+    let mut names: Vec<String> = names.iter().map(|n| normalize_tag(n)).collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Commas are reserved as the separator of the `tags` list filter.
+pub(crate) fn validate_tag(name: &str) -> Result<(), ValidationError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_TAG_LENGTH {
+        return Err(ValidationError::new(
+            "tag_must_be_between_1_and_50_characters",
+        ));
+    }
+    if name.contains(',') {
+        return Err(ValidationError::new("tag_must_not_contain_commas"));
+    }
+    Ok(())
+}
+
+pub(crate) fn validate_tags(names: &[String]) -> Result<(), ValidationError> {
+    if names.len() > MAX_TAGS_PER_EXPENSE {
+        return Err(ValidationError::new("too_many_tags"));
+    }
+    names.iter().try_for_each(|name| validate_tag(name))
+}
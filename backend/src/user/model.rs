@@ -8,10 +8,11 @@ pub struct User {
     pub name: String,
     pub email: String,
     pub password_hash: String,
-    pub email_verified: Option<bool>,          // Add this line
-    pub verification_token: Option<String>, 
+    pub email_verified: Option<bool>, // Add this line
+    pub verification_token: Option<String>,
     pub password_reset_token: Option<String>,
     pub password_reset_expires_at: Option<NaiveDateTime>,
     pub created_at: Option<NaiveDateTime>, // Add this line
-    pub updated_at: Option<NaiveDateTime>
+    pub updated_at: Option<NaiveDateTime>,
+    pub base_currency: String,
 }
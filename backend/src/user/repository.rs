@@ -13,6 +13,7 @@ pub trait UserRepository: Send + Sync {
     async fn find_by_email(&self, email: &str) -> Result<Option<User>>;
     async fn find_by_id(&self, id: i32) -> Result<Option<User>>;
     async fn update_name(&self, id: i32, name: &str) -> Result<Option<User>>;
+    async fn update_base_currency(&self, id: i32, currency: &str) -> Result<Option<User>>;
     // Add these methods for email verification
     async fn set_verification_token(&self, user_id: i32, token: &str) -> Result<()>;
     async fn verify_email_token(&self, token: &str) -> Result<Option<User>>;
@@ -21,11 +22,14 @@ pub trait UserRepository: Send + Sync {
     // Password reset methods
     async fn set_password_reset_token(&self, user_id: i32, reset_token: &str) -> Result<()>;
     async fn verify_password_reset_token(&self, reset_token: &str) -> Result<Option<User>>;
-    async fn update_password_and_clear_reset_token(&self, user_id: i32, password_hash: &str) -> Result<()>;
+    async fn update_password_and_clear_reset_token(
+        &self,
+        user_id: i32,
+        password_hash: &str,
+    ) -> Result<()>;
 
     // New method to update password without affecting other fields
     async fn update_password(&self, user_id: i32, password_hash: &str) -> Result<()>;
-
 }
 
 pub struct UserRepositoryImpl {
@@ -55,7 +59,7 @@ impl UserRepository for UserRepositoryImpl {
     }
 
     async fn find_by_email(&self, email: &str) -> Result<Option<User>> {
-        sqlx::query_as!(User, "SELECT id, name, email, password_hash, email_verified, verification_token,password_reset_token,password_reset_expires_at, created_at,updated_at FROM users WHERE email = $1", email)
+        sqlx::query_as!(User, "SELECT id, name, email, password_hash, email_verified, verification_token,password_reset_token,password_reset_expires_at, created_at,updated_at, base_currency FROM users WHERE email = $1", email)
             .fetch_optional(&*self.pool)
             .await
             .with_context(|| format!("Failed to find user by email: {}", email))
@@ -72,7 +76,7 @@ impl UserRepository for UserRepositoryImpl {
     }
 
     async fn find_by_id(&self, id: i32) -> Result<Option<User>> {
-        sqlx::query_as!(User, "SELECT id, name, email, password_hash, email_verified, verification_token, password_reset_token,password_reset_expires_at,created_at,updated_at FROM users WHERE id = $1", id)
+        sqlx::query_as!(User, "SELECT id, name, email, password_hash, email_verified, verification_token, password_reset_token,password_reset_expires_at,created_at,updated_at, base_currency FROM users WHERE id = $1", id)
             .fetch_optional(&*self.pool)
             .await
             .with_context(|| format!("Failed to find user by id: {}", id))
@@ -90,6 +94,18 @@ impl UserRepository for UserRepositoryImpl {
         .with_context(|| format!("Failed to update user name for id: {}", id))
     }
 
+    async fn update_base_currency(&self, id: i32, currency: &str) -> Result<Option<User>> {
+        sqlx::query_as!(
+            User,
+            "UPDATE users SET base_currency = $1, updated_at = NOW() WHERE id = $2 RETURNING *",
+            currency,
+            id
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .with_context(|| format!("Failed to update base currency for id: {}", id))
+    }
+
     async fn set_verification_token(&self, user_id: i32, token: &str) -> Result<()> {
         sqlx::query!(
             "UPDATE users SET verification_token = $1 WHERE id = $2",
@@ -105,7 +121,7 @@ impl UserRepository for UserRepositoryImpl {
     async fn verify_email_token(&self, token: &str) -> Result<Option<User>> {
         sqlx::query_as!(
             User,
-            "SELECT id, name, email, password_hash, email_verified, verification_token, password_reset_token,password_reset_expires_at,created_at,updated_at FROM users WHERE verification_token = $1 AND email_verified = FALSE",
+            "SELECT id, name, email, password_hash, email_verified, verification_token, password_reset_token,password_reset_expires_at,created_at,updated_at, base_currency FROM users WHERE verification_token = $1 AND email_verified = FALSE",
             token
         )
         .fetch_optional(&*self.pool)
@@ -139,7 +155,7 @@ impl UserRepository for UserRepositoryImpl {
     async fn verify_password_reset_token(&self, reset_token: &str) -> Result<Option<User>> {
         sqlx::query_as!(
             User,
-            "SELECT id, name, email, password_hash, email_verified, verification_token, password_reset_token,password_reset_expires_at,created_at,updated_at FROM users WHERE password_reset_token = $1 AND password_reset_expires_at > NOW()",
+            "SELECT id, name, email, password_hash, email_verified, verification_token, password_reset_token,password_reset_expires_at,created_at,updated_at, base_currency FROM users WHERE password_reset_token = $1 AND password_reset_expires_at > NOW()",
             reset_token
         )
         .fetch_optional(&*self.pool)
@@ -147,7 +163,11 @@ impl UserRepository for UserRepositoryImpl {
         .context("Failed to find user by password reset token")
     }
 
-    async fn update_password_and_clear_reset_token(&self, user_id: i32, password_hash: &str) -> Result<()> {
+    async fn update_password_and_clear_reset_token(
+        &self,
+        user_id: i32,
+        password_hash: &str,
+    ) -> Result<()> {
         sqlx::query!(
             "UPDATE users SET password_hash = $1, password_reset_token = NULL, password_reset_expires_at = NULL, updated_at = NOW() WHERE id = $2",
             password_hash,
@@ -159,11 +179,7 @@ impl UserRepository for UserRepositoryImpl {
         .context("Failed to update password and clear reset token")
     }
 
-    async fn update_password(
-        &self,
-        user_id: i32,
-        password_hash: &str,
-    ) -> Result<()> {
+    async fn update_password(&self, user_id: i32, password_hash: &str) -> Result<()> {
         sqlx::query!(
             "UPDATE users SET password_hash = $1, updated_at = NOW() WHERE id = $2",
             password_hash,
@@ -3,12 +3,15 @@ use gloo_net::http::Request;
 use web_sys::{HtmlInputElement, console};
 use yew::TargetCast;
 use crate::context::auth::{use_auth, check_auth_response};
-use crate::types::{Expense, ExpenseCategory};
+use chrono::NaiveDate;
+use crate::types::{Category, Expense, ExpenseChanges};
 use crate::services::api_service::ApiService;
+use crate::services::tag_service::{format_tags, parse_tags};
 
 #[derive(Properties, PartialEq)]
 pub struct EditExpenseModalProps {
     pub expense: Option<Expense>,
+    pub categories: Vec<Category>,
     pub show: bool,
     pub on_close: Callback<()>,
     pub on_update: Callback<()>,
@@ -18,7 +21,13 @@ pub struct EditExpenseModalProps {
 pub fn edit_expense_modal(props: &EditExpenseModalProps) -> Html {
     let description = use_state(|| "".to_string());
     let amount = use_state(|| "".to_string());
-    let category = use_state(|| ExpenseCategory::Others);
+    let category = use_state(|| 0);
+    let tags = use_state(|| "".to_string());
+    let expense_date = use_state(|| "".to_string());
+    // Version the form was loaded from, sent in `If-Match`
+    let base = use_state(|| None::<Expense>);
+    // Current version after someone else saved first
+    let conflict = use_state(|| None::<Expense>);
     let response_message = use_state(|| "".to_string());
     let auth = use_auth();
 
@@ -27,14 +36,22 @@ pub fn edit_expense_modal(props: &EditExpenseModalProps) -> Html {
         let description = description.clone();
         let amount = amount.clone();
         let category = category.clone();
+        let tags = tags.clone();
+        let expense_date = expense_date.clone();
+        let base = base.clone();
+        let conflict = conflict.clone();
         let expense = props.expense.clone();
         
         use_effect_with(expense, move |expense| {
             if let Some(exp) = expense {
                 description.set(exp.description.clone().unwrap_or_default());
                 amount.set(exp.amount.to_string());
-                category.set(exp.category);
-                console::log_1(&format!("Initialized category from record: {:?}", exp.category).into());
+                category.set(exp.category_id);
+                tags.set(format_tags(&exp.tags));
+                expense_date.set(date_value(exp));
+                base.set(Some(exp.clone()));
+                conflict.set(None);
+                console::log_1(&format!("Initialized category from record: {:?}", exp.category_id).into());
             }
             || ()
         });
@@ -44,22 +61,32 @@ pub fn edit_expense_modal(props: &EditExpenseModalProps) -> Html {
         let description = description.clone();
         let amount = amount.clone();
         let category = category.clone();
+        let tags = tags.clone();
+        let expense_date = expense_date.clone();
+        let conflict = conflict.clone();
         let response_message = response_message.clone();
         let auth = auth.clone();
-        let expense = props.expense.clone();
+        let expense = base.clone();
         let on_update = props.on_update.clone();
         let on_close = props.on_close.clone();
 
         Callback::from(move |_: MouseEvent| {
             console::log_1(&"on_save clicked".into());
             
-            if let Some(exp) = &expense {
+            if let Some(exp) = &*expense {
                 let description = description.clone();
                 let amount = amount.clone();
                 let category = category.clone();
+                let tags = tags.clone();
+                let conflict = conflict.clone();
                 let response_message = response_message.clone();
                 let auth = auth.clone();
                 let expense_id = exp.id;
+                let version = exp.version;
+                let original_date = exp.expense_date.date();
+                let expense_date = NaiveDate::parse_from_str(&expense_date, "%Y-%m-%d")
+                    .ok()
+                    .filter(|date| *date != original_date);
                 let on_update = on_update.clone();
                 let on_close = on_close.clone();
 
@@ -67,18 +94,19 @@ pub fn edit_expense_modal(props: &EditExpenseModalProps) -> Html {
                     console::log_1(&format!("auth.access_token: {:?}", auth.access_token).into());
                     
                     if let Some(token) = &auth.access_token {
-                        let updated_expense = Expense {
-                            id: expense_id,
+                        let changes = ExpenseChanges {
                             description: if description.is_empty() { None } else { Some((*description).clone()) },
                             amount: (*amount).parse().unwrap_or_default(),
-                            category: *category,
-                            expense_date: chrono::Utc::now().naive_utc(),
+                            category_id: *category,
+                            expense_date,
+                            tags: parse_tags(&tags),
                         };
 
-                        let res = ApiService::put("/expenses")
+                        let res = ApiService::patch(&format!("/expenses/{}", expense_id))
                             .header("Authorization", &format!("Bearer {}", token))
                             .header("Content-Type", "application/json")
-                            .json(&updated_expense)
+                            .header("If-Match", &format!("\"{}\"", version))
+                            .json(&changes)
                             .unwrap()
                             .send()
                             .await;
@@ -89,6 +117,12 @@ pub fn edit_expense_modal(props: &EditExpenseModalProps) -> Html {
                                     if resp.status() == 200 {
                                         on_update.emit(());
                                         on_close.emit(());
+                                    } else if resp.status() == 400 {
+                                        response_message.set("Date invalide ou trop éloignée dans le futur".to_string());
+                                    } else if resp.status() == 412
+                                        && let Ok(current) = resp.json::<Expense>().await
+                                    {
+                                        conflict.set(Some(current));
                                     }
                                 }
                             }
@@ -105,6 +139,46 @@ pub fn edit_expense_modal(props: &EditExpenseModalProps) -> Html {
         })
     };
 
+    // Loads the version saved in the meantime; merging keeps the fields
+    // changed in the form, reloading drops them
+    let on_resolve = {
+        let description = description.clone();
+        let amount = amount.clone();
+        let category = category.clone();
+        let tags = tags.clone();
+        let expense_date = expense_date.clone();
+        let base = base.clone();
+        let conflict = conflict.clone();
+        let response_message = response_message.clone();
+
+        Callback::from(move |merge: bool| {
+            let (Some(original), Some(current)) = ((*base).clone(), (*conflict).clone()) else {
+                return;
+            };
+            let pick = |mine: &str, was: String, theirs: String| {
+                if merge && mine != was { mine.to_string() } else { theirs }
+            };
+            description.set(pick(
+                &description,
+                original.description.clone().unwrap_or_default(),
+                current.description.clone().unwrap_or_default(),
+            ));
+            amount.set(pick(&amount, original.amount.to_string(), current.amount.to_string()));
+            tags.set(pick(&tags, format_tags(&original.tags), format_tags(&current.tags)));
+            expense_date.set(pick(&expense_date, date_value(&original), date_value(&current)));
+            if !merge || *category == original.category_id {
+                category.set(current.category_id);
+            }
+            response_message.set(if merge {
+                "Vos modifications ont été appliquées sur la dernière version, vérifiez avant de sauvegarder".to_string()
+            } else {
+                "".to_string()
+            });
+            base.set(Some(current));
+            conflict.set(None);
+        })
+    };
+
     if !props.show {
         return html! {};
     }
@@ -171,37 +245,98 @@ pub fn edit_expense_modal(props: &EditExpenseModalProps) -> Html {
                                     <label class="form-label fw-semibold">{ "Catégorie" }</label>
                                     <select
                                         class="form-select form-select-lg"
-                                        value={format!("{:?}", *category)}
+                                        value={category.to_string()}
                                         onchange={{
                                             let category = category.clone();
                                             Callback::from(move |e: Event| {
                                                 let input: HtmlInputElement = e.target_unchecked_into();
-                                                let value = input.value();
-                                                let cat = match value.as_str() {
-                                                    "Groceries" => ExpenseCategory::Groceries,
-                                                    "Leisure" => ExpenseCategory::Leisure,
-                                                    "Electronics" => ExpenseCategory::Electronics,
-                                                    "Utilities" => ExpenseCategory::Utilities,
-                                                    "Clothing" => ExpenseCategory::Clothing,
-                                                    "Health" => ExpenseCategory::Health,
-                                                    _ => ExpenseCategory::Others,
-                                                };
-                                                category.set(cat);
+                                                if let Ok(id) = input.value().parse::<i32>() {
+                                                    category.set(id);
+                                                }
                                             })
                                         }}
                                     >
-                                        <option value="Groceries">{ "Alimentation" }</option>
-                                        <option value="Leisure">{ "Loisirs" }</option>
-                                        <option value="Electronics">{ "Électronique" }</option>
-                                        <option value="Utilities">{ "Factures" }</option>
-                                        <option value="Clothing">{ "Vêtements" }</option>
-                                        <option value="Health">{ "Santé" }</option>
-                                        <option value="Others">{ "Autres" }</option>
+                                        {
+                                            for props.categories.iter().map(|c| html! {
+                                                <option value={c.id.to_string()} selected={c.id == *category}>{ c.name.clone() }</option>
+                                            })
+                                        }
                                     </select>
                                 </div>
                             </div>
                         </div>
+
+                        <div class="row">
+                            <div class="col-12 col-sm-6">
+                                <div class="mb-3">
+                                    <label class="form-label fw-semibold">{ "Date" }</label>
+                                    <input
+                                        type="date"
+                                        class="form-control form-control-lg"
+                                        value={(*expense_date).clone()}
+                                        oninput={{
+                                            let expense_date = expense_date.clone();
+                                            Callback::from(move |e: InputEvent| {
+                                                let input: HtmlInputElement = e.target_unchecked_into();
+                                                expense_date.set(input.value());
+                                            })
+                                        }}
+                                    />
+                                </div>
+                            </div>
+                            <div class="col-12 col-sm-6">
+                                <div class="mb-3">
+                                    <label class="form-label fw-semibold">{ "Tags" }</label>
+                                    <input
+                                        type="text"
+                                        class="form-control form-control-lg"
+                                        placeholder="vacances-2026, travail"
+                                        value={(*tags).clone()}
+                                        oninput={{
+                                            let tags = tags.clone();
+                                            Callback::from(move |e: InputEvent| {
+                                                let input: HtmlInputElement = e.target_unchecked_into();
+                                                tags.set(input.value());
+                                            })
+                                        }}
+                                    />
+                                </div>
+                            </div>
+                        </div>
                         
+                        {
+                            if conflict.is_some() {
+                                html! {
+                                    <div class="alert alert-warning" role="alert">
+                                        <p class="mb-2">{ "Cette dépense a été modifiée par quelqu'un d'autre depuis son ouverture." }</p>
+                                        <div class="d-flex gap-2">
+                                            <button
+                                                type="button"
+                                                class="btn btn-sm btn-outline-secondary"
+                                                onclick={{
+                                                    let on_resolve = on_resolve.clone();
+                                                    Callback::from(move |_| on_resolve.emit(false))
+                                                }}
+                                            >
+                                                { "Recharger" }
+                                            </button>
+                                            <button
+                                                type="button"
+                                                class="btn btn-sm btn-warning"
+                                                onclick={{
+                                                    let on_resolve = on_resolve.clone();
+                                                    Callback::from(move |_| on_resolve.emit(true))
+                                                }}
+                                            >
+                                                { "Fusionner avec mes modifications" }
+                                            </button>
+                                        </div>
+                                    </div>
+                                }
+                            } else {
+                                html! {}
+                            }
+                        }
                         {
                             if !(*response_message).is_empty() {
                                 html! {
@@ -229,6 +364,7 @@ pub fn edit_expense_modal(props: &EditExpenseModalProps) -> Html {
                             <button 
                                 type="button" 
                                 class="btn btn-primary flex-fill flex-sm-grow-0"
+                                disabled={conflict.is_some()}
                                 onclick={on_save}
                             >
                                 { "Sauvegarder" }
@@ -240,3 +376,8 @@ pub fn edit_expense_modal(props: &EditExpenseModalProps) -> Html {
         </div>
     }
 }
+
+/// Value of a date input for the day of the expense.
+fn date_value(expense: &Expense) -> String {
+    expense.expense_date.date().format("%Y-%m-%d").to_string()
+}
@@ -18,6 +18,10 @@ pub enum Route {
     AddExpense,
     #[at("/expenses/manage")]
     ManageExpenses,
+    #[at("/categories")]
+    Categories,
+    #[at("/incomes")]
+    Incomes,
     #[at("/profile")]
     Profile,
     #[at("/logout")]
@@ -101,6 +105,14 @@ pub fn navbar() -> Html {
                                             <i class="bi bi-list-ul"></i>
                                             <span class="d-lg-inline ms-1">{ " Gérer" }</span>
                                         </Link<Route>>
+                                        <Link<Route> to={Route::Categories} classes="nav-link text-dark mx-1 mx-lg-2">
+                                            <i class="bi bi-tags"></i>
+                                            <span class="d-lg-inline ms-1">{ " Catégories" }</span>
+                                        </Link<Route>>
+                                        <Link<Route> to={Route::Incomes} classes="nav-link text-dark mx-1 mx-lg-2">
+                                            <i class="bi bi-cash-coin"></i>
+                                            <span class="d-lg-inline ms-1">{ " Revenus" }</span>
+                                        </Link<Route>>
                                         <Link<Route> to={Route::Profile} classes="nav-link text-dark mx-1 mx-lg-2">
                                             <i class="bi bi-person"></i>
                                             <span class="d-lg-inline ms-1">{ " Profil" }</span>
@@ -19,6 +19,8 @@ use crate::components::layout::Navbar;
 use crate::pages::{
     manage_expenses::ManageExpenses,
     add_expense::AddExpense,
+    categories::ManageCategories,
+    incomes::ManageIncomes,
 };
 
 #[derive(Clone, Routable, PartialEq)]
@@ -37,6 +39,10 @@ enum Route {
     AddExpense,
     #[at("/expenses/manage")]
     ManageExpenses,
+    #[at("/categories")]
+    Categories,
+    #[at("/incomes")]
+    Incomes,
 }
 
 fn switch(routes: Route) -> Html {
@@ -49,6 +55,8 @@ fn switch(routes: Route) -> Html {
         Route::Logout => html! { <LogoutPage /> },
         Route::AddExpense => html! { <AddExpense /> },
         Route::ManageExpenses => html! { <ManageExpenses /> }, 
+        Route::Categories => html! { <ManageCategories /> },
+        Route::Incomes => html! { <ManageIncomes /> },
     }
 }
 
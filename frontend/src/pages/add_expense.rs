@@ -4,16 +4,26 @@ use gloo_net::http::Request;
 use web_sys::HtmlInputElement;
 use yew::TargetCast;
 use serde::Serialize;
+use chrono::NaiveDate;
 use crate::context::auth::use_auth;
-use crate::types::ExpenseCategory;
 use crate::components::layout::Route;
 use crate::services::api_service::ApiService;
+use crate::services::category_service::{use_categories, default_category_id, find_category};
+use crate::services::currency_service::use_currencies;
+use crate::services::tag_service::parse_tags;
+use crate::types::CreatedExpense;
 
 #[derive(Serialize)]
 struct NewExpense<'a> {
     description: &'a str,
     amount: &'a str,
-    category: ExpenseCategory,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category_id: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    currency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expense_date: Option<NaiveDate>,
+    tags: Vec<String>,
 }
 
 #[function_component(AddExpense)]
@@ -22,8 +32,20 @@ pub fn add_expense() -> Html {
     let navigator = use_navigator().unwrap();
     let description = use_state(|| "".to_string());
     let amount = use_state(|| "".to_string());
-    let category = use_state(|| ExpenseCategory::Others);
+    let categories = use_categories(auth.access_token.clone());
+    // None lets the rules pick the category, or else the default one
+    let category = use_state(|| None::<i32>);
+    let fallback_category = default_category_id(&categories)
+        .and_then(|id| find_category(&categories, id))
+        .map(|c| c.name.clone())
+        .unwrap_or_default();
+    let currencies = use_currencies(auth.access_token.clone());
+    // None sends the expense in the user's base currency
+    let currency = use_state(|| None::<String>);
+    let tags = use_state(|| "".to_string());
+    let expense_date = use_state(|| "".to_string());
     let response_message = use_state(|| "".to_string());
+    let anomaly_message = use_state(|| None::<String>);
 
     // Redirect if not logged in
     if auth.token.is_none() {
@@ -34,7 +56,11 @@ pub fn add_expense() -> Html {
         let description = description.clone();
         let amount = amount.clone();
         let category = category.clone();
+        let currency = currency.clone();
+        let tags = tags.clone();
+        let expense_date = expense_date.clone();
         let response_message = response_message.clone();
+        let anomaly_message = anomaly_message.clone();
         let auth = auth.clone();
         let navigator = navigator.clone();
 
@@ -43,7 +69,11 @@ pub fn add_expense() -> Html {
             let description = description.clone();
             let amount = amount.clone();
             let category = category.clone();
+            let currency = currency.clone();
+            let tags = tags.clone();
+            let expense_date = expense_date.clone();
             let response_message = response_message.clone();
+            let anomaly_message = anomaly_message.clone();
             let auth = auth.clone();
             let navigator = navigator.clone();
 
@@ -52,7 +82,10 @@ pub fn add_expense() -> Html {
                     let new_expense = NewExpense {
                         description: &description,
                         amount: &amount,
-                        category: *category,
+                        category_id: *category,
+                        currency: (*currency).clone(),
+                        expense_date: NaiveDate::parse_from_str(&expense_date, "%Y-%m-%d").ok(),
+                        tags: parse_tags(&tags),
                     };
                     let res = ApiService::post("/expenses")
                         .header("Authorization", &format!("Bearer {}", token))
@@ -64,12 +97,25 @@ pub fn add_expense() -> Html {
                     match res {
                         Ok(resp) => {
                             if resp.status() == 201 {
+                                let anomaly = resp
+                                    .json::<CreatedExpense>()
+                                    .await
+                                    .ok()
+                                    .and_then(|created| created.anomaly);
                                 response_message.set("Dépense ajoutée avec succès!".to_string());
                                 description.set("".to_string());
                                 amount.set("".to_string());
-                                category.set(ExpenseCategory::Others);
-                                // Redirect to manage expenses after 2 seconds
-                                gloo::timers::callback::Timeout::new(2000, move || {
+                                category.set(None);
+                                tags.set("".to_string());
+                                expense_date.set("".to_string());
+                                // Leave time to read the warning before leaving
+                                let delay = if anomaly.is_some() { 6000 } else { 2000 };
+                                anomaly_message.set(anomaly.map(|a| format!(
+                                    "Montant inhabituel pour cette catégorie (habituellement autour de {}). Vous pourrez le confirmer ou l'ignorer dans la liste des anomalies.",
+                                    a.median
+                                )));
+                                // Redirect to manage expenses afterwards
+                                gloo::timers::callback::Timeout::new(delay, move || {
                                     navigator.push(&Route::ManageExpenses);
                                 }).forget();
                             } else {
@@ -118,7 +164,7 @@ pub fn add_expense() -> Html {
                                 <div class="row">
                                     <div class="col-12 col-sm-6">
                                         <div class="mb-3">
-                                            <label class="form-label fw-semibold">{ "Montant (€)" }</label>
+                                            <label class="form-label fw-semibold">{ "Montant" }</label>
                                             <input
                                                 type="number"
                                                 step="0.01"
@@ -141,37 +187,88 @@ pub fn add_expense() -> Html {
                                             <label class="form-label fw-semibold">{ "Catégorie" }</label>
                                             <select
                                                 class="form-select form-select-lg"
-                                                value={format!("{:?}", *category)}
                                                 onchange={{
                                                     let category = category.clone();
                                                     Callback::from(move |e: Event| {
                                                         let input: HtmlInputElement = e.target_unchecked_into();
-                                                        let value = input.value();
-                                                        let cat = match value.as_str() {
-                                                            "Groceries" => ExpenseCategory::Groceries,
-                                                            "Leisure" => ExpenseCategory::Leisure,
-                                                            "Electronics" => ExpenseCategory::Electronics,
-                                                            "Utilities" => ExpenseCategory::Utilities,
-                                                            "Clothing" => ExpenseCategory::Clothing,
-                                                            "Health" => ExpenseCategory::Health,
-                                                            _ => ExpenseCategory::Others,
-                                                        };
-                                                        category.set(cat);
+                                                        category.set(input.value().parse::<i32>().ok());
                                                     })
                                                 }}
                                             >
-                                                <option value="Groceries">{ "Alimentation" }</option>
-                                                <option value="Leisure">{ "Loisirs" }</option>
-                                                <option value="Electronics">{ "Électronique" }</option>
-                                                <option value="Utilities">{ "Factures" }</option>
-                                                <option value="Clothing">{ "Vêtements" }</option>
-                                                <option value="Health">{ "Santé" }</option>
-                                                <option value="Others">{ "Autres" }</option>
+                                                <option value="" selected={category.is_none()}>{ "Automatique" }</option>
+                                                {
+                                                    for categories.iter().map(|c| html! {
+                                                        <option value={c.id.to_string()} selected={Some(c.id) == *category}>{ c.name.clone() }</option>
+                                                    })
+                                                }
                                             </select>
+                                            <div class="form-text">{ format!("Selon vos règles, sinon {}", fallback_category) }</div>
                                         </div>
                                     </div>
                                 </div>
                                 
+                                <div class="row">
+                                    <div class="col-12 col-sm-6">
+                                        <div class="mb-3">
+                                            <label class="form-label fw-semibold">{ "Devise" }</label>
+                                            <select
+                                                class="form-select form-select-lg"
+                                                onchange={{
+                                                    let currency = currency.clone();
+                                                    Callback::from(move |e: Event| {
+                                                        let input: HtmlInputElement = e.target_unchecked_into();
+                                                        let value = input.value();
+                                                        currency.set(if value.is_empty() { None } else { Some(value) });
+                                                    })
+                                                }}
+                                            >
+                                                <option value="" selected={currency.is_none()}>{ "Devise principale" }</option>
+                                                {
+                                                    for currencies.iter().map(|c| html! {
+                                                        <option value={c.code.clone()} selected={Some(&c.code) == currency.as_ref()}>{ c.code.clone() }</option>
+                                                    })
+                                                }
+                                            </select>
+                                        </div>
+                                    </div>
+                                    <div class="col-12 col-sm-6">
+                                        <div class="mb-3">
+                                            <label class="form-label fw-semibold">{ "Tags" }</label>
+                                            <input
+                                                type="text"
+                                                class="form-control form-control-lg"
+                                                placeholder="vacances-2026, travail"
+                                                value={(*tags).clone()}
+                                                oninput={{
+                                                    let tags = tags.clone();
+                                                    Callback::from(move |e: InputEvent| {
+                                                        let input: HtmlInputElement = e.target_unchecked_into();
+                                                        tags.set(input.value());
+                                                    })
+                                                }}
+                                            />
+                                        </div>
+                                    </div>
+                                    <div class="col-12 col-sm-6">
+                                        <div class="mb-3">
+                                            <label class="form-label fw-semibold">{ "Date" }</label>
+                                            <input
+                                                type="date"
+                                                class="form-control form-control-lg"
+                                                value={(*expense_date).clone()}
+                                                oninput={{
+                                                    let expense_date = expense_date.clone();
+                                                    Callback::from(move |e: InputEvent| {
+                                                        let input: HtmlInputElement = e.target_unchecked_into();
+                                                        expense_date.set(input.value());
+                                                    })
+                                                }}
+                                            />
+                                            <div class="form-text">{ "Aujourd'hui si vide" }</div>
+                                        </div>
+                                    </div>
+                                </div>
+
                                 <div class="row">
                                     <div class="col-12">
                                         <div class="d-grid gap-3">
@@ -196,6 +293,17 @@ pub fn add_expense() -> Html {
                                     html! {}
                                 }
                             }
+                            {
+                                if let Some(message) = &*anomaly_message {
+                                    html! {
+                                        <div class="alert alert-warning mt-3" role="alert">
+                                            <i class="bi bi-exclamation-triangle me-2"></i>{ message.clone() }
+                                        </div>
+                                    }
+                                } else {
+                                    html! {}
+                                }
+                            }
                         </div>
                     </div>
                 </div>
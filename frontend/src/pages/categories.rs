@@ -0,0 +1,218 @@
+use yew::prelude::*;
+use yew_router::prelude::*;
+use web_sys::HtmlInputElement;
+use yew::TargetCast;
+use crate::context::auth::use_auth;
+use crate::types::CategoryForm;
+use crate::components::layout::Route;
+use crate::services::category_service::{use_categories, CategoryService};
+
+#[function_component(ManageCategories)]
+pub fn manage_categories() -> Html {
+    let auth = use_auth();
+    let navigator = use_navigator().unwrap();
+    let categories = use_categories(auth.access_token.clone());
+    let name = use_state(|| "".to_string());
+    let color = use_state(|| "#0d6efd".to_string());
+    let icon = use_state(|| "".to_string());
+    let response_message = use_state(|| "".to_string());
+
+    // Redirect if not logged in
+    if auth.token.is_none() {
+        navigator.push(&Route::Login);
+    }
+
+    let on_create = {
+        let name = name.clone();
+        let color = color.clone();
+        let icon = icon.clone();
+        let categories = categories.clone();
+        let response_message = response_message.clone();
+        let auth = auth.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let name = name.clone();
+            let icon = icon.clone();
+            let categories = categories.clone();
+            let response_message = response_message.clone();
+            let form = CategoryForm {
+                name: (*name).trim().to_string(),
+                color: (*color).clone(),
+                icon: if icon.is_empty() { None } else { Some((*icon).clone()) },
+            };
+
+            if let Some(token) = auth.access_token.clone() {
+                wasm_bindgen_futures::spawn_local(async move {
+                    match CategoryService::create(&token, &form).await {
+                        Ok(_) => {
+                            response_message.set("Catégorie ajoutée".to_string());
+                            name.set("".to_string());
+                            icon.set("".to_string());
+                            if let Ok(list) = CategoryService::fetch_all(&token).await {
+                                categories.set(list);
+                            }
+                        }
+                        Err(err) => response_message.set(err),
+                    }
+                });
+            }
+        })
+    };
+
+    let on_delete = {
+        let categories = categories.clone();
+        let response_message = response_message.clone();
+        let auth = auth.clone();
+        Callback::from(move |id: i32| {
+            let categories = categories.clone();
+            let response_message = response_message.clone();
+            if let Some(token) = auth.access_token.clone() {
+                wasm_bindgen_futures::spawn_local(async move {
+                    match CategoryService::delete(&token, id).await {
+                        Ok(()) => {
+                            response_message.set("Catégorie supprimée, ses dépenses ont été réaffectées".to_string());
+                            if let Ok(list) = CategoryService::fetch_all(&token).await {
+                                categories.set(list);
+                            }
+                        }
+                        Err(err) => response_message.set(err),
+                    }
+                });
+            }
+        })
+    };
+
+    html! {
+        <div class="container-fluid">
+            <div class="row justify-content-center">
+                <div class="col-12 col-lg-10 col-xl-8">
+                    <h2 class="mb-4">{ "Catégories" }</h2>
+                    <div class="row g-4">
+                        <div class="col-12 col-md-5">
+                            <div class="card shadow-sm">
+                                <div class="card-header bg-primary text-white">
+                                    <h5 class="mb-0">{ "Nouvelle catégorie" }</h5>
+                                </div>
+                                <div class="card-body">
+                                    <form onsubmit={on_create}>
+                                        <div class="mb-3">
+                                            <label class="form-label fw-semibold">{ "Nom" }</label>
+                                            <input
+                                                type="text"
+                                                class="form-control"
+                                                maxlength="50"
+                                                required=true
+                                                value={(*name).clone()}
+                                                oninput={{
+                                                    let name = name.clone();
+                                                    Callback::from(move |e: InputEvent| {
+                                                        let input: HtmlInputElement = e.target_unchecked_into();
+                                                        name.set(input.value());
+                                                    })
+                                                }}
+                                            />
+                                        </div>
+                                        <div class="mb-3">
+                                            <label class="form-label fw-semibold">{ "Couleur" }</label>
+                                            <input
+                                                type="color"
+                                                class="form-control form-control-color"
+                                                value={(*color).clone()}
+                                                oninput={{
+                                                    let color = color.clone();
+                                                    Callback::from(move |e: InputEvent| {
+                                                        let input: HtmlInputElement = e.target_unchecked_into();
+                                                        color.set(input.value());
+                                                    })
+                                                }}
+                                            />
+                                        </div>
+                                        <div class="mb-3">
+                                            <label class="form-label fw-semibold">{ "Icône (Bootstrap Icons)" }</label>
+                                            <input
+                                                type="text"
+                                                class="form-control"
+                                                placeholder="bi-cart"
+                                                value={(*icon).clone()}
+                                                oninput={{
+                                                    let icon = icon.clone();
+                                                    Callback::from(move |e: InputEvent| {
+                                                        let input: HtmlInputElement = e.target_unchecked_into();
+                                                        icon.set(input.value());
+                                                    })
+                                                }}
+                                            />
+                                        </div>
+                                        <div class="d-grid">
+                                            <button type="submit" class="btn btn-primary">{ "Ajouter" }</button>
+                                        </div>
+                                    </form>
+                                    {
+                                        if !(*response_message).is_empty() {
+                                            html! {
+                                                <div class="alert alert-info mt-3" role="alert">
+                                                    { (*response_message).clone() }
+                                                </div>
+                                            }
+                                        } else {
+                                            html! {}
+                                        }
+                                    }
+                                </div>
+                            </div>
+                        </div>
+                        <div class="col-12 col-md-7">
+                            <div class="card shadow-sm">
+                                <div class="card-header bg-primary text-white">
+                                    <h5 class="mb-0">{ format!("Mes catégories ({})", categories.len()) }</h5>
+                                </div>
+                                <ul class="list-group list-group-flush">
+                                    {
+                                        for categories.iter().map(|category| {
+                                            let id = category.id;
+                                            let on_delete = on_delete.clone();
+                                            html! {
+                                                <li class="list-group-item d-flex justify-content-between align-items-center">
+                                                    <span>
+                                                        <span class="badge me-2" style={format!("background-color: {}", category.color)}>
+                                                            <i class={classes!("bi", category.icon.clone())}></i>
+                                                        </span>
+                                                        { category.name.clone() }
+                                                        {
+                                                            if category.is_default {
+                                                                html! { <small class="text-muted ms-2">{ "(par défaut)" }</small> }
+                                                            } else {
+                                                                html! {}
+                                                            }
+                                                        }
+                                                    </span>
+                                                    {
+                                                        if category.is_default {
+                                                            html! {}
+                                                        } else {
+                                                            html! {
+                                                                <button
+                                                                    type="button"
+                                                                    class="btn btn-outline-danger btn-sm"
+                                                                    title="Supprimer"
+                                                                    onclick={Callback::from(move |_| on_delete.emit(id))}
+                                                                >
+                                                                    <i class="bi bi-trash"></i>
+                                                                </button>
+                                                            }
+                                                        }
+                                                    }
+                                                </li>
+                                            }
+                                        })
+                                    }
+                                </ul>
+                            </div>
+                        </div>
+                    </div>
+                </div>
+            </div>
+        </div>
+    }
+}
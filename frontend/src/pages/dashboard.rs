@@ -1,72 +1,104 @@
-use itertools::Itertools;
 use yew::prelude::*;
 use gloo_net::http::Request;
 use crate::context::auth::{use_auth, check_auth_response};
-use crate::types::{Expense, ExpenseCategory};
+use crate::types::{CashFlowReport, CategoryTotal, Expense, ExpensePage, ExpenseSummary, PeriodTotal, SpendingForecast};
 use chrono::Datelike;
 use crate::services::api_service::ApiService;
+use crate::services::category_service::{use_categories, find_category};
+use crate::services::income_service::IncomeService;
+use crate::services::summary_service::SummaryService;
 
 
 #[function_component(ExpenseDashboard)]
 pub fn expense_dashboard() -> Html {
-    let expenses = use_state(|| vec![] as Vec<Expense>);
+    let recent_expenses = use_state(|| vec![] as Vec<Expense>);
     let auth = use_auth();
+    let categories = use_categories(auth.access_token.clone());
+    let cash_flow = use_state(|| None::<CashFlowReport>);
+    let summary = use_state(|| None::<ExpenseSummary>);
+    let category_totals = use_state(|| vec![] as Vec<CategoryTotal>);
+    let monthly_totals = use_state(|| vec![] as Vec<PeriodTotal>);
+    let forecast = use_state(|| None::<SpendingForecast>);
 
-    // Fetch expenses
+    // Fetch the monthly cash flow
     {
-        let expenses = expenses.clone();
-        let access_token = auth.access_token.clone();
-        let auth_for_effect = auth.clone();
-        use_effect_with(
-            access_token.clone(),
-            move |access_token| {
-                if let Some(token) = access_token {
-                    let expenses = expenses.clone();
-                    let token = token.clone();
-                    let auth = auth_for_effect.clone();
-                    wasm_bindgen_futures::spawn_local(async move {
-                        let res = ApiService::get("/expenses")
-                            .header("Authorization", &format!("Bearer {}", token))
-                            .send()
-                            .await;
-                        if let Ok(resp) = res {
-                            if check_auth_response(resp.status(), &auth) && resp.status() == 200 {
-                                if let Ok(list) = resp.json::<Vec<Expense>>().await {
-                                    expenses.set(list);
-                                }
-                            }
-                        }
-                    });
-                }
-                || ()
+        let cash_flow = cash_flow.clone();
+        use_effect_with(auth.access_token.clone(), move |access_token| {
+            if let Some(token) = access_token.clone() {
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Ok(report) = IncomeService::cash_flow(&token).await {
+                        cash_flow.set(Some(report));
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    // Fetch the totals, grouped on the server
+    {
+        let summary = summary.clone();
+        let category_totals = category_totals.clone();
+        let monthly_totals = monthly_totals.clone();
+        use_effect_with(auth.access_token.clone(), move |access_token| {
+            if let Some(token) = access_token.clone() {
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Ok(totals) = SummaryService::totals(&token).await {
+                        summary.set(Some(totals));
+                    }
+                    if let Ok(breakdown) = SummaryService::by_category(&token).await {
+                        category_totals.set(breakdown.items);
+                    }
+                    if let Ok(breakdown) = SummaryService::by_month(&token).await {
+                        monthly_totals.set(breakdown.items);
+                    }
+                });
             }
-        );
+            || ()
+        });
     }
 
-    // Calculate totals
-    let total_amount: f64 = expenses.iter()
-        .map(|e| e.amount.to_string().parse::<f64>().unwrap_or(0.0))
-        .sum();
+    // Fetch the month-end forecast
+    {
+        let forecast = forecast.clone();
+        use_effect_with(auth.access_token.clone(), move |access_token| {
+            if let Some(token) = access_token.clone() {
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Ok(report) = SummaryService::forecast(&token).await {
+                        forecast.set(Some(report));
+                    }
+                });
+            }
+            || ()
+        });
+    }
 
-    let category_totals: std::collections::HashMap<ExpenseCategory, f64> = {
-        let mut totals = std::collections::HashMap::new();
-        for expense in expenses.iter() {
-            let amount = expense.amount.to_string().parse::<f64>().unwrap_or(0.0);
-            *totals.entry(expense.category).or_insert(0.0) += amount;
-        }
-        totals
-    };
+    // Fetch the most recent expenses
+    {
+        let recent_expenses = recent_expenses.clone();
+        let auth_for_effect = auth.clone();
+        use_effect_with(auth.access_token.clone(), move |access_token| {
+            if let Some(token) = access_token.clone() {
+                let auth = auth_for_effect.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let res = ApiService::get("/expenses?limit=5")
+                        .header("Authorization", &format!("Bearer {}", token))
+                        .send()
+                        .await;
+                    if let Ok(resp) = res
+                        && check_auth_response(resp.status(), &auth)
+                        && resp.status() == 200
+                        && let Ok(page) = resp.json::<ExpensePage>().await
+                    {
+                        recent_expenses.set(page.items);
+                    }
+                });
+            }
+            || ()
+        });
+    }
 
-    // Group expenses by month
-    let monthly_totals: std::collections::HashMap<String, f64> = {
-        let mut totals = std::collections::HashMap::new();
-        for expense in expenses.iter() {
-            let amount = expense.amount.to_string().parse::<f64>().unwrap_or(0.0);
-            let month_key = format!("{:04}-{:02}", expense.expense_date.year(), expense.expense_date.month());
-            *totals.entry(month_key).or_insert(0.0) += amount;
-        }
-        totals
-    };
+    let currency = summary.as_ref().map(|s| s.currency.clone()).unwrap_or_default();
 
     html! {
         <div class="container-fluid">
@@ -84,7 +116,7 @@ pub fn expense_dashboard() -> Html {
                     <div class="card text-white bg-primary h-100">
                         <div class="card-body text-center">
                             <h6 class="card-title">{ "Total" }</h6>
-                            <h4 class="mb-0">{ format!("{:.0} €", total_amount) }</h4>
+                            <h4 class="mb-0">{ summary.as_ref().map(|s| format!("{} {}", s.total, s.currency)).unwrap_or_default() }</h4>
                         </div>
                     </div>
                 </div>
@@ -92,7 +124,7 @@ pub fn expense_dashboard() -> Html {
                     <div class="card text-white bg-success h-100">
                         <div class="card-body text-center">
                             <h6 class="card-title">{ "Nombre" }</h6>
-                            <h4 class="mb-0">{ expenses.len() }</h4>
+                            <h4 class="mb-0">{ summary.as_ref().map(|s| s.count).unwrap_or_default() }</h4>
                         </div>
                     </div>
                 </div>
@@ -100,11 +132,11 @@ pub fn expense_dashboard() -> Html {
                     <div class="card text-white bg-info h-100">
                         <div class="card-body text-center">
                             <h6 class="card-title">{ "Moyenne" }</h6>
-                            <h4 class="mb-0">{ 
-                                if expenses.is_empty() { 
-                                    "0 €".to_string() 
-                                } else { 
-                                    format!("{:.0} €", total_amount / expenses.len() as f64) 
+                            <h4 class="mb-0">{
+                                match summary.as_ref() {
+                                    Some(ExpenseSummary { average: Some(average), currency, .. }) => format!("{} {}", average, currency),
+                                    Some(ExpenseSummary { currency, .. }) => format!("0 {}", currency),
+                                    None => String::new(),
                                 }
                             }</h4>
                         </div>
@@ -129,30 +161,20 @@ pub fn expense_dashboard() -> Html {
                         </div>
                         <div class="card-body">
                             {
-                                for category_totals.iter().map(|(category, total)| {
-                                    let category_name = match category {
-                                        ExpenseCategory::Groceries => "Alimentation",
-                                        ExpenseCategory::Leisure => "Loisirs",
-                                        ExpenseCategory::Electronics => "Électronique",
-                                        ExpenseCategory::Utilities => "Factures",
-                                        ExpenseCategory::Clothing => "Vêtements",
-                                        ExpenseCategory::Health => "Santé",
-                                        ExpenseCategory::Others => "Autres",
-                                    };
-                                    let percentage = if total_amount > 0.0 { (total / total_amount) * 100.0 } else { 0.0 };
-                                    
+                                for category_totals.iter().map(|total| {
+                                    let percentage = total.share.to_string();
                                     html! {
                                         <div class="mb-3">
                                             <div class="d-flex justify-content-between">
-                                                <span>{ category_name }</span>
-                                                <span>{ format!("{:.2} € ({:.1}%)", total, percentage) }</span>
+                                                <span>{ total.category_name.clone() }</span>
+                                                <span>{ format!("{} {} ({}%)", total.total, currency, total.share) }</span>
                                             </div>
                                             <div class="progress">
                                                 <div 
                                                     class="progress-bar" 
                                                     role="progressbar" 
                                                     style={format!("width: {}%", percentage)}
-                                                    aria-valuenow={percentage.to_string()}
+                                                    aria-valuenow={percentage.clone()}
                                                     aria-valuemin="0" 
                                                     aria-valuemax="100"
                                                 ></div>
@@ -175,32 +197,30 @@ pub fn expense_dashboard() -> Html {
                                 if monthly_totals.is_empty() {
                                     html! { <span>{ "Aucune dépense enregistrée." }</span> }
                                 } else {
-                                    let mut sorted_months: Vec<_> = monthly_totals.iter().collect();
-                                    sorted_months.sort_by(|a, b| b.0.cmp(a.0));
                                     html! {
                                         <ul class="list-group">
                                             {
-                                                for sorted_months.iter().map(|(month, total)| {
-                                                    let month_name = match month.split('-').nth(1).unwrap_or("00") {
-                                                        "01" => "Janvier",
-                                                        "02" => "Février", 
-                                                        "03" => "Mars",
-                                                        "04" => "Avril",
-                                                        "05" => "Mai",
-                                                        "06" => "Juin",
-                                                        "07" => "Juillet",
-                                                        "08" => "Août",
-                                                        "09" => "Septembre",
-                                                        "10" => "Octobre",
-                                                        "11" => "Novembre",
-                                                        "12" => "Décembre",
+                                                for monthly_totals.iter().rev().map(|month| {
+                                                    let month_name = match month.period_start.month() {
+                                                        1 => "Janvier",
+                                                        2 => "Février",
+                                                        3 => "Mars",
+                                                        4 => "Avril",
+                                                        5 => "Mai",
+                                                        6 => "Juin",
+                                                        7 => "Juillet",
+                                                        8 => "Août",
+                                                        9 => "Septembre",
+                                                        10 => "Octobre",
+                                                        11 => "Novembre",
+                                                        12 => "Décembre",
                                                         _ => "Inconnu"
                                                     };
-                                                    let year = month.split('-').nth(0).unwrap_or("0000");
+                                                    let year = month.period_start.year();
                                                     html! {
                                                         <li class="list-group-item d-flex justify-content-between align-items-center">
                                                             { format!("{} {}", month_name, year) }
-                                                            <span class="badge bg-primary rounded-pill">{ format!("{:.2} €", total) }</span>
+                                                            <span class="badge bg-primary rounded-pill">{ format!("{} {}", month.total, currency) }</span>
                                                         </li>
                                                     }
                                                 })
@@ -221,14 +241,14 @@ pub fn expense_dashboard() -> Html {
                         <div class="card-body">
                             <div class="list-group list-group-flush" style="max-height: 300px; overflow-y: auto;">
                                 {
-                                        for expenses.iter().sorted_by(|a, b| b.expense_date.cmp(&a.expense_date)).take(5).map(|expense| {
+                                        for recent_expenses.iter().map(|expense| {
                                             html! {
                                                 <div class="list-group-item d-flex justify-content-between align-items-center">
                                                     <div>
                                                         <h6 class="mb-1">{ expense.description.as_deref().unwrap_or("Sans description") }</h6>
-                                                        <small class="text-muted">{ format!("{:?}", expense.category) }</small>
+                                                        <small class="text-muted">{ find_category(&categories, expense.category_id).map(|c| c.name.clone()).unwrap_or_default() }</small>
                                                     </div>
-                                                    <span class="badge bg-primary rounded-pill">{ format!("{} €", expense.amount) }</span>
+                                                    <span class="badge bg-primary rounded-pill">{ format!("{} {}", expense.amount, expense.currency) }</span>
                                                 </div>
                                             }
                                         })
@@ -238,6 +258,114 @@ pub fn expense_dashboard() -> Html {
                     </div>
                 </div>
             </div>
+
+            // Month-end forecast
+            <div class="row g-4 mt-1">
+                <div class="col-12">
+                    <div class="card shadow-sm">
+                        <div class="card-header bg-secondary text-white">
+                            <h6 class="mb-0">{
+                                match &*forecast {
+                                    Some(report) => format!("Prévision de fin de mois (jour {} sur {})", report.days_elapsed, report.days_in_month),
+                                    None => "Prévision de fin de mois".to_string(),
+                                }
+                            }</h6>
+                        </div>
+                        <div class="card-body p-0">
+                            {
+                                match &*forecast {
+                                    Some(report) if report.categories.is_empty() => html! {
+                                        <p class="text-muted p-3 mb-0">{ "Pas encore de dépenses ce mois-ci." }</p>
+                                    },
+                                    Some(report) => html! {
+                                        <div class="table-responsive">
+                                            <table class="table table-sm table-striped mb-0">
+                                                <thead>
+                                                    <tr>
+                                                        <th>{ "Catégorie" }</th>
+                                                        <th class="text-end">{ "Dépensé" }</th>
+                                                        <th class="text-end">{ "Prévu" }</th>
+                                                        <th class="text-end">{ "Fourchette" }</th>
+                                                    </tr>
+                                                </thead>
+                                                <tbody>
+                                                    {
+                                                        for report.categories.iter().map(|category| html! {
+                                                            <tr>
+                                                                <td>{ category.category_name.clone() }</td>
+                                                                <td class="text-end">{ format!("{} {}", category.actual, report.currency) }</td>
+                                                                <td class="text-end fw-bold">{ format!("{} {}", category.projected, report.currency) }</td>
+                                                                <td class="text-end text-muted">{ format!("{} – {}", category.low, category.high) }</td>
+                                                            </tr>
+                                                        })
+                                                    }
+                                                </tbody>
+                                                <tfoot>
+                                                    <tr class="fw-bold">
+                                                        <td>{ "Total" }</td>
+                                                        <td class="text-end">{ format!("{} {}", report.actual, report.currency) }</td>
+                                                        <td class="text-end">{ format!("{} {}", report.projected, report.currency) }</td>
+                                                        <td class="text-end text-muted">{ format!("{} – {}", report.low, report.high) }</td>
+                                                    </tr>
+                                                </tfoot>
+                                            </table>
+                                        </div>
+                                    },
+                                    None => html! { <p class="text-muted p-3 mb-0">{ "Chargement..." }</p> },
+                                }
+                            }
+                        </div>
+                    </div>
+                </div>
+            </div>
+
+            // Cash flow
+            <div class="row g-4 mt-1">
+                <div class="col-12">
+                    <div class="card shadow-sm">
+                        <div class="card-header bg-dark text-white">
+                            <h6 class="mb-0">{ "Flux de trésorerie (12 derniers mois)" }</h6>
+                        </div>
+                        <div class="card-body p-0">
+                            {
+                                if let Some(report) = &*cash_flow {
+                                    html! {
+                                        <div class="table-responsive">
+                                            <table class="table table-sm table-striped mb-0">
+                                                <thead>
+                                                    <tr>
+                                                        <th>{ "Mois" }</th>
+                                                        <th class="text-end">{ "Revenus" }</th>
+                                                        <th class="text-end">{ "Dépenses" }</th>
+                                                        <th class="text-end">{ "Solde" }</th>
+                                                    </tr>
+                                                </thead>
+                                                <tbody>
+                                                    {
+                                                        for report.months.iter().rev().map(|month| {
+                                                            let net_class = if month.net < bigdecimal::BigDecimal::from(0) { "text-end text-danger fw-bold" } else { "text-end text-success fw-bold" };
+                                                            html! {
+                                                                <tr>
+                                                                    <td>{ month.month.format("%m/%Y").to_string() }</td>
+                                                                    <td class="text-end">{ format!("{} {}", month.income, report.currency) }</td>
+                                                                    <td class="text-end">{ format!("{} {}", month.expenses, report.currency) }</td>
+                                                                    <td class={net_class}>{ format!("{} {}", month.net, report.currency) }</td>
+                                                                </tr>
+                                                            }
+                                                        })
+                                                    }
+                                                </tbody>
+                                            </table>
+                                        </div>
+                                    }
+                                } else {
+                                    html! { <p class="text-muted p-3 mb-0">{ "Chargement..." }</p> }
+                                }
+                            }
+                        </div>
+                    </div>
+                </div>
+            </div>
                 </div>
             </div>
         </div>
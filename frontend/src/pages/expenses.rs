@@ -4,24 +4,27 @@ use web_sys::HtmlInputElement;
 use yew::TargetCast;
 use serde::Serialize;
 use crate::context::auth::{use_auth, check_auth_response};
-use crate::types::{Expense, ExpenseCategory};
+use crate::types::{Expense, ExpensePage};
 use crate::components::expense_edit::EditExpenseModal;
 use yew_router::prelude::*;
 use crate::{Route};
 use crate::pages::expenses_list::ExpenseListComponent; // Ajout de l'import
 use crate::services::api_service::ApiService;
+use crate::services::category_service::{use_categories, default_category_id};
 
 #[function_component(ExpenseComponent)]
 pub fn expense_component() -> Html {
     let expenses = use_state(|| vec![] as Vec<Expense>);
     let description = use_state(|| "".to_string());
     let amount = use_state(|| "".to_string());
-    let category = use_state(|| ExpenseCategory::Others);
+    let category = use_state(|| None::<i32>);
     let response_message = use_state(|| "".to_string());
     let show_edit_modal = use_state(|| false);
     let edit_expense = use_state(|| None::<Expense>);
     let auth = use_auth();
     let navigator = use_navigator().unwrap();
+    let categories = use_categories(auth.access_token.clone());
+    let selected_category = (*category).or_else(|| default_category_id(&categories));
 
     // Redirect if not logged in
     if auth.token.is_none() {
@@ -47,8 +50,8 @@ pub fn expense_component() -> Html {
                             .await;
                         if let Ok(resp) = res {
                             if check_auth_response(resp.status(), &auth) && resp.status() == 200 {
-                                if let Ok(list) = resp.json::<Vec<Expense>>().await {
-                                    expenses.set(list);
+                                if let Ok(page) = resp.json::<ExpensePage>().await {
+                                    expenses.set(page.items);
                                 }
                             }
                         }
@@ -83,12 +86,12 @@ pub fn expense_component() -> Html {
                     struct NewExpense<'a> {
                         description: &'a str,
                         amount: &'a str,
-                        category: ExpenseCategory,
+                        category_id: Option<i32>,
                     }
                     let new_expense = NewExpense {
                         description: &description,
                         amount: &amount,
-                        category: *category,
+                        category_id: selected_category,
                     };
                     let res = Request::post("http://localhost:3001/expenses")
                         .header("Authorization", &format!("Bearer {}", token))
@@ -105,7 +108,7 @@ pub fn expense_component() -> Html {
                                     // Clear the form
                                     description.set("".to_string());
                                     amount.set("".to_string());
-                                    category.set(ExpenseCategory::Others);
+                                    category.set(None);
                                     // Refresh list
                                     let res = Request::get("http://localhost:3001/expenses")
                                         .header("Authorization", &format!("Bearer {}", token))
@@ -113,8 +116,8 @@ pub fn expense_component() -> Html {
                                         .await;
                                     if let Ok(resp) = res {
                                         if resp.status() == 200 {
-                                            if let Ok(list) = resp.json::<Vec<Expense>>().await {
-                                                expenses.set(list);
+                                            if let Ok(page) = resp.json::<ExpensePage>().await {
+                                                expenses.set(page.items);
                                             }
                                         }
                                     }
@@ -158,8 +161,8 @@ pub fn expense_component() -> Html {
                                         .await;
                                     if let Ok(resp) = res {
                                         if resp.status() == 200 {
-                                            if let Ok(list) = resp.json::<Vec<Expense>>().await {
-                                                expenses.set(list);
+                                            if let Ok(page) = resp.json::<ExpensePage>().await {
+                                                expenses.set(page.items);
                                             }
                                         }
                                     }
@@ -209,8 +212,8 @@ pub fn expense_component() -> Html {
                         .await;
                     if let Ok(resp) = res {
                         if resp.status() == 200 {
-                            if let Ok(list) = resp.json::<Vec<Expense>>().await {
-                                expenses.set(list);
+                            if let Ok(page) = resp.json::<ExpensePage>().await {
+                                expenses.set(page.items);
                             }
                         }
                     }
@@ -264,32 +267,19 @@ pub fn expense_component() -> Html {
                                     <div class="mb-3">
                                         <select
                                             class="form-select"
-                                            value={format!("{:?}", *category)}
                                             onchange={{
                                                 let category = category.clone();
                                                 Callback::from(move |e: Event| {
                                                     let input: HtmlInputElement = e.target_unchecked_into();
-                                                    let value = input.value();
-                                                    let cat = match value.as_str() {
-                                                        "Groceries" => ExpenseCategory::Groceries,
-                                                        "Leisure" => ExpenseCategory::Leisure,
-                                                        "Electronics" => ExpenseCategory::Electronics,
-                                                        "Utilities" => ExpenseCategory::Utilities,
-                                                        "Clothing" => ExpenseCategory::Clothing,
-                                                        "Health" => ExpenseCategory::Health,
-                                                        _ => ExpenseCategory::Others,
-                                                    };
-                                                    category.set(cat);
+                                                    category.set(input.value().parse::<i32>().ok());
                                                 })
                                             }}
                                         >
-                                            <option value="Groceries">{ "Alimentation" }</option>
-                                            <option value="Leisure">{ "Loisirs" }</option>
-                                            <option value="Electronics">{ "Électronique" }</option>
-                                            <option value="Utilities">{ "Factures" }</option>
-                                            <option value="Clothing">{ "Vêtements" }</option>
-                                            <option value="Health">{ "Santé" }</option>
-                                            <option value="Others">{ "Autres" }</option>
+                                            {
+                                                for categories.iter().map(|c| html! {
+                                                    <option value={c.id.to_string()} selected={Some(c.id) == selected_category}>{ c.name.clone() }</option>
+                                                })
+                                            }
                                         </select>
                                     </div>
                                     <div class="d-grid">
@@ -321,6 +311,7 @@ pub fn expense_component() -> Html {
                                     html! {
                                         <ExpenseListComponent
                                             expenses={(*expenses).clone()}
+                                            categories={(*categories).clone()}
                                             on_update_click={on_update_click.clone()}
                                             on_delete={on_delete.clone()}
                                         />
@@ -334,6 +325,7 @@ pub fn expense_component() -> Html {
             
             <EditExpenseModal
                 expense={(*edit_expense).clone()}
+                categories={(*categories).clone()}
                 show={*show_edit_modal}
                 on_close={on_edit_close}
                 on_update={on_edit_update}
@@ -1,9 +1,11 @@
 use yew::prelude::*;
-use crate::types::{Expense, ExpenseCategory};
+use crate::types::{Category, Expense};
+use crate::services::category_service::find_category;
 
 #[derive(Properties, PartialEq)]
 pub struct ExpenseListProps {
     pub expenses: Vec<Expense>,
+    pub categories: Vec<Category>,
     pub on_update_click: Callback<Expense>,
     pub on_delete: Callback<i32>,
 }
@@ -22,34 +24,32 @@ pub fn expense_list_component(props: &ExpenseListProps) -> Html {
                 {
                     for props.expenses.iter().map(|expense| {
                         let exp = expense.clone();
-                        let category_badge_class = match exp.category {
-                            ExpenseCategory::Groceries => "bg-success",
-                            ExpenseCategory::Leisure => "bg-primary",
-                            ExpenseCategory::Electronics => "bg-info",
-                            ExpenseCategory::Utilities => "bg-warning",
-                            ExpenseCategory::Clothing => "bg-secondary",
-                            ExpenseCategory::Health => "bg-danger",
-                            ExpenseCategory::Others => "bg-dark",
-                        };
-                        let category_text = match exp.category {
-                            ExpenseCategory::Groceries => "Alimentation",
-                            ExpenseCategory::Leisure => "Loisirs",
-                            ExpenseCategory::Electronics => "électronique",
-                            ExpenseCategory::Utilities => "Factures",
-                            ExpenseCategory::Clothing => "Vêtements",
-                            ExpenseCategory::Health => "Santé",
-                            ExpenseCategory::Others => "Autres",
-                        };
+                        let category = find_category(&props.categories, exp.category_id);
+                        let category_color = category.map(|c| c.color.clone()).unwrap_or_else(|| "#212529".to_string());
+                        let category_text = category.map(|c| c.name.clone()).unwrap_or_else(|| "Autres".to_string());
                         html! {
                             <div class="list-group-item">
                                 <div class="row align-items-center">
                                     <div class="col-12 col-sm-8 col-md-9">
                                         <div class="d-flex flex-column flex-sm-row align-items-start align-items-sm-center">
                                             <div class="flex-grow-1 mb-2 mb-sm-0">
-                                                <h6 class="mb-1">{ exp.description.as_deref().unwrap_or("Sans description") }</h6>
+                                                <h6 class="mb-1">
+                                                    {
+                                                        match &exp.highlight {
+                                                            // Escaped by the server, only <mark> is markup
+                                                            Some(highlight) => Html::from_html_unchecked(AttrValue::from(highlight.clone())),
+                                                            None => html! { exp.description.as_deref().unwrap_or("Sans description") },
+                                                        }
+                                                    }
+                                                </h6>
                                                 <div class="d-flex flex-column flex-sm-row align-items-start align-items-sm-center gap-2">
-                                                    <span class="fw-bold text-success">{ format!("{} €", exp.amount) }</span>
-                                                    <span class={format!("badge {} badge-sm", category_badge_class)}>{ category_text }</span>
+                                                    <span class="fw-bold text-success">{ format!("{} {}", exp.amount, exp.currency) }</span>
+                                                    <span class="badge badge-sm" style={format!("background-color: {}", category_color)}>{ category_text }</span>
+                                                    {
+                                                        for exp.tags.iter().map(|tag| html! {
+                                                            <span class="badge badge-sm bg-light text-dark border">{ format!("#{}", tag) }</span>
+                                                        })
+                                                    }
                                                 </div>
                                             </div>
                                         </div>
@@ -0,0 +1,252 @@
+use yew::prelude::*;
+use yew_router::prelude::*;
+use web_sys::HtmlInputElement;
+use yew::TargetCast;
+use chrono::NaiveDate;
+use crate::context::auth::use_auth;
+use crate::types::{Income, IncomeForm};
+use crate::components::layout::Route;
+use crate::services::income_service::IncomeService;
+
+#[function_component(ManageIncomes)]
+pub fn manage_incomes() -> Html {
+    let auth = use_auth();
+    let navigator = use_navigator().unwrap();
+    let incomes = use_state(Vec::<Income>::new);
+    let source = use_state(|| "".to_string());
+    let amount = use_state(|| "".to_string());
+    let income_date = use_state(|| "".to_string());
+    let recurring = use_state(|| false);
+    let response_message = use_state(|| "".to_string());
+
+    // Redirect if not logged in
+    if auth.token.is_none() {
+        navigator.push(&Route::Login);
+    }
+
+    // Fetch incomes
+    {
+        let incomes = incomes.clone();
+        use_effect_with(auth.access_token.clone(), move |access_token| {
+            if let Some(token) = access_token.clone() {
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Ok(list) = IncomeService::fetch_all(&token).await {
+                        incomes.set(list);
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let on_create = {
+        let incomes = incomes.clone();
+        let source = source.clone();
+        let amount = amount.clone();
+        let income_date = income_date.clone();
+        let recurring = recurring.clone();
+        let response_message = response_message.clone();
+        let auth = auth.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let incomes = incomes.clone();
+            let source = source.clone();
+            let amount = amount.clone();
+            let response_message = response_message.clone();
+            let form = IncomeForm {
+                source: (*source).trim().to_string(),
+                amount: (*amount).clone(),
+                // Empty means today
+                income_date: NaiveDate::parse_from_str(&income_date, "%Y-%m-%d").ok(),
+                recurring: *recurring,
+            };
+
+            if let Some(token) = auth.access_token.clone() {
+                wasm_bindgen_futures::spawn_local(async move {
+                    match IncomeService::create(&token, &form).await {
+                        Ok(_) => {
+                            response_message.set("Revenu ajouté".to_string());
+                            source.set("".to_string());
+                            amount.set("".to_string());
+                            if let Ok(list) = IncomeService::fetch_all(&token).await {
+                                incomes.set(list);
+                            }
+                        }
+                        Err(err) => response_message.set(err),
+                    }
+                });
+            }
+        })
+    };
+
+    let on_delete = {
+        let incomes = incomes.clone();
+        let response_message = response_message.clone();
+        let auth = auth.clone();
+        Callback::from(move |id: i32| {
+            let incomes = incomes.clone();
+            let response_message = response_message.clone();
+            if let Some(token) = auth.access_token.clone() {
+                wasm_bindgen_futures::spawn_local(async move {
+                    match IncomeService::delete(&token, id).await {
+                        Ok(()) => {
+                            response_message.set("Revenu supprimé".to_string());
+                            if let Ok(list) = IncomeService::fetch_all(&token).await {
+                                incomes.set(list);
+                            }
+                        }
+                        Err(err) => response_message.set(err),
+                    }
+                });
+            }
+        })
+    };
+
+    html! {
+        <div class="container-fluid">
+            <div class="row justify-content-center">
+                <div class="col-12 col-lg-10 col-xl-8">
+                    <h2 class="mb-4">{ "Revenus" }</h2>
+                    <div class="row g-4">
+                        <div class="col-12 col-md-5">
+                            <div class="card shadow-sm">
+                                <div class="card-header bg-success text-white">
+                                    <h5 class="mb-0">{ "Nouveau revenu" }</h5>
+                                </div>
+                                <div class="card-body">
+                                    <form onsubmit={on_create}>
+                                        <div class="mb-3">
+                                            <label class="form-label fw-semibold">{ "Source" }</label>
+                                            <input
+                                                type="text"
+                                                class="form-control"
+                                                placeholder="Salaire, loyer perçu..."
+                                                maxlength="100"
+                                                required=true
+                                                value={(*source).clone()}
+                                                oninput={{
+                                                    let source = source.clone();
+                                                    Callback::from(move |e: InputEvent| {
+                                                        let input: HtmlInputElement = e.target_unchecked_into();
+                                                        source.set(input.value());
+                                                    })
+                                                }}
+                                            />
+                                        </div>
+                                        <div class="mb-3">
+                                            <label class="form-label fw-semibold">{ "Montant" }</label>
+                                            <input
+                                                type="number"
+                                                step="0.01"
+                                                class="form-control"
+                                                placeholder="0.00"
+                                                required=true
+                                                value={(*amount).clone()}
+                                                oninput={{
+                                                    let amount = amount.clone();
+                                                    Callback::from(move |e: InputEvent| {
+                                                        let input: HtmlInputElement = e.target_unchecked_into();
+                                                        amount.set(input.value());
+                                                    })
+                                                }}
+                                            />
+                                        </div>
+                                        <div class="mb-3">
+                                            <label class="form-label fw-semibold">{ "Date" }</label>
+                                            <input
+                                                type="date"
+                                                class="form-control"
+                                                value={(*income_date).clone()}
+                                                oninput={{
+                                                    let income_date = income_date.clone();
+                                                    Callback::from(move |e: InputEvent| {
+                                                        let input: HtmlInputElement = e.target_unchecked_into();
+                                                        income_date.set(input.value());
+                                                    })
+                                                }}
+                                            />
+                                        </div>
+                                        <div class="form-check mb-3">
+                                            <input
+                                                type="checkbox"
+                                                class="form-check-input"
+                                                id="income-recurring"
+                                                checked={*recurring}
+                                                onchange={{
+                                                    let recurring = recurring.clone();
+                                                    Callback::from(move |e: Event| {
+                                                        let input: HtmlInputElement = e.target_unchecked_into();
+                                                        recurring.set(input.checked());
+                                                    })
+                                                }}
+                                            />
+                                            <label class="form-check-label" for="income-recurring">{ "Revenu régulier" }</label>
+                                        </div>
+                                        <div class="d-grid">
+                                            <button type="submit" class="btn btn-success">{ "Ajouter" }</button>
+                                        </div>
+                                    </form>
+                                    {
+                                        if !(*response_message).is_empty() {
+                                            html! {
+                                                <div class="alert alert-info mt-3" role="alert">
+                                                    { (*response_message).clone() }
+                                                </div>
+                                            }
+                                        } else {
+                                            html! {}
+                                        }
+                                    }
+                                </div>
+                            </div>
+                        </div>
+                        <div class="col-12 col-md-7">
+                            <div class="card shadow-sm">
+                                <div class="card-header bg-success text-white">
+                                    <h5 class="mb-0">{ format!("Mes revenus ({})", incomes.len()) }</h5>
+                                </div>
+                                <ul class="list-group list-group-flush">
+                                    {
+                                        for incomes.iter().map(|income| {
+                                            let id = income.id;
+                                            let on_delete = on_delete.clone();
+                                            html! {
+                                                <li class="list-group-item d-flex justify-content-between align-items-center">
+                                                    <div>
+                                                        <h6 class="mb-1">
+                                                            { income.source.clone() }
+                                                            {
+                                                                if income.recurring {
+                                                                    html! { <span class="badge bg-secondary ms-2">{ "régulier" }</span> }
+                                                                } else {
+                                                                    html! {}
+                                                                }
+                                                            }
+                                                        </h6>
+                                                        <small class="text-muted">{ income.income_date.format("%d/%m/%Y").to_string() }</small>
+                                                    </div>
+                                                    <div class="d-flex align-items-center gap-2">
+                                                        <span class="fw-bold text-success">{ format!("{} {}", income.amount, income.currency) }</span>
+                                                        <button
+                                                            type="button"
+                                                            class="btn btn-outline-danger btn-sm"
+                                                            title="Supprimer"
+                                                            onclick={Callback::from(move |_| on_delete.emit(id))}
+                                                        >
+                                                            <i class="bi bi-trash"></i>
+                                                        </button>
+                                                    </div>
+                                                </li>
+                                            }
+                                        })
+                                    }
+                                </ul>
+                            </div>
+                        </div>
+                    </div>
+                </div>
+            </div>
+        </div>
+    }
+}
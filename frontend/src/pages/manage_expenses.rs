@@ -2,12 +2,13 @@ use yew::prelude::*;
 use yew_router::prelude::*;
 use gloo_net::http::Request;
 use crate::context::auth::use_auth;
-use crate::types::{Expense, ExpenseCategory};
+use crate::types::{Expense, ExpensePage};
 use crate::pages::expenses_list::ExpenseListComponent;
 use crate::components::expense_edit::EditExpenseModal;
 use crate::components::layout::Route;
 use web_sys::{HtmlInputElement, console};
 use crate::services::api_service::ApiService;
+use crate::services::category_service::use_categories;
 
 #[function_component(ManageExpenses)]
 pub fn manage_expenses() -> Html {
@@ -16,35 +17,37 @@ pub fn manage_expenses() -> Html {
     let expenses = use_state(|| vec![] as Vec<Expense>);
     let show_edit_modal = use_state(|| false);
     let edit_expense = use_state(|| None::<Expense>);
-    let selected_category = use_state(|| None::<ExpenseCategory>);
+    let categories = use_categories(auth.access_token.clone());
+    let selected_category = use_state(|| None::<i32>);
     let confirm_delete_id = use_state(|| None::<i32>);
+    let next_cursor = use_state(|| None::<String>);
+    let search_input = use_state(|| "".to_string());
+    // Search applied to the list, set when the search form is submitted
+    let search = use_state(|| None::<String>);
 
     // Redirect if not logged in
     if auth.token.is_none() {
         navigator.push(&Route::Login);
     }
 
-    // Fetch expenses
+    // Fetch the first page whenever the token, the category filter or the search changes
     {
         let expenses = expenses.clone();
+        let next_cursor = next_cursor.clone();
         let access_token = auth.access_token.clone();
+        let category = *selected_category;
         use_effect_with(
-            access_token.clone(),
-            move |access_token| {
+            (access_token.clone(), category, (*search).clone()),
+            move |(access_token, category, search)| {
                 if let Some(token) = access_token {
                     let expenses = expenses.clone();
+                    let next_cursor = next_cursor.clone();
                     let token = token.clone();
+                    let path = expenses_path(*category, search.as_deref(), None);
                     wasm_bindgen_futures::spawn_local(async move {
-                        let res = ApiService::get("/expenses")
-                            .header("Authorization", &format!("Bearer {}", token))
-                            .send()
-                            .await;
-                        if let Ok(resp) = res {
-                            if resp.status() == 200 {
-                                if let Ok(list) = resp.json::<Vec<Expense>>().await {
-                                    expenses.set(list);
-                                }
-                            }
+                        if let Some(page) = fetch_page(&token, &path).await {
+                            expenses.set(page.items);
+                            next_cursor.set(page.next_cursor);
                         }
                     });
                 }
@@ -53,6 +56,30 @@ pub fn manage_expenses() -> Html {
         );
     }
 
+    // Append the next page
+    let on_load_more = {
+        let expenses = expenses.clone();
+        let next_cursor = next_cursor.clone();
+        let selected_category = selected_category.clone();
+        let search = search.clone();
+        let auth = auth.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let (Some(token), Some(cursor)) = (auth.access_token.clone(), (*next_cursor).clone()) {
+                let expenses = expenses.clone();
+                let next_cursor = next_cursor.clone();
+                let path = expenses_path(*selected_category, search.as_deref(), Some(&cursor));
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Some(page) = fetch_page(&token, &path).await {
+                        let mut list = (*expenses).clone();
+                        list.extend(page.items);
+                        expenses.set(list);
+                        next_cursor.set(page.next_cursor);
+                    }
+                });
+            }
+        })
+    };
+
     // Delete expense (trigger confirmation modal)
     let on_delete = {
         let confirm_delete_id = confirm_delete_id.clone();
@@ -64,33 +91,35 @@ pub fn manage_expenses() -> Html {
     // Confirm delete handlers
     let on_confirm_delete = {
         let expenses = expenses.clone();
+        let next_cursor = next_cursor.clone();
+        let selected_category = selected_category.clone();
+        let search = search.clone();
         let auth = auth.clone();
         let confirm_delete_id = confirm_delete_id.clone();
         Callback::from(move |_| {
             if let Some(id) = *confirm_delete_id {
                 let expenses = expenses.clone();
+                let next_cursor = next_cursor.clone();
+                let path = expenses_path(*selected_category, search.as_deref(), None);
                 let auth = auth.clone();
                 let confirm_delete_id = confirm_delete_id.clone();
+                let version = expenses.iter().find(|e| e.id == id).map(|e| e.version);
                 wasm_bindgen_futures::spawn_local(async move {
                     if let Some(token) = &auth.access_token {
                         let url = format!("http://localhost:3001/expenses/{}", id);
-                        let res = Request::delete(&url)
-                            .header("Authorization", &format!("Bearer {}", token))
-                            .send()
-                            .await;
+                        let mut request = Request::delete(&url)
+                            .header("Authorization", &format!("Bearer {}", token));
+                        if let Some(version) = version {
+                            request = request.header("If-Match", &format!("\"{}\"", version));
+                        }
+                        let res = request.send().await;
                         if let Ok(resp) = res {
-                            if resp.status() == 204 {
+                            // 412: changed since it was listed, show its current values instead
+                            if resp.status() == 204 || resp.status() == 412 {
                                 // Refresh list
-                                let res = Request::get("http://localhost:3001/expenses")
-                                    .header("Authorization", &format!("Bearer {}", token))
-                                    .send()
-                                    .await;
-                                if let Ok(resp) = res {
-                                    if resp.status() == 200 {
-                                        if let Ok(list) = resp.json::<Vec<Expense>>().await {
-                                            expenses.set(list);
-                                        }
-                                    }
+                                if let Some(page) = fetch_page(token, &path).await {
+                                    expenses.set(page.items);
+                                    next_cursor.set(page.next_cursor);
                                 }
                             }
                         }
@@ -127,59 +156,44 @@ pub fn manage_expenses() -> Html {
 
     let on_edit_update = {
         let expenses = expenses.clone();
+        let next_cursor = next_cursor.clone();
+        let selected_category = selected_category.clone();
+        let search = search.clone();
         let auth = auth.clone();
         Callback::from(move |_| {
             let expenses = expenses.clone();
+            let next_cursor = next_cursor.clone();
+            let path = expenses_path(*selected_category, search.as_deref(), None);
             let auth = auth.clone();
             wasm_bindgen_futures::spawn_local(async move {
-                if let Some(token) = &auth.access_token {
-                    let res = Request::get("http://localhost:3001/expenses")
-                        .header("Authorization", &format!("Bearer {}", token))
-                        .send()
-                        .await;
-                    if let Ok(resp) = res {
-                        if resp.status() == 200 {
-                            if let Ok(list) = resp.json::<Vec<Expense>>().await {
-                                expenses.set(list);
-                            }
-                        }
-                    }
+                if let Some(token) = &auth.access_token
+                    && let Some(page) = fetch_page(token, &path).await
+                {
+                    expenses.set(page.items);
+                    next_cursor.set(page.next_cursor);
                 }
             });
         })
     };
 
-    // Filter expenses by category
-    let filtered_expenses = {
-        let expenses = (*expenses).clone();
-        let selected_category = (*selected_category).clone();
-        
-        if let Some(category) = selected_category {
-            expenses.into_iter().filter(|e| e.category == category).collect::<Vec<_>>()
-        } else {
-            expenses
-        }
-    };
+    let filtered_expenses = (*expenses).clone();
 
     let on_category_filter = {
         let selected_category = selected_category.clone();
         Callback::from(move |e: Event| {
             let input: web_sys::HtmlSelectElement = e.target_unchecked_into();
-            let value = input.value();
-            let category = if value == "all" {
-                None
-            } else {
-                Some(match value.as_str() {
-                    "Groceries" => ExpenseCategory::Groceries,
-                    "Leisure" => ExpenseCategory::Leisure,
-                    "Electronics" => ExpenseCategory::Electronics,
-                    "Utilities" => ExpenseCategory::Utilities,
-                    "Clothing" => ExpenseCategory::Clothing,
-                    "Health" => ExpenseCategory::Health,
-                    _ => ExpenseCategory::Others,
-                })
-            };
-            selected_category.set(category);
+            // "all" does not parse and clears the filter
+            selected_category.set(input.value().parse::<i32>().ok());
+        })
+    };
+
+    let on_search = {
+        let search_input = search_input.clone();
+        let search = search.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let text = search_input.trim().to_string();
+            search.set(if text.is_empty() { None } else { Some(text) });
         })
     };
 
@@ -199,7 +213,7 @@ pub fn manage_expenses() -> Html {
                             <div class="card-header bg-primary text-white">
                                 <div class="row align-items-center">
                                     <div class="col-12 col-md-6">
-                                        <h5 class="mb-2 mb-md-0">{ format!("Mes dépenses ({} au total)", filtered_expenses.len()) }</h5>
+                                        <h5 class="mb-2 mb-md-0">{ format!("Mes dépenses ({} affichées)", filtered_expenses.len()) }</h5>
                                     </div>
                                     <div class="col-12 col-md-6">
                                         <div class="d-flex flex-column flex-sm-row align-items-sm-center">
@@ -207,35 +221,60 @@ pub fn manage_expenses() -> Html {
                                             <select 
                                                 class="form-select form-select-sm" 
                                                 onchange={on_category_filter}
-                                                value={
-                                                    if let Some(cat) = *selected_category {
-                                                        format!("{:?}", cat)
-                                                    } else {
-                                                        "all".to_string()
-                                                    }
-                                                }
                                             >
-                                                <option value="all">{ "Toutes" }</option>
-                                                <option value="Groceries">{ "Alimentation" }</option>
-                                                <option value="Leisure">{ "Loisirs" }</option>
-                                                <option value="Electronics">{ "Électronique" }</option>
-                                                <option value="Utilities">{ "Factures" }</option>
-                                                <option value="Clothing">{ "Vêtements" }</option>
-                                                <option value="Health">{ "Santé" }</option>
-                                                <option value="Others">{ "Autres" }</option>
+                                                <option value="all" selected={selected_category.is_none()}>{ "Toutes" }</option>
+                                                {
+                                                    for categories.iter().map(|c| html! {
+                                                        <option value={c.id.to_string()} selected={Some(c.id) == *selected_category}>{ c.name.clone() }</option>
+                                                    })
+                                                }
                                             </select>
                                         </div>
                                     </div>
                                 </div>
                             </div>
                             <div class="card-body p-2 p-md-3">
+                                <form class="mb-3" onsubmit={on_search}>
+                                    <div class="input-group">
+                                        <input
+                                            type="search"
+                                            class="form-control"
+                                            placeholder="Rechercher dans les descriptions"
+                                            value={(*search_input).clone()}
+                                            oninput={{
+                                                let search_input = search_input.clone();
+                                                Callback::from(move |e: InputEvent| {
+                                                    let input: HtmlInputElement = e.target_unchecked_into();
+                                                    search_input.set(input.value());
+                                                })
+                                            }}
+                                        />
+                                        <button type="submit" class="btn btn-outline-primary">
+                                            <i class="bi bi-search"></i>
+                                        </button>
+                                    </div>
+                                </form>
                                 <div class="table-responsive">
                                     <ExpenseListComponent
                                         expenses={filtered_expenses}
+                                        categories={(*categories).clone()}
                                         on_update_click={on_update_click.clone()}
                                         on_delete={on_delete.clone()}
                                     />
                                 </div>
+                                {
+                                    if next_cursor.is_some() {
+                                        html! {
+                                            <div class="text-center mt-3">
+                                                <button type="button" class="btn btn-outline-primary" onclick={on_load_more}>
+                                                    { "Charger plus" }
+                                                </button>
+                                            </div>
+                                        }
+                                    } else {
+                                        html! {}
+                                    }
+                                }
                             </div>
                         </div>
                     </div>
@@ -244,6 +283,7 @@ pub fn manage_expenses() -> Html {
             
             <EditExpenseModal
                 expense={(*edit_expense).clone()}
+                categories={(*categories).clone()}
                 show={*show_edit_modal}
                 on_close={on_edit_close}
                 on_update={on_edit_update}
@@ -262,7 +302,7 @@ pub fn manage_expenses() -> Html {
                                     </div>
                                     <div class="modal-body">
                                         <p>{ "Voulez-vous vraiment supprimer cette dépense ?" }</p>
-                                        <p class="text-muted">{ "Cette action est irréversible." }</p>
+                                        <p class="text-muted">{ "Elle sera placée dans la corbeille et pourra être restaurée." }</p>
                                     </div>
                                     <div class="modal-footer">
                                         <button type="button" class="btn btn-secondary" onclick={on_cancel_delete}>
@@ -283,3 +323,40 @@ pub fn manage_expenses() -> Html {
         </>
     }
 }
+
+fn expenses_path(category_id: Option<i32>, search: Option<&str>, cursor: Option<&str>) -> String {
+    let mut path = "/expenses?limit=50".to_string();
+    if let Some(category_id) = category_id {
+        path.push_str(&format!("&category_id={}", category_id));
+    }
+    if let Some(search) = search {
+        path.push_str(&format!("&q={}", encode_query_value(search)));
+    }
+    if let Some(cursor) = cursor {
+        path.push_str(&format!("&cursor={}", cursor));
+    }
+    path
+}
+
+async fn fetch_page(token: &str, path: &str) -> Option<ExpensePage> {
+    let resp = ApiService::get(path)
+        .header("Authorization", &format!("Bearer {}", token))
+        .send()
+        .await
+        .ok()?;
+    if resp.status() != 200 {
+        return None;
+    }
+    resp.json::<ExpensePage>().await.ok()
+}
+
+/// Percent-encodes a query string value.
+fn encode_query_value(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
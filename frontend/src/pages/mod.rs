@@ -4,6 +4,8 @@ pub mod home;
 pub mod expenses_list;
 pub mod add_expense;
 pub mod manage_expenses;
+pub mod categories;
+pub mod incomes;
 pub mod login;
 pub mod register;
 pub mod dashboard;
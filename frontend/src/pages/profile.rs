@@ -1,6 +1,6 @@
 use crate::services::api_service::ApiService;
 use gloo_net::http::Request;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use web_sys::HtmlInputElement;
 use yew::prelude::*;
 use yew::TargetCast;
@@ -18,6 +18,112 @@ struct ChangePasswordPayload {
     new_password: String,
 }
 
+#[derive(Serialize, Deserialize)]
+struct DigestSubscription {
+    frequency: String,
+}
+
+/// Opt-in spending digest email: "Weekly", "Monthly" or off.
+#[function_component(DigestSettings)]
+fn digest_settings() -> Html {
+    let auth = use_auth();
+    let frequency = use_state(|| "".to_string());
+    let message = use_state(|| "".to_string());
+
+    {
+        let frequency = frequency.clone();
+        use_effect_with(auth.access_token.clone(), move |access_token| {
+            if let Some(token) = access_token.clone() {
+                wasm_bindgen_futures::spawn_local(async move {
+                    let res = ApiService::get("/digest")
+                        .header("Authorization", &format!("Bearer {}", token))
+                        .send()
+                        .await;
+                    if let Ok(resp) = res
+                        && let Ok(subscription) = resp.json::<DigestSubscription>().await
+                    {
+                        frequency.set(subscription.frequency);
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let on_change = {
+        let frequency = frequency.clone();
+        let message = message.clone();
+        let auth = auth.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlInputElement = e.target_unchecked_into();
+            let value = select.value();
+            let frequency = frequency.clone();
+            let message = message.clone();
+            let auth = auth.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Some(token) = &auth.access_token {
+                    let authorization = format!("Bearer {}", token);
+                    let res = if value.is_empty() {
+                        ApiService::delete("/digest")
+                            .header("Authorization", &authorization)
+                            .send()
+                            .await
+                    } else {
+                        ApiService::put("/digest")
+                            .header("Authorization", &authorization)
+                            .header("Content-Type", "application/json")
+                            .json(&DigestSubscription { frequency: value.clone() })
+                            .unwrap()
+                            .send()
+                            .await
+                    };
+                    match res {
+                        Ok(resp) if resp.ok() || resp.status() == 404 => {
+                            message.set(if value.is_empty() {
+                                "Résumé désactivé".to_string()
+                            } else {
+                                "Résumé activé: le premier arrivera à la fin de la période en cours".to_string()
+                            });
+                            frequency.set(value);
+                        }
+                        Ok(_) => message.set("Erreur lors de la mise à jour".to_string()),
+                        Err(_) => message.set("Erreur réseau".to_string()),
+                    }
+                }
+            });
+        })
+    };
+
+    html! {
+        <div class="card mb-4 shadow-sm">
+            <div class="card-header bg-info text-white">
+                <h6 class="mb-0">{ "Résumé des dépenses par email" }</h6>
+            </div>
+            <div class="card-body">
+                <p class="card-text text-muted small">
+                    { "Totaux par catégorie, plus grosses dépenses, évolution et budgets de la période écoulée." }
+                </p>
+                <select class="form-select" onchange={on_change}>
+                    <option value="" selected={frequency.is_empty()}>{ "Désactivé" }</option>
+                    <option value="Weekly" selected={*frequency == "Weekly"}>{ "Chaque semaine" }</option>
+                    <option value="Monthly" selected={*frequency == "Monthly"}>{ "Chaque mois" }</option>
+                </select>
+                {
+                    if !(*message).is_empty() {
+                        html! {
+                            <div class="alert alert-info mt-3" role="alert">
+                                { (*message).clone() }
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+            </div>
+        </div>
+    }
+}
+
 #[function_component(Profile)]
 pub fn profile() -> Html {
     let auth = use_auth();
@@ -301,6 +407,10 @@ pub fn profile() -> Html {
                                         }
                                     }
 
+                                    if !*show_password_form {
+                                        <DigestSettings />
+                                    }
+
                                     <div class="card shadow-sm">
                                         <div class="card-header bg-warning text-dark d-flex justify-content-between align-items-center">
                                             <h6 class="mb-0">{ "Sécurité" }</h6>
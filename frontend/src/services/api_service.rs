@@ -28,6 +28,10 @@ impl ApiService {
         Request::put(&Self::url(path))
     }
 
+    pub fn patch(path: &str) -> RequestBuilder {
+        Request::patch(&Self::url(path))
+    }
+
     pub fn delete(path: &str) -> RequestBuilder {
         Request::delete(&Self::url(path))
     }
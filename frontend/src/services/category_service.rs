@@ -0,0 +1,91 @@
+use yew::prelude::*;
+use crate::types::{Category, CategoryForm};
+use crate::services::api_service::ApiService;
+
+pub struct CategoryService;
+
+impl CategoryService {
+    pub async fn fetch_all(token: &str) -> Result<Vec<Category>, String> {
+        let response = ApiService::get("/categories")
+            .header("Authorization", &format!("Bearer {}", token))
+            .send()
+            .await
+            .map_err(|_| "Network error")?;
+
+        if response.status() == 200 {
+            response
+                .json::<Vec<Category>>()
+                .await
+                .map_err(|_| "Failed to parse response".to_string())
+        } else {
+            Err("Server error".to_string())
+        }
+    }
+
+    pub async fn create(token: &str, form: &CategoryForm) -> Result<Category, String> {
+        let response = ApiService::post("/categories")
+            .header("Authorization", &format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .json(form)
+            .map_err(|_| "Failed to serialize request")?
+            .send()
+            .await
+            .map_err(|_| "Network error")?;
+
+        match response.status() {
+            201 => response
+                .json::<Category>()
+                .await
+                .map_err(|_| "Failed to parse response".to_string()),
+            409 => Err("Cette catégorie existe déjà".to_string()),
+            _ => Err("Server error".to_string()),
+        }
+    }
+
+    pub async fn delete(token: &str, id: i32) -> Result<(), String> {
+        let response = ApiService::delete(&format!("/categories/{}", id))
+            .header("Authorization", &format!("Bearer {}", token))
+            .send()
+            .await
+            .map_err(|_| "Network error")?;
+
+        match response.status() {
+            204 => Ok(()),
+            409 => Err("La catégorie par défaut ne peut pas être supprimée".to_string()),
+            _ => Err("Server error".to_string()),
+        }
+    }
+}
+
+/// Loads the user's categories whenever the access token changes.
+#[hook]
+pub fn use_categories(access_token: Option<String>) -> UseStateHandle<Vec<Category>> {
+    let categories = use_state(Vec::<Category>::new);
+    {
+        let categories = categories.clone();
+        use_effect_with(access_token, move |access_token| {
+            if let Some(token) = access_token.clone() {
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Ok(list) = CategoryService::fetch_all(&token).await {
+                        categories.set(list);
+                    }
+                });
+            }
+            || ()
+        });
+    }
+    categories
+}
+
+pub fn find_category(categories: &[Category], id: i32) -> Option<&Category> {
+    categories.iter().find(|c| c.id == id)
+}
+
+/// Category preselected in forms: the fallback one, else the first.
+pub fn default_category_id(categories: &[Category]) -> Option<i32> {
+    categories
+        .iter()
+        .find(|c| c.is_default)
+        .or_else(|| categories.first())
+        .map(|c| c.id)
+}
@@ -0,0 +1,44 @@
+use yew::prelude::*;
+use crate::types::Currency;
+use crate::services::api_service::ApiService;
+
+pub struct CurrencyService;
+
+impl CurrencyService {
+    pub async fn fetch_all(token: &str) -> Result<Vec<Currency>, String> {
+        let response = ApiService::get("/currencies")
+            .header("Authorization", &format!("Bearer {}", token))
+            .send()
+            .await
+            .map_err(|_| "Network error")?;
+
+        if response.status() == 200 {
+            response
+                .json::<Vec<Currency>>()
+                .await
+                .map_err(|_| "Failed to parse response".to_string())
+        } else {
+            Err("Server error".to_string())
+        }
+    }
+}
+
+/// Loads the currencies with known exchange rates whenever the access token changes.
+#[hook]
+pub fn use_currencies(access_token: Option<String>) -> UseStateHandle<Vec<Currency>> {
+    let currencies = use_state(Vec::<Currency>::new);
+    {
+        let currencies = currencies.clone();
+        use_effect_with(access_token, move |access_token| {
+            if let Some(token) = access_token.clone() {
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Ok(list) = CurrencyService::fetch_all(&token).await {
+                        currencies.set(list);
+                    }
+                });
+            }
+            || ()
+        });
+    }
+    currencies
+}
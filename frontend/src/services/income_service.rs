@@ -0,0 +1,74 @@
+use crate::types::{CashFlowReport, Income, IncomeForm};
+use crate::services::api_service::ApiService;
+
+pub struct IncomeService;
+
+impl IncomeService {
+    pub async fn fetch_all(token: &str) -> Result<Vec<Income>, String> {
+        let response = ApiService::get("/incomes")
+            .header("Authorization", &format!("Bearer {}", token))
+            .send()
+            .await
+            .map_err(|_| "Network error")?;
+
+        if response.status() == 200 {
+            response
+                .json::<Vec<Income>>()
+                .await
+                .map_err(|_| "Failed to parse response".to_string())
+        } else {
+            Err("Server error".to_string())
+        }
+    }
+
+    pub async fn create(token: &str, form: &IncomeForm) -> Result<Income, String> {
+        let response = ApiService::post("/incomes")
+            .header("Authorization", &format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .json(form)
+            .map_err(|_| "Failed to serialize request")?
+            .send()
+            .await
+            .map_err(|_| "Network error")?;
+
+        match response.status() {
+            201 => response
+                .json::<Income>()
+                .await
+                .map_err(|_| "Failed to parse response".to_string()),
+            400 => Err("Revenu invalide".to_string()),
+            _ => Err("Server error".to_string()),
+        }
+    }
+
+    pub async fn delete(token: &str, id: i32) -> Result<(), String> {
+        let response = ApiService::delete(&format!("/incomes/{}", id))
+            .header("Authorization", &format!("Bearer {}", token))
+            .send()
+            .await
+            .map_err(|_| "Network error")?;
+
+        match response.status() {
+            204 => Ok(()),
+            _ => Err("Server error".to_string()),
+        }
+    }
+
+    /// Monthly income, expenses and net over the last twelve months.
+    pub async fn cash_flow(token: &str) -> Result<CashFlowReport, String> {
+        let response = ApiService::get("/cash-flow")
+            .header("Authorization", &format!("Bearer {}", token))
+            .send()
+            .await
+            .map_err(|_| "Network error")?;
+
+        if response.status() == 200 {
+            response
+                .json::<CashFlowReport>()
+                .await
+                .map_err(|_| "Failed to parse response".to_string())
+        } else {
+            Err("Server error".to_string())
+        }
+    }
+}
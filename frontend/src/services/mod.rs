@@ -1,2 +1,7 @@
 pub mod api_service;
+pub mod category_service;
+pub mod currency_service;
+pub mod income_service;
+pub mod summary_service;
+pub mod tag_service;
 pub mod url_service;
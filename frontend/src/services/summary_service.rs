@@ -0,0 +1,41 @@
+use serde::de::DeserializeOwned;
+use crate::types::{CategoryTotal, ExpenseSummary, PeriodTotal, SpendingForecast, SummaryBreakdown};
+use crate::services::api_service::ApiService;
+
+/// Expense totals computed by the server, over all expenses.
+pub struct SummaryService;
+
+impl SummaryService {
+    pub async fn totals(token: &str) -> Result<ExpenseSummary, String> {
+        Self::fetch(token, "/expenses/summary").await
+    }
+
+    pub async fn by_category(token: &str) -> Result<SummaryBreakdown<CategoryTotal>, String> {
+        Self::fetch(token, "/expenses/summary/categories").await
+    }
+
+    pub async fn by_month(token: &str) -> Result<SummaryBreakdown<PeriodTotal>, String> {
+        Self::fetch(token, "/expenses/summary/periods?period=month").await
+    }
+
+    pub async fn forecast(token: &str) -> Result<SpendingForecast, String> {
+        Self::fetch(token, "/expenses/forecast").await
+    }
+
+    async fn fetch<T: DeserializeOwned>(token: &str, path: &str) -> Result<T, String> {
+        let response = ApiService::get(path)
+            .header("Authorization", &format!("Bearer {}", token))
+            .send()
+            .await
+            .map_err(|_| "Network error")?;
+
+        if response.status() == 200 {
+            response
+                .json::<T>()
+                .await
+                .map_err(|_| "Failed to parse response".to_string())
+        } else {
+            Err("Server error".to_string())
+        }
+    }
+}
@@ -0,0 +1,13 @@
+/// Splits the comma-separated tag input of the expense forms.
+pub fn parse_tags(input: &str) -> Vec<String> {
+    input
+        .split(',')
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+/// Inverse of `parse_tags`, to fill the input from an expense.
+pub fn format_tags(tags: &[String]) -> String {
+    tags.join(", ")
+}
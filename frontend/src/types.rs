@@ -1,5 +1,5 @@
 use bigdecimal::BigDecimal;
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 use std::cmp::PartialEq;
 
@@ -27,36 +27,181 @@ pub struct RegisterResponse {
     pub message: String,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum ExpenseCategory {
-    Groceries,
-    Leisure,
-    Electronics,
-    Utilities,
-    Clothing,
-    Health,
-    Others,
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Category {
+    pub id: i32,
+    pub name: String,
+    pub color: String,
+    pub icon: Option<String>,
+    pub is_default: bool,
+}
+
+#[derive(Serialize)]
+pub struct CategoryForm {
+    pub name: String,
+    pub color: String,
+    pub icon: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Expense {
     pub id: i32,
-    pub category: ExpenseCategory,
+    pub category_id: i32,
     pub amount: BigDecimal,
+    pub currency: String,
+    /// Amount in the user's base currency, when a rate is known.
+    pub amount_in_base: Option<BigDecimal>,
     pub description: Option<String>,
     pub expense_date: NaiveDateTime,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Sent back in `If-Match` so concurrent edits are not overwritten.
+    #[serde(default)]
+    pub version: i32,
+    /// Escaped description with search matches in `<mark>`, when searching.
+    #[serde(default, skip_serializing)]
+    pub highlight: Option<String>,
 }
 
 impl PartialEq for Expense {
     fn eq(&self, other: &Self) -> bool {
         self.id == other.id
-            && self.category == other.category
+            && self.category_id == other.category_id
             && self.amount.to_string() == other.amount.to_string()
+            && self.currency == other.currency
             && self.description == other.description
             && self.expense_date == other.expense_date
+            && self.tags == other.tags
+            && self.version == other.version
+            && self.highlight == other.highlight
     }
 }
 
+/// Body of `PATCH /expenses/{id}`; `None` description clears it.
+#[derive(Serialize)]
+pub struct ExpenseChanges {
+    pub category_id: i32,
+    pub amount: BigDecimal,
+    pub description: Option<String>,
+    /// Only sent when the date was changed, to keep the time of day.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expense_date: Option<NaiveDate>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Currency {
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExpensePage {
+    pub items: Vec<Expense>,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Income {
+    pub id: i32,
+    pub source: String,
+    pub amount: BigDecimal,
+    pub currency: String,
+    pub income_date: NaiveDate,
+    pub recurring: bool,
+}
+
+#[derive(Serialize)]
+pub struct IncomeForm {
+    pub source: String,
+    pub amount: String,
+    pub income_date: Option<NaiveDate>,
+    pub recurring: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CashFlowMonth {
+    pub month: NaiveDate,
+    pub income: BigDecimal,
+    pub expenses: BigDecimal,
+    pub net: BigDecimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CashFlowReport {
+    pub currency: String,
+    pub months: Vec<CashFlowMonth>,
+}
+
+/// Totals over a date range, in the user's base currency.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ExpenseSummary {
+    pub currency: String,
+    pub total: BigDecimal,
+    pub count: i64,
+    pub average: Option<BigDecimal>,
+    pub unconverted: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CategoryTotal {
+    pub category_id: i32,
+    pub category_name: String,
+    pub total: BigDecimal,
+    pub count: i64,
+    pub share: BigDecimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PeriodTotal {
+    pub period_start: NaiveDate,
+    pub total: BigDecimal,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SummaryBreakdown<T> {
+    pub currency: String,
+    pub items: Vec<T>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CategoryForecast {
+    pub category_id: i32,
+    pub category_name: String,
+    pub actual: BigDecimal,
+    pub projected: BigDecimal,
+    pub low: BigDecimal,
+    pub high: BigDecimal,
+}
+
+/// Month-end projection of the current month, in the user's base currency.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SpendingForecast {
+    pub currency: String,
+    pub days_elapsed: u32,
+    pub days_in_month: u32,
+    pub actual: BigDecimal,
+    pub projected: BigDecimal,
+    pub low: BigDecimal,
+    pub high: BigDecimal,
+    pub categories: Vec<CategoryForecast>,
+}
+
+/// Raised when the amount of a new expense stands out from its category.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ExpenseAnomaly {
+    pub id: i32,
+    pub score: BigDecimal,
+    /// Usual amount of the category, in the user's base currency.
+    pub median: BigDecimal,
+}
+
+/// Response to a new expense; only the anomaly is read.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CreatedExpense {
+    pub anomaly: Option<ExpenseAnomaly>,
+}
+
 #[derive(Serialize)]
 pub struct ChangePasswordForm {
     pub current_password: String,